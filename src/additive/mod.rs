@@ -0,0 +1,240 @@
+#[cfg(test)]
+mod tests;
+
+use alloc::{vec, vec::Vec};
+use core::f64::consts::PI;
+
+use crate::{Float, Oscillator, Waveform, advance_phase, next_wrap, wrap};
+
+/// One sine partial of an [`AdditiveOsc`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Partial<T: Float = f32> {
+    /// Frequency as a multiple of the oscillator's frequency. Whole numbers
+    /// give harmonics, anything else inharmonic partials.
+    pub ratio: T,
+    /// Peak amplitude of the partial.
+    pub amplitude: T,
+    /// Phase offset in cycles, added to the partial's own phase.
+    pub phase: T,
+}
+
+impl<T: Float> Partial<T> {
+    /// Creates a partial at `ratio` times the oscillator's frequency, with
+    /// no phase offset.
+    pub fn new(ratio: T, amplitude: T) -> Self {
+        Self {
+            ratio,
+            amplitude,
+            phase: T::ZERO,
+        }
+    }
+
+    /// Returns the partial with its phase offset set to `phase` cycles.
+    pub fn with_phase(self, phase: T) -> Self {
+        Self { phase, ..self }
+    }
+}
+
+/// An additive oscillator summing sine partials.
+///
+/// Every partial has its own frequency ratio, amplitude and phase offset,
+/// which can all be changed while playing. Partials at or above Nyquist are
+/// muted whenever the frequency changes, so any series stays free of
+/// aliasing, at the cost of one sine per audible partial every sample.
+///
+/// Each partial keeps its own phase, so inharmonic ratios ring on freely
+/// instead of restarting with the fundamental's cycle.
+///
+/// # Example
+///
+/// ```
+/// use oscy::{additive::{AdditiveOsc, Partial}, Oscillator, Waveform};
+///
+/// // a band-limited saw from its first 32 harmonics
+/// let mut saw: AdditiveOsc = AdditiveOsc::from_waveform(44100.0, 110.0, Waveform::Saw, 32);
+/// assert!(saw.next_sample().abs() < 1.2);
+///
+/// // a bell from inharmonic partials
+/// let bell = [(0.56, 1.0), (0.92, 0.7), (1.19, 0.6), (1.71, 0.4), (2.74, 0.3)];
+/// let mut bell = AdditiveOsc::new(
+///     44100.0,
+///     440.0,
+///     bell.map(|(ratio, amplitude)| Partial::new(ratio, amplitude)),
+/// );
+/// bell.set_amplitude(0, 0.8);
+/// ```
+pub struct AdditiveOsc<T: Float = f32> {
+    phase: T,
+    phase_increment: T,
+    sample_rate: T,
+    partials: Vec<Partial<T>>,
+    // running phase of every partial, without its offset
+    phases: Vec<T>,
+    // whether every partial sits below Nyquist
+    audible: Vec<bool>,
+}
+
+impl<T: Float> AdditiveOsc<T> {
+    /// Creates a new additive oscillator from `partials`.
+    pub fn new(
+        sample_rate: T,
+        frequency: T,
+        partials: impl IntoIterator<Item = Partial<T>>,
+    ) -> Self {
+        let partials: Vec<Partial<T>> = partials.into_iter().collect();
+        let mut osc = Self {
+            phase: T::ZERO,
+            phase_increment: frequency / sample_rate,
+            sample_rate,
+            phases: vec![T::ZERO; partials.len()],
+            audible: vec![true; partials.len()],
+            partials,
+        };
+        osc.update_audible();
+        osc
+    }
+
+    /// Creates an oscillator summing the first `harmonics` harmonics of one
+    /// of the standard waveforms.
+    ///
+    /// The partials follow the waveform's Fourier series and line up with
+    /// the phase of the other oscillators. [`Waveform::Pulse`] uses a pulse
+    /// width of 0.5 and [`Waveform::Morph`] a shape of 0.0, matching their
+    /// defaults. Harmonics missing from the waveform, such as the even ones
+    /// of a square, are kept with zero amplitude so they can be brought in
+    /// with [`AdditiveOsc::set_amplitude`].
+    pub fn from_waveform(
+        sample_rate: T,
+        frequency: T,
+        waveform: Waveform,
+        harmonics: usize,
+    ) -> Self {
+        let partials = (1..=harmonics).map(|k| {
+            let n = k as f64;
+            let odd = k % 2 == 1;
+            let (amplitude, phase) = match waveform {
+                Waveform::Sine | Waveform::Morph => (if k == 1 { 1.0 } else { 0.0 }, 0.0),
+                // 2·phase - 1, a sine series turned upside down
+                Waveform::Saw => (2.0 / (PI * n), 0.5),
+                Waveform::Square | Waveform::Pulse => (if odd { 4.0 / (PI * n) } else { 0.0 }, 0.0),
+                // -1 at phase 0, a cosine series turned upside down
+                Waveform::Triangle => (if odd { 8.0 / (PI * PI * n * n) } else { 0.0 }, 0.75),
+            };
+            Partial::new(T::from_usize(k), T::from_f64(amplitude)).with_phase(T::from_f64(phase))
+        });
+
+        Self::new(sample_rate, frequency, partials)
+    }
+
+    /// Returns the partials.
+    pub fn partials(&self) -> &[Partial<T>] {
+        &self.partials
+    }
+
+    /// Sets the amplitude of the partial at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of range.
+    pub fn set_amplitude(&mut self, index: usize, amplitude: T) {
+        self.partials[index].amplitude = amplitude;
+    }
+
+    /// Sets the frequency ratio of the partial at `index`, muting it if
+    /// that takes it past Nyquist. Its phase carries on from where it is.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of range.
+    pub fn set_ratio(&mut self, index: usize, ratio: T) {
+        self.partials[index].ratio = ratio;
+        self.update_audible();
+    }
+
+    /// Sets the phase offset of the partial at `index`, in cycles.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of range.
+    pub fn set_partial_phase(&mut self, index: usize, phase: T) {
+        self.partials[index].phase = phase;
+    }
+
+    /// Mutes the partials at or above Nyquist at the current frequency.
+    fn update_audible(&mut self) {
+        let increment = self.phase_increment.abs();
+        for (audible, partial) in self.audible.iter_mut().zip(&self.partials) {
+            *audible = (partial.ratio * increment).abs() < T::HALF;
+        }
+    }
+
+    /// Moves the phase of the fundamental and every partial one step forward.
+    fn advance(&mut self) {
+        self.phase = advance_phase(self.phase, self.phase_increment);
+        let partials = self.partials.iter().zip(&self.audible);
+        for (phase, (partial, &audible)) in self.phases.iter_mut().zip(partials) {
+            let increment = partial.ratio * self.phase_increment;
+            // muted partials may move more than a cycle per step
+            *phase = if audible {
+                advance_phase(*phase, increment)
+            } else {
+                wrap(*phase + increment)
+            };
+        }
+    }
+
+    /// Sums the audible partials at their current phases.
+    fn render(&self) -> T {
+        self.partials
+            .iter()
+            .zip(&self.phases)
+            .zip(&self.audible)
+            .filter(|&((partial, _), &audible)| audible && partial.amplitude != T::ZERO)
+            .map(|((partial, &phase), _)| {
+                partial.amplitude * ((phase + partial.phase) * T::TAU).sin()
+            })
+            .sum()
+    }
+}
+
+impl<T: Float> Oscillator<T> for AdditiveOsc<T> {
+    fn set_frequency(&mut self, hz: T) {
+        self.phase_increment = hz / self.sample_rate;
+        self.update_audible();
+    }
+
+    /// Sets the phase of the fundamental. Every partial moves to the same
+    /// point of its own cycle, `ratio` times the phase.
+    fn set_phase(&mut self, phase: T) {
+        self.phase = wrap(phase);
+        for (partial_phase, partial) in self.phases.iter_mut().zip(&self.partials) {
+            *partial_phase = wrap(partial.ratio * self.phase);
+        }
+    }
+
+    fn phase(&self) -> T {
+        self.phase
+    }
+
+    fn reset(&mut self) {
+        self.phase = T::ZERO;
+        self.phases.fill(T::ZERO);
+    }
+
+    fn next_wrap(&self) -> Option<T> {
+        next_wrap(self.phase, self.phase_increment)
+    }
+
+    fn next_sample(&mut self) -> T {
+        self.advance();
+        self.render()
+    }
+}
+
+impl<T: Float> Iterator for AdditiveOsc<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        Some(self.next_sample())
+    }
+}
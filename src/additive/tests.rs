@@ -0,0 +1,218 @@
+use std::f32::consts::TAU;
+
+use super::{AdditiveOsc, Partial};
+use crate::{
+    Oscillator, Waveform,
+    naive::NaiveOsc,
+    test_utils::{ANALYSIS_LEN, aliasing_ratio, analysis_frequency},
+};
+
+const EPSILON: f32 = 1e-4;
+
+fn approx_eq(a: f32, b: f32) -> bool {
+    (a - b).abs() < EPSILON
+}
+
+fn sine(ratio: f32) -> AdditiveOsc {
+    AdditiveOsc::new(44100.0, 100.0, [Partial::new(ratio, 1.0)])
+}
+
+// partial tests
+
+#[test]
+fn test_single_partial_matches_naive_sine() {
+    let mut osc = AdditiveOsc::from_waveform(44100.0, 441.0, Waveform::Sine, 8);
+    let mut naive = NaiveOsc::new(44100.0, 441.0, Waveform::Sine);
+
+    for _ in 0..1000 {
+        assert!(approx_eq(osc.next_sample(), naive.next_sample()));
+    }
+}
+
+#[test]
+fn test_harmonic_series_follow_naive() {
+    // 1000 samples per cycle, with every harmonic up to 200
+    for waveform in [Waveform::Saw, Waveform::Square, Waveform::Triangle] {
+        let mut osc = AdditiveOsc::from_waveform(44100.0, 44.1, waveform, 200);
+        let mut naive: NaiveOsc = NaiveOsc::new(44100.0, 44.1, waveform);
+
+        for i in 0..1000i32 {
+            let (sample, expected) = (osc.next_sample(), naive.next_sample());
+            // away from the edges and their ringing
+            if [50, 450, 550, 950]
+                .iter()
+                .all(|&edge| (i - edge).abs() > 60)
+            {
+                assert!((sample - expected).abs() < 0.02, "{waveform:?} at {i}");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_inharmonic_ratio() {
+    let mut osc = sine(2.76);
+    let mut reference = NaiveOsc::new(44100.0, 276.0, Waveform::Sine);
+
+    for _ in 0..1000 {
+        assert!((osc.next_sample() - reference.next_sample()).abs() < 1e-3);
+    }
+}
+
+#[test]
+fn test_partial_phase_offset() {
+    let mut osc = sine(1.0);
+    osc.set_partial_phase(0, 0.25);
+    osc.set_phase(-1.0 / 441.0);
+
+    // a quarter cycle ahead, the sine starts at its peak
+    assert!(approx_eq(osc.next_sample(), 1.0));
+}
+
+#[test]
+fn test_set_amplitude_and_ratio() {
+    let mut osc = AdditiveOsc::new(
+        44100.0,
+        100.0,
+        [Partial::new(1.0, 1.0), Partial::new(2.0, 0.0)],
+    );
+    osc.set_amplitude(0, 0.0);
+    osc.set_amplitude(1, 0.5);
+    osc.set_ratio(1, 3.0);
+    assert_eq!(osc.partials()[1], Partial::new(3.0, 0.5));
+
+    let mut reference: NaiveOsc = NaiveOsc::new(44100.0, 300.0, Waveform::Sine);
+    for _ in 0..1000 {
+        assert!((osc.next_sample() - 0.5 * reference.next_sample()).abs() < 1e-3);
+    }
+}
+
+#[test]
+fn test_partials_above_nyquist_are_muted() {
+    let mut osc: AdditiveOsc = AdditiveOsc::new(44100.0, 1000.0, [Partial::new(30.0, 1.0)]);
+    for _ in 0..100 {
+        assert_eq!(osc.next_sample(), 0.0);
+    }
+
+    // back below Nyquist
+    osc.set_frequency(500.0);
+    assert!(osc.by_ref().take(100).any(|sample| sample.abs() > 0.5));
+
+    osc.set_ratio(0, 50.0);
+    for _ in 0..100 {
+        assert_eq!(osc.next_sample(), 0.0);
+    }
+}
+
+#[test]
+fn test_full_series_does_not_alias() {
+    let cycles = 233;
+    let frequency = analysis_frequency(44100.0, cycles);
+
+    let osc = AdditiveOsc::from_waveform(44100.0, frequency, Waveform::Saw, 100);
+    let samples: Vec<f32> = osc.take(ANALYSIS_LEN).collect();
+    assert!(aliasing_ratio(&samples, cycles) < 1e-4);
+}
+
+#[test]
+fn test_muted_partials_stay_in_phase() {
+    // a harmonic muted during a sweep comes back lined up with the fundamental
+    let mut swept = AdditiveOsc::from_waveform(44100.0, 441.0, Waveform::Saw, 64);
+    swept.set_frequency(4410.0);
+    swept.by_ref().take(123).for_each(drop);
+    swept.set_frequency(441.0);
+
+    let mut reference: AdditiveOsc = AdditiveOsc::from_waveform(44100.0, 441.0, Waveform::Saw, 64);
+    reference.set_phase(swept.phase());
+
+    for _ in 0..100 {
+        assert!((swept.next_sample() - reference.next_sample()).abs() < 1e-3);
+    }
+}
+
+#[test]
+fn test_negative_frequency_runs_backwards() {
+    let mut forwards = AdditiveOsc::from_waveform(44100.0, 441.0, Waveform::Saw, 16);
+    let mut backwards: AdditiveOsc = AdditiveOsc::from_waveform(44100.0, -441.0, Waveform::Saw, 16);
+    forwards.set_phase(0.5);
+    backwards.set_phase(0.5);
+
+    // the saw is odd around the middle of the cycle
+    for _ in 0..100 {
+        assert!((forwards.next_sample() + backwards.next_sample()).abs() < 1e-3);
+    }
+}
+
+#[test]
+fn test_tiny_negative_phase_stays_below_one() {
+    // a step from 1e-9 back by 2e-9 lands at -1e-9, which rounds to 1.0
+    let mut osc: AdditiveOsc = AdditiveOsc::from_waveform(1.0, -2e-9, Waveform::Saw, 4);
+    osc.set_phase(1e-9);
+    osc.next_sample();
+    assert!(osc.phase() < 1.0);
+
+    osc.set_phase(-1e-9);
+    assert!(osc.phase() < 1.0);
+}
+
+// sample type tests
+
+#[test]
+fn test_f64_matches_f32() {
+    let mut single = AdditiveOsc::<f32>::from_waveform(44100.0, 441.0, Waveform::Saw, 16);
+    let mut double = AdditiveOsc::<f64>::from_waveform(44100.0, 441.0, Waveform::Saw, 16);
+
+    for _ in 0..1000 {
+        assert!((single.next_sample() as f64 - double.next_sample()).abs() < 1e-3);
+    }
+}
+
+// trait impl tests
+
+#[test]
+fn test_set_phase() {
+    let mut osc = sine(1.5);
+    osc.set_phase(-0.25);
+    assert!(approx_eq(osc.phase(), 0.75));
+
+    // the partial sits at 1.5 times the phase
+    osc.set_frequency(0.0);
+    assert!(approx_eq(osc.next_sample(), (1.125 * TAU).sin()));
+}
+
+#[test]
+fn test_reset() {
+    let mut osc = sine(2.0);
+    osc.next_sample();
+    osc.reset();
+    assert!(approx_eq(osc.phase(), 0.0));
+}
+
+#[test]
+fn test_next_wrap_reports_fraction() {
+    let mut osc = AdditiveOsc::new(10.0, 3.0, [Partial::new(1.0, 1.0)]); // inc = 0.3
+    osc.set_phase(0.9);
+
+    let fraction: f32 = osc.next_wrap().unwrap();
+    assert!((fraction - 1.0 / 3.0).abs() < 1e-5);
+}
+
+#[test]
+fn test_fill_buffer() {
+    let mut filled = AdditiveOsc::from_waveform(44100.0, 440.0, Waveform::Square, 16);
+    let mut reference = AdditiveOsc::from_waveform(44100.0, 440.0, Waveform::Square, 16);
+    let mut buffer = [0.0f32; 64];
+
+    filled.fill(&mut buffer);
+
+    for sample in buffer {
+        assert!(approx_eq(sample, reference.next_sample()));
+    }
+}
+
+#[test]
+fn test_iterator() {
+    let osc = sine(1.0);
+    let samples: Vec<f32> = osc.take(64).collect();
+    assert_eq!(samples.len(), 64);
+}
@@ -0,0 +1,267 @@
+//! Minimal `f32` vectors for the oscillator bank.
+//!
+//! x86 targets with SSE2 or AVX enabled at compile time use the intrinsics
+//! directly, 4 or 8 lanes wide. Everything else falls back to arrays of 4
+//! processed lane by lane, which the compiler usually vectorizes itself.
+
+use core::ops::{Add, BitOr, Mul, Sub};
+
+pub(crate) use imp::{F32s, LANES, Mask};
+
+#[cfg(all(
+    any(target_arch = "x86", target_arch = "x86_64"),
+    target_feature = "avx"
+))]
+mod imp {
+    #[cfg(target_arch = "x86")]
+    use core::arch::x86::*;
+    #[cfg(target_arch = "x86_64")]
+    use core::arch::x86_64::*;
+
+    // SAFETY: the intrinsics below are only unsafe because they need their
+    // target feature, which the module's cfg guarantees
+
+    pub(crate) const LANES: usize = 8;
+
+    #[derive(Clone, Copy)]
+    pub(crate) struct F32s(pub(super) __m256);
+
+    #[derive(Clone, Copy)]
+    pub(crate) struct Mask(pub(super) __m256);
+
+    impl F32s {
+        pub(crate) fn splat(x: f32) -> Self {
+            Self(unsafe { _mm256_set1_ps(x) })
+        }
+
+        pub(crate) fn load(src: &[f32; LANES]) -> Self {
+            // SAFETY: the array holds exactly one vector, and the load
+            // doesn't need alignment
+            Self(unsafe { _mm256_loadu_ps(src.as_ptr()) })
+        }
+
+        pub(crate) fn store(self, dst: &mut [f32; LANES]) {
+            // SAFETY: as for `load`
+            unsafe { _mm256_storeu_ps(dst.as_mut_ptr(), self.0) }
+        }
+
+        pub(crate) fn abs(self) -> Self {
+            // clear the sign bit
+            Self(unsafe { _mm256_andnot_ps(_mm256_set1_ps(-0.0), self.0) })
+        }
+
+        pub(crate) fn lt(self, other: Self) -> Mask {
+            Mask(unsafe { _mm256_cmp_ps::<_CMP_LT_OQ>(self.0, other.0) })
+        }
+
+        pub(crate) fn gt(self, other: Self) -> Mask {
+            Mask(unsafe { _mm256_cmp_ps::<_CMP_GT_OQ>(self.0, other.0) })
+        }
+
+        pub(crate) fn ge(self, other: Self) -> Mask {
+            Mask(unsafe { _mm256_cmp_ps::<_CMP_GE_OQ>(self.0, other.0) })
+        }
+
+        pub(super) fn add(self, other: Self) -> Self {
+            Self(unsafe { _mm256_add_ps(self.0, other.0) })
+        }
+
+        pub(super) fn sub(self, other: Self) -> Self {
+            Self(unsafe { _mm256_sub_ps(self.0, other.0) })
+        }
+
+        pub(super) fn mul(self, other: Self) -> Self {
+            Self(unsafe { _mm256_mul_ps(self.0, other.0) })
+        }
+    }
+
+    impl Mask {
+        pub(crate) fn select(self, a: F32s, b: F32s) -> F32s {
+            F32s(unsafe { _mm256_blendv_ps(b.0, a.0, self.0) })
+        }
+
+        pub(super) fn or(self, other: Self) -> Self {
+            Self(unsafe { _mm256_or_ps(self.0, other.0) })
+        }
+    }
+}
+
+#[cfg(all(
+    any(target_arch = "x86", target_arch = "x86_64"),
+    target_feature = "sse2",
+    not(target_feature = "avx")
+))]
+mod imp {
+    #[cfg(target_arch = "x86")]
+    use core::arch::x86::*;
+    #[cfg(target_arch = "x86_64")]
+    use core::arch::x86_64::*;
+
+    // SAFETY: the intrinsics below are only unsafe because they need their
+    // target feature, which the module's cfg guarantees
+
+    pub(crate) const LANES: usize = 4;
+
+    #[derive(Clone, Copy)]
+    pub(crate) struct F32s(pub(super) __m128);
+
+    #[derive(Clone, Copy)]
+    pub(crate) struct Mask(pub(super) __m128);
+
+    impl F32s {
+        pub(crate) fn splat(x: f32) -> Self {
+            Self(unsafe { _mm_set1_ps(x) })
+        }
+
+        pub(crate) fn load(src: &[f32; LANES]) -> Self {
+            // SAFETY: the array holds exactly one vector, and the load
+            // doesn't need alignment
+            Self(unsafe { _mm_loadu_ps(src.as_ptr()) })
+        }
+
+        pub(crate) fn store(self, dst: &mut [f32; LANES]) {
+            // SAFETY: as for `load`
+            unsafe { _mm_storeu_ps(dst.as_mut_ptr(), self.0) }
+        }
+
+        pub(crate) fn abs(self) -> Self {
+            // clear the sign bit
+            Self(unsafe { _mm_andnot_ps(_mm_set1_ps(-0.0), self.0) })
+        }
+
+        pub(crate) fn lt(self, other: Self) -> Mask {
+            Mask(unsafe { _mm_cmplt_ps(self.0, other.0) })
+        }
+
+        pub(crate) fn gt(self, other: Self) -> Mask {
+            Mask(unsafe { _mm_cmpgt_ps(self.0, other.0) })
+        }
+
+        pub(crate) fn ge(self, other: Self) -> Mask {
+            Mask(unsafe { _mm_cmpge_ps(self.0, other.0) })
+        }
+
+        pub(super) fn add(self, other: Self) -> Self {
+            Self(unsafe { _mm_add_ps(self.0, other.0) })
+        }
+
+        pub(super) fn sub(self, other: Self) -> Self {
+            Self(unsafe { _mm_sub_ps(self.0, other.0) })
+        }
+
+        pub(super) fn mul(self, other: Self) -> Self {
+            Self(unsafe { _mm_mul_ps(self.0, other.0) })
+        }
+    }
+
+    impl Mask {
+        pub(crate) fn select(self, a: F32s, b: F32s) -> F32s {
+            // SSE2 has no blend, so combine both sides through the mask
+            F32s(unsafe { _mm_or_ps(_mm_and_ps(self.0, a.0), _mm_andnot_ps(self.0, b.0)) })
+        }
+
+        pub(super) fn or(self, other: Self) -> Self {
+            Self(unsafe { _mm_or_ps(self.0, other.0) })
+        }
+    }
+}
+
+#[cfg(not(all(
+    any(target_arch = "x86", target_arch = "x86_64"),
+    target_feature = "sse2"
+)))]
+mod imp {
+    use core::array;
+
+    pub(crate) const LANES: usize = 4;
+
+    #[derive(Clone, Copy)]
+    pub(crate) struct F32s(pub(super) [f32; LANES]);
+
+    #[derive(Clone, Copy)]
+    pub(crate) struct Mask(pub(super) [bool; LANES]);
+
+    impl F32s {
+        pub(crate) fn splat(x: f32) -> Self {
+            Self([x; LANES])
+        }
+
+        pub(crate) fn load(src: &[f32; LANES]) -> Self {
+            Self(*src)
+        }
+
+        pub(crate) fn store(self, dst: &mut [f32; LANES]) {
+            *dst = self.0;
+        }
+
+        pub(crate) fn abs(self) -> Self {
+            Self(self.0.map(|x| x.abs()))
+        }
+
+        pub(crate) fn lt(self, other: Self) -> Mask {
+            Mask(array::from_fn(|i| self.0[i] < other.0[i]))
+        }
+
+        pub(crate) fn gt(self, other: Self) -> Mask {
+            Mask(array::from_fn(|i| self.0[i] > other.0[i]))
+        }
+
+        pub(crate) fn ge(self, other: Self) -> Mask {
+            Mask(array::from_fn(|i| self.0[i] >= other.0[i]))
+        }
+
+        pub(super) fn add(self, other: Self) -> Self {
+            Self(array::from_fn(|i| self.0[i] + other.0[i]))
+        }
+
+        pub(super) fn sub(self, other: Self) -> Self {
+            Self(array::from_fn(|i| self.0[i] - other.0[i]))
+        }
+
+        pub(super) fn mul(self, other: Self) -> Self {
+            Self(array::from_fn(|i| self.0[i] * other.0[i]))
+        }
+    }
+
+    impl Mask {
+        pub(crate) fn select(self, a: F32s, b: F32s) -> F32s {
+            F32s(array::from_fn(|i| if self.0[i] { a.0[i] } else { b.0[i] }))
+        }
+
+        pub(super) fn or(self, other: Self) -> Self {
+            Self(array::from_fn(|i| self.0[i] | other.0[i]))
+        }
+    }
+}
+
+impl Add for F32s {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        F32s::add(self, other)
+    }
+}
+
+impl Sub for F32s {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        F32s::sub(self, other)
+    }
+}
+
+impl Mul for F32s {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        F32s::mul(self, other)
+    }
+}
+
+impl BitOr for Mask {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        self.or(other)
+    }
+}
@@ -0,0 +1,202 @@
+use super::OscBank;
+use crate::{Oscillator, Waveform, poly_blep::PolyBlepOsc, sine::SineMode};
+
+const EPSILON: f32 = 1e-6;
+
+fn approx_eq(a: f32, b: f32) -> bool {
+    (a - b).abs() < EPSILON
+}
+
+const WAVEFORMS: [Waveform; 6] = [
+    Waveform::Sine,
+    Waveform::Saw,
+    Waveform::Square,
+    Waveform::Pulse,
+    Waveform::Triangle,
+    Waveform::Morph,
+];
+
+// an odd count, so the last group of lanes is only partly used
+const FREQUENCIES: [f32; 11] = [
+    55.0, 110.0, 220.0, 440.0, 880.0, 1760.0, 3520.0, 7040.0, 13_000.0, -440.0, -5000.0,
+];
+
+/// Builds a bank and matching scalar oscillators with a different pulse
+/// width and shape for every voice.
+fn bank_and_oscillators(waveform: Waveform) -> (OscBank, Vec<PolyBlepOsc>) {
+    let mut bank = OscBank::new(44100.0, waveform, FREQUENCIES.len());
+    let mut oscillators = Vec::new();
+
+    for (voice, &hz) in FREQUENCIES.iter().enumerate() {
+        let amount = voice as f32 / FREQUENCIES.len() as f32;
+        let mut osc = PolyBlepOsc::new(44100.0, hz, waveform);
+        osc.set_pulse_width(amount);
+        osc.set_shape(amount);
+        bank.set_frequency(voice, hz);
+        bank.set_pulse_width(voice, amount);
+        bank.set_shape(voice, amount);
+        oscillators.push(osc);
+    }
+
+    (bank, oscillators)
+}
+
+// scalar comparison tests
+
+#[test]
+fn test_voices_match_poly_blep() {
+    for waveform in WAVEFORMS {
+        let (mut bank, mut oscillators) = bank_and_oscillators(waveform);
+
+        for _ in 0..2000 {
+            let voices = bank.next_voices();
+            for (sample, osc) in voices.iter().zip(&mut oscillators) {
+                let expected = osc.next_sample();
+                assert!(
+                    (sample - expected).abs() < 1e-4,
+                    "{waveform:?}: {sample} vs {expected}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_phases_match_poly_blep() {
+    let (mut bank, mut oscillators) = bank_and_oscillators(Waveform::Saw);
+
+    for _ in 0..2000 {
+        bank.next_sample();
+        for (voice, osc) in oscillators.iter_mut().enumerate() {
+            osc.next_sample();
+            assert_eq!(bank.phase(voice), osc.phase());
+        }
+    }
+}
+
+#[test]
+fn test_sine_polynomial_error() {
+    let mut bank = OscBank::new(44100.0, Waveform::Sine, 1);
+    bank.set_frequency(0, 1.0);
+
+    let mut max_error: f32 = 0.0;
+    for _ in 0..44100 {
+        let sample = bank.next_sample();
+        let expected = (bank.phase(0) * std::f32::consts::TAU).sin();
+        max_error = max_error.max((sample - expected).abs());
+    }
+    assert!(max_error < 2e-6, "max error {max_error}");
+}
+
+#[test]
+fn test_sine_matches_polynomial_mode() {
+    let mut bank = OscBank::new(44100.0, Waveform::Sine, 1);
+    bank.set_frequency(0, 440.0);
+
+    for _ in 0..1000 {
+        let sample = bank.next_sample();
+        assert!(approx_eq(sample, SineMode::Polynomial.sine(bank.phase(0))));
+    }
+}
+
+// mix tests
+
+#[test]
+fn test_mix_applies_gains() {
+    let (mut bank, _) = bank_and_oscillators(Waveform::Saw);
+    for voice in 0..bank.voice_count() {
+        bank.set_gain(voice, voice as f32 * 0.1);
+    }
+
+    for _ in 0..100 {
+        let mix = bank.next_sample();
+        let voices = bank.output.as_flattened();
+        let expected: f32 = (0..FREQUENCIES.len())
+            .map(|voice| voices[voice] * voice as f32 * 0.1)
+            .sum();
+        assert!((mix - expected).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn test_padding_lanes_are_silent() {
+    // a single voice at full scale, the other lanes must add nothing
+    let mut bank = OscBank::new(4.0, Waveform::Square, 1);
+    bank.set_frequency(0, 0.0);
+
+    assert!(approx_eq(bank.next_sample(), 1.0));
+    assert_eq!(bank.next_voices().len(), 1);
+}
+
+#[test]
+fn test_set_waveform() {
+    let (mut bank, _) = bank_and_oscillators(Waveform::Saw);
+    bank.set_waveform(Waveform::Triangle);
+    let (mut triangle, _) = bank_and_oscillators(Waveform::Triangle);
+
+    for _ in 0..100 {
+        assert_eq!(bank.next_sample(), triangle.next_sample());
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_voice_out_of_range_panics() {
+    let mut bank = OscBank::new(44100.0, Waveform::Saw, 3);
+    bank.set_frequency(3, 440.0);
+}
+
+// trait impl tests
+
+#[test]
+fn test_set_phase() {
+    let mut bank = OscBank::new(4.0, Waveform::Saw, 2);
+    bank.set_frequency(1, 1.0);
+    bank.set_phase(1, 1.25);
+    assert!(approx_eq(bank.phase(1), 0.25));
+
+    // phase 0.5, away from the reset
+    assert!(approx_eq(bank.next_voices()[1], 0.0));
+}
+
+#[test]
+fn test_set_phase_tiny_negative_stays_below_one() {
+    // -1e-9 wraps to 1 - 1e-9, which rounds to 1.0
+    let mut bank = OscBank::new(4.0, Waveform::Saw, 2);
+    bank.set_phase(1, -1e-9);
+    assert!(bank.phase(1) < 1.0);
+}
+
+#[test]
+fn test_reset_zeros_phase() {
+    let (mut bank, _) = bank_and_oscillators(Waveform::Saw);
+    bank.next_sample();
+    bank.reset();
+
+    for voice in 0..bank.voice_count() {
+        assert_eq!(bank.phase(voice), 0.0);
+    }
+}
+
+#[test]
+fn test_fill_buffer() {
+    let (mut bank, _) = bank_and_oscillators(Waveform::Pulse);
+    let (mut single, _) = bank_and_oscillators(Waveform::Pulse);
+    let mut buffer = [0.0f32; 64];
+
+    bank.fill(&mut buffer);
+
+    for &sample in &buffer {
+        assert_eq!(sample, single.next_sample());
+    }
+}
+
+#[test]
+fn test_iterator() {
+    let (bank, _) = bank_and_oscillators(Waveform::Morph);
+    let (mut single, _) = bank_and_oscillators(Waveform::Morph);
+
+    for sample in bank.take(64) {
+        assert_eq!(sample, single.next_sample());
+    }
+}
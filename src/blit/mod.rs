@@ -0,0 +1,472 @@
+#[cfg(test)]
+mod tests;
+
+use core::f64::consts::TAU;
+
+use crate::{
+    Float, Oscillator, Waveform, advance_phase, morph_weights,
+    naive::{naive_pulse, naive_saw, naive_sine, naive_triangle},
+    next_wrap, wrap,
+};
+
+/// Leak of the integrators per cycle, which keeps their DC error from
+/// building up.
+const LEAK_PER_CYCLE: f64 = 0.1;
+/// Upper bound on the harmonics below Nyquist, reached as the frequency nears
+/// zero.
+const MAX_HARMONICS: f64 = 1024.0;
+
+/// A bandlimited oscillator built on a bandlimited impulse train (BLIT).
+///
+/// Each cycle's discontinuities are drawn as impulses from the closed-form
+/// sum of every harmonic below Nyquist, and the waveforms are recovered by
+/// leaky integration: saw from a unipolar train with its DC removed, square
+/// and pulse from a bipolar train, and triangle by integrating the square
+/// again. As the impulses hold no energy above Nyquist, the waveforms alias
+/// far less than polyBLEP, at the cost of evaluating the impulse train every
+/// sample. With every harmonic kept up to Nyquist, the edges ring, and
+/// samples next to an edge can overshoot ±1.0 by a few tenths. The
+/// integrators start from their steady state, but need a few cycles to
+/// settle after large frequency jumps.
+///
+/// BLIT suits steady pitches and gentle modulation such as vibrato or slow
+/// sweeps, including through zero. Under fast, deep FM the harmonics below
+/// Nyquist change faster than the impulses can settle, which leaves the
+/// integrators off by a drifting DC offset. The triangle integrates that
+/// offset a second time, so it drifts the most. For audio-rate FM prefer
+/// [`PolyBlepOsc`](crate::poly_blep::PolyBlepOsc) or
+/// `MinBlepOsc`.
+///
+/// The integrators leak a fixed amount per cycle rather than per sample, so
+/// the waveforms keep the same shape at every pitch and under FM, with each
+/// harmonic delayed by under a degree. For the impulse train itself, see
+/// [`ImpulseTrain`].
+///
+/// # Example
+///
+/// ```
+/// use oscy::{blit::BlitOsc, Oscillator, Waveform};
+///
+/// let mut osc = BlitOsc::new(44100.0, 440.0, Waveform::Saw);
+/// let sample: f32 = osc.next_sample();
+/// assert!(sample.abs() < 1.5);
+/// ```
+pub struct BlitOsc<T: Float = f32> {
+    phase: T,
+    phase_increment: T,
+    sample_rate: T,
+    waveform: Waveform,
+    pulse_width: T,
+    shape: T,
+    band: Band<T>,
+    // phase offset applied by the latest sample, for phase modulation
+    offset: T,
+    saw: T,
+    // DC-free square or pulse, depending on the waveform
+    square: T,
+    triangle: T,
+}
+
+impl<T: Float> BlitOsc<T> {
+    /// Creates a new BLIT oscillator.
+    pub fn new(sample_rate: T, frequency: T, waveform: Waveform) -> Self {
+        let phase_increment = frequency / sample_rate;
+        let mut osc = Self {
+            phase: T::ZERO,
+            phase_increment,
+            sample_rate,
+            waveform,
+            pulse_width: T::HALF,
+            shape: T::ZERO,
+            band: Band::new(phase_increment),
+            offset: T::ZERO,
+            saw: T::ZERO,
+            square: T::ZERO,
+            triangle: T::ZERO,
+        };
+        osc.settle_integrators();
+        osc
+    }
+
+    /// Sets the pulse width used by [`Waveform::Pulse`].
+    ///
+    /// The width is the fraction of the cycle spent high, clamped to
+    /// [0.0, 1.0]. Changing it shifts the integrated pulse by the change in
+    /// level instead of restarting the integrators, so it can be modulated
+    /// every sample.
+    pub fn set_pulse_width(&mut self, width: T) {
+        let width = width.clamp(T::ZERO, T::ONE);
+
+        if self.waveform == Waveform::Pulse {
+            // the integrator holds the pulse without its DC, and misses the
+            // falling edge if it moves across the current phase
+            let phase = (self.phase + self.offset).rem_euclid(T::ONE);
+            self.square += naive_pulse(phase, width)
+                - naive_pulse(phase, self.pulse_width)
+                - T::TWO * (width - self.pulse_width);
+        }
+
+        self.pulse_width = width;
+    }
+
+    /// Sets the morph shape used by [`Waveform::Morph`].
+    ///
+    /// The shape is clamped to [0.0, 1.0] and moves through sine (0.0),
+    /// triangle (1/3), saw (2/3) and square (1.0).
+    pub fn set_shape(&mut self, shape: T) {
+        self.shape = shape.clamp(T::ZERO, T::ONE);
+    }
+
+    fn square_width(&self) -> T {
+        match self.waveform {
+            Waveform::Pulse => self.pulse_width,
+            _ => T::HALF,
+        }
+    }
+
+    /// Sets the integrators to the values they settle to at the current
+    /// phase and frequency, so the output starts without a transient.
+    ///
+    /// Each harmonic of the impulse train passes through the leaky
+    /// integrator with gain `1 / (1 - leak·e^(-iω))`, so the steady state is
+    /// summed harmonic by harmonic.
+    fn settle_integrators(&mut self) {
+        self.offset = T::ZERO;
+
+        let inc = self.phase_increment.to_f64();
+        if inc == 0.0 {
+            let width = self.square_width();
+            self.saw = naive_saw(self.phase);
+            self.square = naive_pulse(self.phase, width) - (T::TWO * width - T::ONE);
+            self.triangle = naive_triangle(self.phase);
+            return;
+        }
+
+        let phase = self.phase.to_f64() - 0.5 * inc;
+        let width = self.square_width().to_f64();
+        let leak = leak(self.phase_increment).to_f64();
+        let top = self.band.top.to_usize();
+        let (mut saw, mut square, mut triangle) = (0.0, 0.0, 0.0);
+
+        // every harmonic but DC sits on both sides of the spectrum, hence
+        // twice the real part of each positive one
+        for k in 1..=top {
+            let level = if k == top {
+                self.band.top_level.to_f64()
+            } else {
+                1.0
+            };
+            let k = k as f64;
+            let (sin_w, cos_w) = (TAU * k * inc).sin_cos();
+            let gain = complex_div((level, 0.0), (1.0 - leak * cos_w, leak * sin_w));
+
+            let (sin_p, cos_p) = (TAU * k * phase).sin_cos();
+            let (sin_e, cos_e) = (TAU * k * width).sin_cos();
+
+            // saw input: -2·inc per harmonic of the unipolar train
+            let saw_k = complex_mul((-2.0 * inc * cos_p, -2.0 * inc * sin_p), gain);
+            // square input: impulses at phase 0 minus impulses at the width
+            let edges = complex_mul((cos_p, sin_p), (1.0 - cos_e, sin_e));
+            let square_k = complex_mul((2.0 * inc * edges.0, 2.0 * inc * edges.1), gain);
+            let triangle_k = complex_mul((4.0 * inc * square_k.0, 4.0 * inc * square_k.1), gain);
+
+            saw += 2.0 * saw_k.0;
+            square += 2.0 * square_k.0;
+            triangle += 2.0 * triangle_k.0;
+        }
+
+        self.saw = T::from_f64(saw);
+        self.square = T::from_f64(square);
+        self.triangle = T::from_f64(triangle);
+    }
+
+    /// Moves the phase one step forward and integrates the impulse train at
+    /// the phase shifted by `offset`.
+    fn step(&mut self, offset: T) -> T {
+        self.phase = advance_phase(self.phase, self.phase_increment);
+
+        // a changing offset moves the phase along with the accumulator
+        let increment = self.phase_increment + offset - self.offset;
+        self.offset = offset;
+        let phase = (self.phase + offset).rem_euclid(T::ONE);
+
+        // each sample integrates the step that ends on it, so reading the
+        // impulses half a step back centres the edges like the other
+        // oscillators do
+        let impulse_phase = (phase - T::HALF * increment).rem_euclid(T::ONE);
+        let leak = leak(increment);
+
+        match self.waveform {
+            Waveform::Sine => naive_sine(phase),
+            Waveform::Saw => self.integrate_saw(impulse_phase, increment, leak),
+            Waveform::Square | Waveform::Pulse => {
+                self.integrate_square(impulse_phase, increment, leak) + T::TWO * self.square_width()
+                    - T::ONE
+            }
+            Waveform::Triangle => {
+                self.integrate_square(impulse_phase, increment, leak);
+                self.integrate_triangle(increment, leak)
+            }
+            Waveform::Morph => {
+                let [sine, triangle, saw, square] = morph_weights(self.shape);
+
+                // all integrators keep running so the shape can move freely
+                let saw_out = self.integrate_saw(impulse_phase, increment, leak);
+                let square_out = self.integrate_square(impulse_phase, increment, leak);
+                let triangle_out = self.integrate_triangle(increment, leak);
+
+                sine * naive_sine(phase)
+                    + triangle * triangle_out
+                    + saw * saw_out
+                    + square * square_out
+            }
+        }
+    }
+
+    fn integrate_saw(&mut self, phase: T, increment: T, leak: T) -> T {
+        // rises by 2 over the cycle and drops by 2 at every impulse
+        let impulse = increment * self.band.impulse(phase);
+        self.saw = leak * self.saw + T::TWO * (increment - impulse);
+        self.saw
+    }
+
+    fn integrate_square(&mut self, phase: T, increment: T, leak: T) -> T {
+        // rises by 2 at phase 0 and falls by 2 at the pulse width
+        let falling = (phase + T::ONE - self.square_width()).fract();
+        let impulses = self.band.impulse(phase) - self.band.impulse(falling);
+        self.square = leak * self.square + T::TWO * increment * impulses;
+        self.square
+    }
+
+    fn integrate_triangle(&mut self, increment: T, leak: T) -> T {
+        // slope of ±4 per cycle, following the square
+        self.triangle = leak * self.triangle + T::from_f64(4.0) * increment * self.square;
+        self.triangle
+    }
+}
+
+impl<T: Float> Oscillator<T> for BlitOsc<T> {
+    fn set_frequency(&mut self, hz: T) {
+        self.phase_increment = hz / self.sample_rate;
+        self.band = Band::new(self.phase_increment);
+    }
+
+    fn set_phase(&mut self, phase: T) {
+        self.phase = wrap(phase);
+        self.settle_integrators();
+    }
+
+    fn phase(&self) -> T {
+        self.phase
+    }
+
+    fn reset(&mut self) {
+        self.phase = T::ZERO;
+        self.settle_integrators();
+    }
+
+    /// Switches the waveform, settling the integrators for it so the switch
+    /// doesn't start a transient. Settling sums every harmonic once, so a
+    /// switch costs far more than a sample.
+    fn set_waveform(&mut self, waveform: Waveform) {
+        self.waveform = waveform;
+        self.settle_integrators();
+    }
+
+    fn next_wrap(&self) -> Option<T> {
+        next_wrap(self.phase, self.phase_increment)
+    }
+
+    fn next_sample(&mut self) -> T {
+        self.step(T::ZERO)
+    }
+
+    fn fill_with_fm(&mut self, buffer: &mut [T], frequencies: &[T]) {
+        let inv_sample_rate = T::ONE / self.sample_rate;
+
+        for (sample, &hz) in buffer.iter_mut().zip(frequencies) {
+            self.phase_increment = hz * inv_sample_rate;
+            self.band = Band::new(self.phase_increment);
+            *sample = self.step(T::ZERO);
+        }
+    }
+
+    fn fill_with_pm(&mut self, buffer: &mut [T], phase_offsets: &[T]) {
+        // the offsets shift the impulses, and their changes feed the
+        // integrators like extra phase increment
+        for (sample, &offset) in buffer.iter_mut().zip(phase_offsets) {
+            *sample = self.step(offset);
+        }
+    }
+}
+
+impl<T: Float> Iterator for BlitOsc<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        Some(self.next_sample())
+    }
+}
+
+/// A bandlimited impulse train (BLIT).
+///
+/// Produces one impulse per cycle made of every harmonic below Nyquist at
+/// equal amplitude, normalized so each impulse peaks at 1.0. The train is
+/// unipolar, so it carries a DC offset of about `frequency / sample_rate`.
+/// Useful as an alias-free excitation for physical models, or as the raw
+/// material for other bandlimited waveforms.
+///
+/// # Example
+///
+/// ```
+/// use oscy::{blit::ImpulseTrain, Oscillator};
+///
+/// let mut train = ImpulseTrain::new(44100.0, 100.0);
+/// train.set_phase(0.99);
+/// let excitation: Vec<f32> = train.take(1024).collect();
+/// assert!(excitation.iter().all(|sample| sample.abs() <= 1.0));
+/// ```
+pub struct ImpulseTrain<T: Float = f32> {
+    phase: T,
+    phase_increment: T,
+    sample_rate: T,
+    band: Band<T>,
+}
+
+impl<T: Float> ImpulseTrain<T> {
+    /// Creates a new impulse train.
+    pub fn new(sample_rate: T, frequency: T) -> Self {
+        let phase_increment = frequency / sample_rate;
+        Self {
+            phase: T::ZERO,
+            phase_increment,
+            sample_rate,
+            band: Band::new(phase_increment),
+        }
+    }
+}
+
+impl<T: Float> Oscillator<T> for ImpulseTrain<T> {
+    fn set_frequency(&mut self, hz: T) {
+        self.phase_increment = hz / self.sample_rate;
+        self.band = Band::new(self.phase_increment);
+    }
+
+    fn set_phase(&mut self, phase: T) {
+        self.phase = wrap(phase);
+    }
+
+    fn phase(&self) -> T {
+        self.phase
+    }
+
+    fn reset(&mut self) {
+        self.phase = T::ZERO;
+    }
+
+    fn next_wrap(&self) -> Option<T> {
+        next_wrap(self.phase, self.phase_increment)
+    }
+
+    fn next_sample(&mut self) -> T {
+        self.phase = advance_phase(self.phase, self.phase_increment);
+
+        self.band.impulse(self.phase) / self.band.peak()
+    }
+}
+
+impl<T: Float> Iterator for ImpulseTrain<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        Some(self.next_sample())
+    }
+}
+
+/// Returns the integrator leak for a step of `phase_increment`.
+fn leak<T: Float>(phase_increment: T) -> T {
+    (T::ONE - T::from_f64(LEAK_PER_CYCLE) * phase_increment.abs()).max(T::ZERO)
+}
+
+/// The harmonics summed into an impulse.
+///
+/// Every harmonic below Nyquist is summed at full level, except the highest
+/// one, which fades in as it moves away from Nyquist. A harmonic appearing
+/// at full level would leave a DC offset in the integrators every time the
+/// frequency crosses a harmonic boundary, which adds up under FM.
+#[derive(Clone, Copy)]
+struct Band<T: Float> {
+    // odd number of full-level harmonics, counting DC and both sides of the
+    // spectrum
+    count: T,
+    top: T,
+    top_level: T,
+}
+
+impl<T: Float> Band<T> {
+    fn new(phase_increment: T) -> Self {
+        let below_nyquist = T::HALF / phase_increment.abs();
+
+        let max_harmonics = T::from_f64(MAX_HARMONICS);
+        if below_nyquist >= max_harmonics {
+            return Self {
+                count: T::TWO * max_harmonics - T::ONE,
+                top: max_harmonics,
+                top_level: T::ONE,
+            };
+        }
+
+        let top = below_nyquist.floor();
+        if top < T::ONE {
+            // above Nyquist only DC is left
+            return Self {
+                count: T::ONE,
+                top: T::ZERO,
+                top_level: T::ZERO,
+            };
+        }
+
+        Self {
+            count: T::TWO * top - T::ONE,
+            top,
+            top_level: below_nyquist - top,
+        }
+    }
+
+    /// Evaluates the impulse at `phase`, which averages 1.0 over a cycle.
+    fn impulse(&self, phase: T) -> T {
+        let top = self.top * phase;
+        dirichlet(phase, self.count)
+            + T::TWO * self.top_level * (T::TAU * (top - top.round())).cos()
+    }
+
+    /// Returns the value of the impulse at its peak.
+    fn peak(&self) -> T {
+        self.count + T::TWO * self.top_level
+    }
+}
+
+/// Evaluates the Dirichlet kernel `sin(π·m·x) / sin(π·x)`, the sum of `m`
+/// equal-amplitude harmonics, which peaks at `m` once per cycle.
+fn dirichlet<T: Float>(phase: T, harmonics: T) -> T {
+    // the kernel repeats every cycle for odd `m`, so centre the phase on the
+    // peak where the ratio needs the most precision
+    let x = phase - phase.round();
+    let denominator = (T::PI * x).sin();
+
+    if denominator.abs() < T::from_f64(1e-6) {
+        return harmonics;
+    }
+    // the numerator repeats every 2, keep its argument small as well
+    let y = harmonics * x;
+    (T::PI * (y - T::TWO * (T::HALF * y).round())).sin() / denominator
+}
+
+fn complex_mul(a: (f64, f64), b: (f64, f64)) -> (f64, f64) {
+    (a.0 * b.0 - a.1 * b.1, a.0 * b.1 + a.1 * b.0)
+}
+
+fn complex_div(a: (f64, f64), b: (f64, f64)) -> (f64, f64) {
+    let norm = b.0 * b.0 + b.1 * b.1;
+    complex_mul(a, (b.0 / norm, -b.1 / norm))
+}
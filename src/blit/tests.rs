@@ -0,0 +1,300 @@
+use std::f32::consts::TAU;
+
+use super::{BlitOsc, ImpulseTrain};
+use crate::{
+    Event, EventKind, Oscillator, Waveform,
+    naive::NaiveOsc,
+    poly_blep::PolyBlepOsc,
+    test_utils::{ANALYSIS_LEN, aliasing_ratio, analysis_frequency},
+};
+
+const EPSILON: f32 = 1e-5;
+
+fn approx_eq(a: f32, b: f32) -> bool {
+    (a - b).abs() < EPSILON
+}
+
+const WAVEFORMS: [Waveform; 6] = [
+    Waveform::Sine,
+    Waveform::Saw,
+    Waveform::Square,
+    Waveform::Pulse,
+    Waveform::Triangle,
+    Waveform::Morph,
+];
+
+fn blit(frequency: f32, waveform: Waveform) -> BlitOsc {
+    let mut osc = BlitOsc::new(44100.0, frequency, waveform);
+    osc.set_pulse_width(0.3);
+    osc.set_shape(0.5);
+    // settle the integrators for the new pulse width
+    osc.reset();
+    osc
+}
+
+fn poly_blep(frequency: f32, waveform: Waveform) -> PolyBlepOsc {
+    let mut osc = PolyBlepOsc::new(44100.0, frequency, waveform);
+    osc.set_pulse_width(0.3);
+    osc.set_shape(0.5);
+    osc
+}
+
+// impulse train tests
+
+#[test]
+fn test_impulse_train_peaks_at_one() {
+    let mut train = ImpulseTrain::new(100.0, 1.0); // 100 samples per cycle
+    train.set_phase(0.99);
+
+    assert!(approx_eq(train.next_sample(), 1.0));
+    assert!(train.next_sample().abs() < 0.1);
+}
+
+#[test]
+fn test_impulse_train_area() {
+    // harmonic 50 sits on Nyquist and is left out, so each impulse peaks at
+    // 99 and sums to 100 / 99
+    let train = ImpulseTrain::new(100.0, 1.0);
+    let sum: f32 = train.take(100).sum();
+    assert!((sum - 100.0 / 99.0).abs() < 1e-3);
+}
+
+#[test]
+fn test_impulse_train_is_bandlimited() {
+    let cycles = 233;
+    let frequency = analysis_frequency(44100.0, cycles);
+    let samples: Vec<f32> = ImpulseTrain::new(44100.0, frequency)
+        .take(ANALYSIS_LEN)
+        .collect();
+
+    assert!(aliasing_ratio(&samples, cycles) < 1e-9);
+}
+
+// oscillator tests
+
+#[test]
+fn test_aliases_less_than_poly_blep() {
+    let cycles = 61;
+    let frequency = analysis_frequency(44100.0, cycles);
+
+    for waveform in WAVEFORMS.into_iter().skip(1) {
+        let blit: Vec<f32> = blit(frequency, waveform).take(ANALYSIS_LEN).collect();
+        let poly: Vec<f32> = poly_blep(frequency, waveform).take(ANALYSIS_LEN).collect();
+
+        let blit = aliasing_ratio(&blit, cycles);
+        let poly = aliasing_ratio(&poly, cycles);
+        assert!(blit < poly * 0.01, "{waveform:?}: {blit} vs {poly}");
+    }
+}
+
+#[test]
+fn test_negative_frequency_aliases_less_than_poly_blep() {
+    let cycles = 61;
+    let frequency = -analysis_frequency(44100.0, cycles);
+
+    for waveform in [Waveform::Saw, Waveform::Square, Waveform::Triangle] {
+        let blit: Vec<f32> = blit(frequency, waveform).take(ANALYSIS_LEN).collect();
+        let poly: Vec<f32> = poly_blep(frequency, waveform).take(ANALYSIS_LEN).collect();
+
+        assert!(aliasing_ratio(&blit, cycles) < aliasing_ratio(&poly, cycles) * 0.01);
+    }
+}
+
+#[test]
+fn test_saw_follows_naive_mid_ramp() {
+    let mut osc = BlitOsc::new(44100.0, 441.0, Waveform::Saw); // 100 samples per cycle
+    let mut naive: NaiveOsc = NaiveOsc::new(44100.0, 441.0, Waveform::Saw);
+
+    for i in 0..1000 {
+        let (sample, expected) = (osc.next_sample(), naive.next_sample());
+        if (20..80).contains(&(i % 100)) {
+            assert!((sample - expected).abs() < 0.02);
+        }
+    }
+}
+
+#[test]
+fn test_pulse_keeps_its_levels() {
+    let mut osc = BlitOsc::new(44100.0, 441.0, Waveform::Pulse); // 100 samples per cycle
+    osc.set_pulse_width(0.25);
+
+    let samples: Vec<f32> = osc.take(1000).collect();
+    let mean = samples.iter().sum::<f32>() / samples.len() as f32;
+
+    assert!((samples[912] - 1.0).abs() < 0.05);
+    assert!((samples[962] + 1.0).abs() < 0.05);
+    assert!((mean + 0.5).abs() < 0.05);
+}
+
+#[test]
+fn test_triangle_stays_centred() {
+    let osc = BlitOsc::new(44100.0, 2000.0, Waveform::Triangle);
+    let samples: Vec<f32> = osc.take(10_000).collect();
+    let mean = samples.iter().sum::<f32>() / samples.len() as f32;
+
+    assert!(mean.abs() < 0.01);
+    assert!(samples.iter().all(|sample| sample.abs() < 1.05));
+}
+
+#[test]
+fn test_output_bounded() {
+    for waveform in WAVEFORMS {
+        let mut osc = blit(3000.0, waveform);
+
+        for _ in 0..2000 {
+            assert!(osc.next_sample().abs() < 1.5);
+        }
+    }
+}
+
+#[test]
+fn test_slow_through_zero_sweep_stays_bounded() {
+    for waveform in WAVEFORMS {
+        let mut osc = blit(200.0, waveform);
+
+        // one second sweep between -400 Hz and 800 Hz
+        for i in 0..44_100 {
+            let modulator = (i as f32 / 44_100.0 * TAU).sin();
+            osc.set_frequency(200.0 + 600.0 * modulator);
+            assert!(osc.next_sample().abs() < 1.5, "{waveform:?}");
+        }
+    }
+}
+
+#[test]
+fn test_vibrato_keeps_the_triangle_level() {
+    let mut osc = BlitOsc::new(44100.0, 1000.0, Waveform::Triangle);
+
+    for i in 0..44_100 {
+        let modulator = (i as f32 / 8820.0 * TAU).sin();
+        osc.set_frequency(1000.0 + 300.0 * modulator);
+        assert!(osc.next_sample().abs() < 1.05);
+    }
+}
+
+#[test]
+fn test_fill_with_pm_matches_shifted_phase() {
+    let mut osc = BlitOsc::new(44100.0, 441.0, Waveform::Saw);
+    let mut shifted = BlitOsc::new(44100.0, 441.0, Waveform::Saw);
+    osc.set_phase(0.5);
+    shifted.set_phase(0.6);
+
+    // a jump in the offset acts like a one-sample frequency jump, so ease
+    // it in and compare once the integrators have settled
+    let offsets: Vec<f32> = (0..2048)
+        .map(|i| 0.1 * (i as f32 / 256.0).min(1.0))
+        .collect();
+    let mut buffer = [0.0f32; 2048];
+    osc.fill_with_pm(&mut buffer, &offsets);
+
+    for _ in 0..1024 {
+        shifted.next_sample();
+    }
+    for sample in &buffer[1024..] {
+        assert!((sample - shifted.next_sample()).abs() < 0.01);
+    }
+}
+
+#[test]
+fn test_reset_restarts_output() {
+    let mut osc = BlitOsc::new(44100.0, 3000.0, Waveform::Triangle);
+    let first: Vec<f32> = (0..64).map(|_| osc.next_sample()).collect();
+
+    osc.reset();
+    let second: Vec<f32> = (0..64).map(|_| osc.next_sample()).collect();
+
+    for (a, b) in first.iter().zip(&second) {
+        assert!(approx_eq(*a, *b));
+    }
+}
+
+#[test]
+fn test_tiny_negative_phase_stays_below_one() {
+    // a step from 1e-9 back by 2e-9 lands at -1e-9, which rounds to 1.0
+    let mut osc: BlitOsc = BlitOsc::new(1.0, -2e-9, Waveform::Saw);
+    osc.set_phase(1e-9);
+    osc.next_sample();
+    assert!(osc.phase() < 1.0);
+
+    osc.set_phase(-1e-9);
+    assert!(osc.phase() < 1.0);
+
+    let mut train: ImpulseTrain = ImpulseTrain::new(1.0, -2e-9);
+    train.set_phase(1e-9);
+    train.next_sample();
+    assert!(train.phase() < 1.0);
+
+    train.set_phase(-1e-9);
+    assert!(train.phase() < 1.0);
+}
+
+// sample type tests
+
+#[test]
+fn test_f64_matches_f32() {
+    let mut single = BlitOsc::<f32>::new(44100.0, 1234.0, Waveform::Triangle);
+    let mut double = BlitOsc::<f64>::new(44100.0, 1234.0, Waveform::Triangle);
+
+    for _ in 0..1000 {
+        assert!((single.next_sample() as f64 - double.next_sample()).abs() < 1e-3);
+    }
+}
+
+// trait impl tests
+
+#[test]
+fn test_waveform_event_switches_waveform() {
+    let mut osc: BlitOsc = BlitOsc::new(44100.0, 441.0, Waveform::Saw);
+    for _ in 0..100 {
+        osc.next_sample();
+    }
+    let mut expected: BlitOsc = BlitOsc::new(44100.0, 441.0, Waveform::Square);
+    expected.set_phase(osc.phase());
+
+    let mut buffer = [0.0; 64];
+    osc.fill_with_events(
+        &mut buffer,
+        &[Event::new(0, EventKind::Waveform(Waveform::Square))],
+    );
+
+    // the integrators are settled, so the square starts cleanly
+    for sample in buffer {
+        assert!((sample - expected.next_sample()).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn test_set_phase() {
+    let mut osc = BlitOsc::new(4.0, 1.0, Waveform::Saw);
+    osc.set_phase(-0.25);
+    assert!(approx_eq(osc.phase(), 0.75));
+}
+
+#[test]
+fn test_next_wrap_reports_fraction() {
+    let mut osc = BlitOsc::new(10.0, 3.0, Waveform::Saw); // inc = 0.3
+    osc.set_phase(0.9);
+
+    let fraction: f32 = osc.next_wrap().unwrap();
+    assert!((fraction - 1.0 / 3.0).abs() < 1e-5);
+}
+
+#[test]
+fn test_fill_buffer() {
+    let mut osc = BlitOsc::new(44100.0, 440.0, Waveform::Square);
+    let mut reference = BlitOsc::new(44100.0, 440.0, Waveform::Square);
+    let mut buffer = [0.0f32; 64];
+
+    osc.fill(&mut buffer);
+
+    for sample in buffer {
+        assert!(approx_eq(sample, reference.next_sample()));
+    }
+}
+
+#[test]
+fn test_iterator() {
+    let osc = BlitOsc::new(44100.0, 440.0, Waveform::Saw);
+    let samples: Vec<f32> = osc.take(64).collect();
+    assert_eq!(samples.len(), 64);
+}
@@ -0,0 +1,327 @@
+use std::f32::consts::TAU;
+
+use super::{DpwOrder, DpwOsc};
+use crate::{
+    Event, EventKind, Oscillator, Waveform,
+    naive::NaiveOsc,
+    poly_blep::PolyBlepOsc,
+    test_utils::{ANALYSIS_LEN, aliasing_ratio, analysis_frequency},
+};
+
+const EPSILON: f32 = 1e-5;
+
+fn approx_eq(a: f32, b: f32) -> bool {
+    (a - b).abs() < EPSILON
+}
+
+const ORDERS: [DpwOrder; 3] = [DpwOrder::Second, DpwOrder::Third, DpwOrder::Fourth];
+
+fn aliasing(frequency: f32, cycles: usize, waveform: Waveform, order: DpwOrder) -> f32 {
+    let samples: Vec<f32> = DpwOsc::new(44100.0, frequency, waveform, order)
+        .take(ANALYSIS_LEN)
+        .collect();
+    aliasing_ratio(&samples, cycles)
+}
+
+/// Naive saw at the phase the output lags behind, for a saw started at 0.
+fn delayed_saw(osc: &DpwOsc, phase: f32) -> f32 {
+    let phase_increment = osc.phase_increment as f32;
+    2.0 * (phase - osc.latency() * phase_increment).rem_euclid(1.0) - 1.0
+}
+
+#[test]
+fn test_aliases_less_than_naive() {
+    let cycles = 61;
+    let frequency = analysis_frequency(44100.0, cycles);
+
+    for waveform in [Waveform::Saw, Waveform::Square, Waveform::Triangle] {
+        let naive: Vec<f32> = NaiveOsc::new(44100.0, frequency, waveform)
+            .take(ANALYSIS_LEN)
+            .collect();
+        let naive = aliasing_ratio(&naive, cycles);
+
+        for order in ORDERS {
+            let dpw = aliasing(frequency, cycles, waveform, order);
+            assert!(
+                dpw < naive * 0.2,
+                "{waveform:?} {order:?}: {dpw} vs {naive}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_orders_compared_to_poly_blep() {
+    let cycles = 233;
+    let frequency = analysis_frequency(44100.0, cycles);
+
+    for waveform in [Waveform::Saw, Waveform::Square] {
+        let poly: Vec<f32> = PolyBlepOsc::new(44100.0, frequency, waveform)
+            .take(ANALYSIS_LEN)
+            .collect();
+        let poly = aliasing_ratio(&poly, cycles);
+        let [second, third, fourth] =
+            ORDERS.map(|order| aliasing(frequency, cycles, waveform, order));
+
+        // the third order matches polyBLEP's two-sample residual exactly
+        assert!(second > poly, "{waveform:?}");
+        assert!(third < poly * 1.01, "{waveform:?}");
+        assert!(fourth < poly * 0.5, "{waveform:?}");
+    }
+}
+
+#[test]
+fn test_higher_orders_alias_less() {
+    let cycles = 61;
+    let frequency = analysis_frequency(44100.0, cycles);
+
+    for waveform in [Waveform::Saw, Waveform::Square, Waveform::Triangle] {
+        let [second, third, fourth] =
+            ORDERS.map(|order| aliasing(frequency, cycles, waveform, order));
+
+        assert!(third < second, "{waveform:?}: {third} vs {second}");
+        assert!(fourth < third, "{waveform:?}: {fourth} vs {third}");
+    }
+}
+
+#[test]
+fn test_saw_follows_naive_mid_ramp() {
+    for order in ORDERS {
+        // 100 samples per cycle
+        let mut osc = DpwOsc::new(44100.0, 441.0, Waveform::Saw, order);
+
+        for i in 1..=1000 {
+            let phase = (i as f32 * 0.01).fract();
+            let expected = delayed_saw(&osc, phase);
+            let sample = osc.next_sample();
+
+            if (10..90).contains(&(i % 100)) {
+                assert!(
+                    (sample - expected).abs() < 1e-3,
+                    "{order:?}: {sample} vs {expected}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_frequency_change_has_no_transient() {
+    for order in ORDERS {
+        let mut osc: DpwOsc = DpwOsc::new(44100.0, 441.0, Waveform::Saw, order);
+        for _ in 0..20 {
+            osc.next_sample();
+        }
+
+        // double the frequency mid-ramp. The slope moves from 0.02 to 0.04
+        // per sample as the change passes through the differences, without
+        // overshooting
+        osc.set_frequency(882.0);
+        let mut previous = osc.next_sample();
+        for _ in 0..10 {
+            let sample = osc.next_sample();
+            let slope = sample - previous;
+            assert!(
+                slope > 0.02 - 1e-3 && slope < 0.04 + 1e-3,
+                "{order:?}: {slope}"
+            );
+            previous = sample;
+        }
+        assert!((osc.next_sample() - previous - 0.04).abs() < 1e-3);
+    }
+}
+
+#[test]
+fn test_pulse_keeps_its_levels() {
+    let mut osc = DpwOsc::new(44100.0, 441.0, Waveform::Pulse, DpwOrder::Third);
+    osc.set_pulse_width(0.25);
+
+    let samples: Vec<f32> = osc.take(1000).collect();
+    let mean = samples.iter().sum::<f32>() / samples.len() as f32;
+
+    assert!((samples[912] - 1.0).abs() < 1e-3);
+    assert!((samples[962] + 1.0).abs() < 1e-3);
+    assert!((mean + 0.5).abs() < 0.01);
+}
+
+#[test]
+fn test_pulse_width_modulation_has_no_transient() {
+    let mut osc = DpwOsc::new(44100.0, 441.0, Waveform::Pulse, DpwOrder::Fourth);
+
+    for i in 0..10_000 {
+        osc.set_pulse_width(0.5 + 0.4 * (i as f32 / 1000.0 * TAU).sin());
+        assert!(osc.next_sample().abs() < 1.2);
+    }
+}
+
+#[test]
+fn test_triangle_stays_centred() {
+    for order in ORDERS {
+        let osc = DpwOsc::new(44100.0, 2000.0, Waveform::Triangle, order);
+        let samples: Vec<f32> = osc.take(10_000).collect();
+        let mean = samples.iter().sum::<f32>() / samples.len() as f32;
+
+        assert!(mean.abs() < 0.01);
+        assert!(samples.iter().all(|sample| sample.abs() < 1.05));
+    }
+}
+
+#[test]
+fn test_low_frequencies_keep_their_precision() {
+    // the fourth order differences cancel the most digits
+    let mut osc = DpwOsc::new(44100.0, 1.0, Waveform::Saw, DpwOrder::Fourth);
+
+    for i in 1..=44_100 {
+        let phase = (i as f32 / 44100.0).fract();
+        let expected = delayed_saw(&osc, phase);
+        let sample = osc.next_sample();
+
+        if (0.01..0.99).contains(&phase) {
+            assert!((sample - expected).abs() < 1e-3, "{sample} vs {expected}");
+        }
+    }
+}
+
+#[test]
+fn test_through_zero_fm_stays_bounded() {
+    for waveform in [
+        Waveform::Saw,
+        Waveform::Pulse,
+        Waveform::Triangle,
+        Waveform::Morph,
+    ] {
+        for order in ORDERS {
+            let mut osc = DpwOsc::new(44100.0, 0.0, waveform, order);
+            osc.set_pulse_width(0.3);
+            osc.set_shape(0.5);
+
+            for i in 0..10_000 {
+                let modulator = (i as f32 / 300.0 * TAU).sin();
+                osc.set_frequency(200.0 + 2000.0 * modulator);
+                assert!(osc.next_sample().abs() < 1.5, "{waveform:?} {order:?}");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_fill_with_pm_matches_shifted_phase() {
+    let mut osc = DpwOsc::new(44100.0, 441.0, Waveform::Saw, DpwOrder::Third);
+    let mut shifted = DpwOsc::new(44100.0, 441.0, Waveform::Saw, DpwOrder::Third);
+    osc.set_phase(0.5);
+    shifted.set_phase(0.6);
+
+    // the jump to the offset takes two samples to pass through the
+    // differences
+    let mut buffer = [0.0f32; 256];
+    osc.fill_with_pm(&mut buffer, &[0.1; 256]);
+
+    for (i, sample) in buffer.into_iter().enumerate() {
+        let expected = shifted.next_sample();
+        if i >= 2 {
+            assert!((sample - expected).abs() < 1e-3);
+        }
+    }
+}
+
+#[test]
+fn test_latency() {
+    let latencies = ORDERS.map(|order| DpwOsc::new(44100.0, 440.0, Waveform::Saw, order).latency());
+    assert_eq!(latencies, [0.5, 1.0, 1.5]);
+}
+
+#[test]
+fn test_reset_restarts_output() {
+    let mut osc = DpwOsc::new(44100.0, 3000.0, Waveform::Triangle, DpwOrder::Fourth);
+    let first: Vec<f32> = (0..64).map(|_| osc.next_sample()).collect();
+
+    osc.reset();
+    let second: Vec<f32> = (0..64).map(|_| osc.next_sample()).collect();
+
+    for (a, b) in first.iter().zip(&second) {
+        assert!(approx_eq(*a, *b));
+    }
+}
+
+#[test]
+fn test_tiny_negative_phase_stays_below_one() {
+    // a step from 1e-9 back by 2e-9 lands at -1e-9, which rounds to 1.0
+    let mut osc: DpwOsc = DpwOsc::new(1.0, -2e-9, Waveform::Saw, DpwOrder::Second);
+    osc.set_phase(1e-9);
+    osc.next_sample();
+    assert!(osc.phase() < 1.0);
+
+    osc.set_phase(-1e-9);
+    assert!(osc.phase() < 1.0);
+}
+
+// sample type tests
+
+#[test]
+fn test_f64_matches_f32() {
+    let mut single = DpwOsc::<f32>::new(44100.0, 1234.0, Waveform::Saw, DpwOrder::Third);
+    let mut double = DpwOsc::<f64>::new(44100.0, 1234.0, Waveform::Saw, DpwOrder::Third);
+
+    for _ in 0..1000 {
+        assert!((single.next_sample() as f64 - double.next_sample()).abs() < 1e-3);
+    }
+}
+
+// trait impl tests
+
+#[test]
+fn test_waveform_event_switches_waveform() {
+    let mut osc: DpwOsc = DpwOsc::new(44100.0, 441.0, Waveform::Saw, DpwOrder::Third);
+    for _ in 0..100 {
+        osc.next_sample();
+    }
+    let mut expected: DpwOsc = DpwOsc::new(44100.0, 441.0, Waveform::Square, DpwOrder::Third);
+    expected.set_phase(osc.phase());
+
+    let mut buffer = [0.0; 64];
+    osc.fill_with_events(
+        &mut buffer,
+        &[Event::new(0, EventKind::Waveform(Waveform::Square))],
+    );
+
+    // the differentiators are refilled, so the square starts cleanly
+    for sample in buffer {
+        assert!((sample - expected.next_sample()).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn test_set_phase() {
+    let mut osc = DpwOsc::new(4.0, 1.0, Waveform::Saw, DpwOrder::Second);
+    osc.set_phase(-0.25);
+    assert!(approx_eq(osc.phase(), 0.75));
+}
+
+#[test]
+fn test_next_wrap_reports_fraction() {
+    let mut osc = DpwOsc::new(10.0, 3.0, Waveform::Saw, DpwOrder::Second); // inc = 0.3
+    osc.set_phase(0.9);
+
+    let fraction: f32 = osc.next_wrap().unwrap();
+    assert!((fraction - 1.0 / 3.0).abs() < 1e-5);
+}
+
+#[test]
+fn test_fill_buffer() {
+    let mut osc = DpwOsc::new(44100.0, 440.0, Waveform::Square, DpwOrder::Third);
+    let mut reference = DpwOsc::new(44100.0, 440.0, Waveform::Square, DpwOrder::Third);
+    let mut buffer = [0.0f32; 64];
+
+    osc.fill(&mut buffer);
+
+    for sample in buffer {
+        assert!(approx_eq(sample, reference.next_sample()));
+    }
+}
+
+#[test]
+fn test_iterator() {
+    let osc = DpwOsc::new(44100.0, 440.0, Waveform::Saw, DpwOrder::Second);
+    let samples: Vec<f32> = osc.take(64).collect();
+    assert_eq!(samples.len(), 64);
+}
@@ -0,0 +1,500 @@
+#[cfg(test)]
+mod tests;
+
+use core::fmt::Debug;
+use core::marker::PhantomData;
+
+use crate::{Waveform, sine::POLYNOMIAL};
+
+/// 1.0 in Q31, the format every waveform is computed in.
+const ONE: i64 = 1 << 31;
+
+/// One full cycle of the 32-bit phase accumulator.
+const CYCLE: u64 = 1 << 32;
+
+/// Odd polynomial approximating sin(π/2·x) on [-1.0, 1.0], in Q31, highest
+/// order first. The same polynomial as the float sine, fitted for a maximum
+/// error of about 6e-7 (-124 dB).
+const SINE: [i64; 4] = to_q31(POLYNOMIAL);
+
+/// Rounds polynomial coefficients to Q31.
+const fn to_q31(coefficients: [f32; 4]) -> [i64; 4] {
+    let mut q31 = [0; 4];
+    let mut i = 0;
+    while i < coefficients.len() {
+        let scaled = coefficients[i] as f64 * ONE as f64;
+        // round half away from zero
+        q31[i] = (scaled + if scaled < 0.0 { -0.5 } else { 0.5 }) as i64;
+        i += 1;
+    }
+    q31
+}
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for i16 {}
+    impl Sealed for i32 {}
+}
+
+/// A fixed-point sample type, implemented for [`i16`] (Q15) and [`i32`]
+/// (Q31).
+///
+/// Full scale, -1.0 to just below 1.0, spans the whole integer range. The
+/// oscillators compute every sample in Q31 and convert it at the end.
+///
+/// The trait is sealed.
+pub trait FixedSample:
+    sealed::Sealed + Copy + Default + Debug + Ord + Send + Sync + 'static
+{
+    /// Converts from Q31, dropping the bits that don't fit.
+    fn from_q31(x: i32) -> Self;
+    /// Converts to Q31.
+    fn to_q31(self) -> i32;
+}
+
+impl FixedSample for i16 {
+    fn from_q31(x: i32) -> Self {
+        (x >> 16) as i16
+    }
+
+    fn to_q31(self) -> i32 {
+        i32::from(self) << 16
+    }
+}
+
+impl FixedSample for i32 {
+    fn from_q31(x: i32) -> Self {
+        x
+    }
+
+    fn to_q31(self) -> i32 {
+        self
+    }
+}
+
+/// A trait for fixed-point oscillators, for targets without an FPU.
+///
+/// Like [`Oscillator`](crate::Oscillator), but the phase is a 32-bit
+/// integer accumulator where 2^32 is one full cycle, so it wraps for free.
+/// Frequencies are in hertz as Q16.16, i.e. `440 << 16` for 440 Hz, and
+/// samples are [`FixedSample`]s.
+///
+/// Frequencies run forwards only; there's no through-zero FM or hard sync.
+pub trait FixedOscillator<S: FixedSample> {
+    /// Sets the frequency in hertz as Q16.16.
+    ///
+    /// Takes a 64-bit division. For modulation every sample, see
+    /// [`FixedOscillator::set_phase_increment`].
+    fn set_frequency(&mut self, hz: u32);
+
+    /// Sets how far the phase moves every sample, where 2^32 is one cycle.
+    fn set_phase_increment(&mut self, increment: u32);
+
+    /// Sets the current phase, where 2^32 is one cycle.
+    fn set_phase(&mut self, phase: u32);
+
+    /// Returns the current phase, where 2^32 is one cycle.
+    fn phase(&self) -> u32;
+
+    /// Resets the oscillator to its initial state.
+    fn reset(&mut self);
+
+    /// Generates and returns the next sample.
+    fn next_sample(&mut self) -> S;
+
+    /// Fills a buffer with consecutive samples.
+    fn fill(&mut self, buffer: &mut [S]) {
+        for sample in buffer.iter_mut() {
+            *sample = self.next_sample();
+        }
+    }
+}
+
+/// Returns the phase increment for `hz`, in hertz as Q16.16.
+fn phase_increment(sample_rate: u32, hz: u32) -> u32 {
+    // hz / sample_rate of 2^32, with the 2^16 of Q16.16 already in `hz`
+    ((u64::from(hz) << 16) / u64::from(sample_rate)) as u32
+}
+
+/// A fixed-point naive oscillator with no anti-aliasing.
+///
+/// The fixed-point counterpart of [`NaiveOsc`](crate::naive::NaiveOsc),
+/// using only integer arithmetic per sample. The sine comes from a
+/// polynomial accurate to about 6e-7, beyond the resolution of Q15.
+///
+/// # Example
+///
+/// ```
+/// use oscy::{fixed::{FixedNaiveOsc, FixedOscillator}, Waveform};
+///
+/// // Q15 samples from a 440 Hz saw
+/// let mut osc: FixedNaiveOsc<i16> = FixedNaiveOsc::new(48_000, 440 << 16, Waveform::Saw);
+/// let sample = osc.next_sample();
+/// assert!(sample < 0);
+/// ```
+pub struct FixedNaiveOsc<S: FixedSample = i16> {
+    phase: u32,
+    phase_increment: u32,
+    sample_rate: u32,
+    waveform: Waveform,
+    pulse_width: u32,
+    shape: u32,
+    _sample: PhantomData<S>,
+}
+
+impl<S: FixedSample> FixedNaiveOsc<S> {
+    /// Creates a new fixed-point naive oscillator at `hz`, in hertz as
+    /// Q16.16.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sample_rate` is zero.
+    pub fn new(sample_rate: u32, hz: u32, waveform: Waveform) -> Self {
+        assert!(sample_rate > 0, "the sample rate must be positive");
+
+        Self {
+            phase: 0,
+            phase_increment: phase_increment(sample_rate, hz),
+            sample_rate,
+            waveform,
+            pulse_width: 1 << 31,
+            shape: 0,
+            _sample: PhantomData,
+        }
+    }
+
+    /// Sets the pulse width used by [`Waveform::Pulse`], as the part of
+    /// the cycle spent high, where 2^32 is the whole cycle.
+    pub fn set_pulse_width(&mut self, width: u32) {
+        self.pulse_width = width;
+    }
+
+    /// Sets the morph shape used by [`Waveform::Morph`], from sine at 0
+    /// through triangle and saw to square at `u32::MAX`.
+    pub fn set_shape(&mut self, shape: u32) {
+        self.shape = shape;
+    }
+
+    /// Computes the sample at the current phase, in Q31.
+    fn render(&self) -> i64 {
+        let phase = self.phase;
+        match self.waveform {
+            Waveform::Sine => sine(phase),
+            Waveform::Saw => saw(phase),
+            Waveform::Square => pulse(phase, 1 << 31),
+            Waveform::Pulse => pulse(phase, self.pulse_width),
+            Waveform::Triangle => triangle(phase),
+            Waveform::Morph => morph(self.shape, |waveform| match waveform {
+                Waveform::Sine => sine(phase),
+                Waveform::Triangle => triangle(phase),
+                Waveform::Saw => saw(phase),
+                _ => pulse(phase, 1 << 31),
+            }),
+        }
+    }
+}
+
+impl<S: FixedSample> FixedOscillator<S> for FixedNaiveOsc<S> {
+    fn set_frequency(&mut self, hz: u32) {
+        self.phase_increment = phase_increment(self.sample_rate, hz);
+    }
+
+    fn set_phase_increment(&mut self, increment: u32) {
+        self.phase_increment = increment;
+    }
+
+    fn set_phase(&mut self, phase: u32) {
+        self.phase = phase;
+    }
+
+    fn phase(&self) -> u32 {
+        self.phase
+    }
+
+    fn reset(&mut self) {
+        self.phase = 0;
+    }
+
+    fn next_sample(&mut self) -> S {
+        self.phase = self.phase.wrapping_add(self.phase_increment);
+        to_sample(self.render())
+    }
+}
+
+impl<S: FixedSample> Iterator for FixedNaiveOsc<S> {
+    type Item = S;
+
+    fn next(&mut self) -> Option<S> {
+        Some(self.next_sample())
+    }
+}
+
+/// A fixed-point bandlimited oscillator using polyBLEP.
+///
+/// The fixed-point counterpart of
+/// [`PolyBlepOsc`](crate::poly_blep::PolyBlepOsc). The polyBLEP and
+/// polyBLAMP residuals are computed in Q31, dividing by the phase increment
+/// through a reciprocal that is updated whenever the frequency changes, so
+/// every sample takes only integer multiplies.
+///
+/// # Example
+///
+/// ```
+/// use oscy::{fixed::{FixedOscillator, FixedPolyBlepOsc}, Waveform};
+///
+/// // Q31 samples from a 440 Hz square
+/// let mut osc: FixedPolyBlepOsc<i32> =
+///     FixedPolyBlepOsc::new(48_000, 440 << 16, Waveform::Square);
+/// let mut buffer = [0i32; 64];
+/// osc.fill(&mut buffer);
+/// ```
+pub struct FixedPolyBlepOsc<S: FixedSample = i16> {
+    phase: u32,
+    phase_increment: u32,
+    // 2^62 / phase_increment, so residuals multiply instead of divide
+    reciprocal: u64,
+    sample_rate: u32,
+    waveform: Waveform,
+    pulse_width: u32,
+    shape: u32,
+    _sample: PhantomData<S>,
+}
+
+impl<S: FixedSample> FixedPolyBlepOsc<S> {
+    /// Creates a new fixed-point polyBLEP oscillator at `hz`, in hertz as
+    /// Q16.16.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sample_rate` is zero.
+    pub fn new(sample_rate: u32, hz: u32, waveform: Waveform) -> Self {
+        assert!(sample_rate > 0, "the sample rate must be positive");
+
+        let mut osc = Self {
+            phase: 0,
+            phase_increment: 0,
+            reciprocal: 0,
+            sample_rate,
+            waveform,
+            pulse_width: 1 << 31,
+            shape: 0,
+            _sample: PhantomData,
+        };
+        osc.set_phase_increment(phase_increment(sample_rate, hz));
+        osc
+    }
+
+    /// Sets the pulse width used by [`Waveform::Pulse`], as the part of
+    /// the cycle spent high, where 2^32 is the whole cycle.
+    pub fn set_pulse_width(&mut self, width: u32) {
+        self.pulse_width = width;
+    }
+
+    /// Sets the morph shape used by [`Waveform::Morph`], from sine at 0
+    /// through triangle and saw to square at `u32::MAX`.
+    pub fn set_shape(&mut self, shape: u32) {
+        self.shape = shape;
+    }
+
+    /// Computes the polyBLEP correction at `phase`, in Q31.
+    ///
+    /// Non-zero within one sample of a discontinuity at phase 0, following
+    /// the same 2-sample residual as
+    /// [`PolyBlepOsc::poly_blep`](crate::poly_blep::PolyBlepOsc::poly_blep).
+    pub fn poly_blep(&self, phase: u32) -> i64 {
+        match self.distance(phase) {
+            // just after the discontinuity
+            Some((t, true)) => -square(ONE - t),
+            // just before it
+            Some((t, false)) => square(ONE - t),
+            None => 0,
+        }
+    }
+
+    /// Computes the polyBLAMP correction at `phase`, in Q31, for a unit
+    /// slope change measured in samples.
+    pub fn poly_blamp(&self, phase: u32) -> i64 {
+        match self.distance(phase) {
+            Some((t, _)) => cube(ONE - t) / 3,
+            None => 0,
+        }
+    }
+
+    /// Returns how far `phase` is from the discontinuity at phase 0 in
+    /// samples, in Q31, and whether it lies after it, if that's less than
+    /// one sample.
+    fn distance(&self, phase: u32) -> Option<(i64, bool)> {
+        let increment = u64::from(self.phase_increment);
+        let (distance, after) = if u64::from(phase) < increment {
+            (u64::from(phase), true)
+        } else if CYCLE - u64::from(phase) < increment {
+            (CYCLE - u64::from(phase), false)
+        } else {
+            return None;
+        };
+
+        // distance < increment, so the product stays below 2^62
+        Some((((distance * self.reciprocal) >> 31) as i64, after))
+    }
+
+    fn saw(&self) -> i64 {
+        saw(self.phase) - self.poly_blep(self.phase)
+    }
+
+    fn pulse(&self, width: u32) -> i64 {
+        // falling edge sits at the pulse width
+        let falling = self.phase.wrapping_sub(width);
+        pulse(self.phase, width) + self.poly_blep(self.phase) - self.poly_blep(falling)
+    }
+
+    fn triangle(&self) -> i64 {
+        // slope changes by +8 at phase 0 and by -8 at phase 0.5, per
+        // sample that's 8 * increment / 2^32, applied with a shift
+        let corners = self.poly_blamp(self.phase) - self.poly_blamp(self.phase ^ (1 << 31));
+        triangle(self.phase) + ((i64::from(self.phase_increment) * corners) >> 29)
+    }
+
+    /// Computes the band-limited sample at the current phase, in Q31.
+    fn render(&self) -> i64 {
+        match self.waveform {
+            Waveform::Sine => sine(self.phase),
+            Waveform::Saw => self.saw(),
+            Waveform::Square => self.pulse(1 << 31),
+            Waveform::Pulse => self.pulse(self.pulse_width),
+            Waveform::Triangle => self.triangle(),
+            Waveform::Morph => morph(self.shape, |waveform| match waveform {
+                Waveform::Sine => sine(self.phase),
+                Waveform::Triangle => self.triangle(),
+                Waveform::Saw => self.saw(),
+                _ => self.pulse(1 << 31),
+            }),
+        }
+    }
+}
+
+impl<S: FixedSample> FixedOscillator<S> for FixedPolyBlepOsc<S> {
+    fn set_frequency(&mut self, hz: u32) {
+        self.set_phase_increment(phase_increment(self.sample_rate, hz));
+    }
+
+    /// Sets how far the phase moves every sample, where 2^32 is one cycle.
+    ///
+    /// Updates the reciprocal of the increment, which takes a 64-bit
+    /// division.
+    fn set_phase_increment(&mut self, increment: u32) {
+        self.phase_increment = increment;
+        self.reciprocal = match increment {
+            0 => 0,
+            increment => (1 << 62) / u64::from(increment),
+        };
+    }
+
+    fn set_phase(&mut self, phase: u32) {
+        self.phase = phase;
+    }
+
+    fn phase(&self) -> u32 {
+        self.phase
+    }
+
+    fn reset(&mut self) {
+        self.phase = 0;
+    }
+
+    fn next_sample(&mut self) -> S {
+        self.phase = self.phase.wrapping_add(self.phase_increment);
+        to_sample(self.render())
+    }
+}
+
+impl<S: FixedSample> Iterator for FixedPolyBlepOsc<S> {
+    type Item = S;
+
+    fn next(&mut self) -> Option<S> {
+        Some(self.next_sample())
+    }
+}
+
+/// Multiplies two Q31 values.
+fn mul(a: i64, b: i64) -> i64 {
+    (a * b) >> 31
+}
+
+fn square(x: i64) -> i64 {
+    mul(x, x)
+}
+
+fn cube(x: i64) -> i64 {
+    mul(mul(x, x), x)
+}
+
+/// Saturates a Q31 value into the sample type.
+fn to_sample<S: FixedSample>(x: i64) -> S {
+    S::from_q31(x.clamp(i64::from(i32::MIN), i64::from(i32::MAX)) as i32)
+}
+
+fn sine(phase: u32) -> i64 {
+    // as a signed phase in [-0.5, 0.5), folded onto [-0.25, 0.25], where
+    // the polynomial takes over
+    let half = 1 << 31;
+    let quarter = 1 << 30;
+    let phase = i64::from(phase as i32);
+    let folded = if phase > quarter {
+        half - phase
+    } else if phase < -quarter {
+        -half - phase
+    } else {
+        phase
+    };
+
+    // a quarter cycle is 1.0 in Q31
+    let x = folded << 1;
+    let x2 = mul(x, x);
+    let sum = SINE[1..].iter().fold(SINE[0], |sum, &c| c + mul(sum, x2));
+    mul(sum, x)
+}
+
+fn saw(phase: u32) -> i64 {
+    // 2 * phase - 1, the phase is already twice its value in Q31
+    i64::from(phase) - ONE
+}
+
+fn pulse(phase: u32, width: u32) -> i64 {
+    if phase < width { ONE } else { -ONE }
+}
+
+fn triangle(phase: u32) -> i64 {
+    let phase = i64::from(phase);
+    if phase < ONE {
+        2 * phase - ONE
+    } else {
+        3 * ONE - 2 * phase
+    }
+}
+
+/// Blends the waveforms for a morph `shape`, with `waveform` rendering
+/// each of them in Q31.
+fn morph(shape: u32, waveform: impl Fn(Waveform) -> i64) -> i64 {
+    const ORDER: [Waveform; 4] = [
+        Waveform::Sine,
+        Waveform::Triangle,
+        Waveform::Saw,
+        Waveform::Square,
+    ];
+
+    // three segments between the four waveforms
+    let position = u64::from(shape) * 3;
+    let segment = (position >> 32).min(2) as usize;
+    let blend = ((position - ((segment as u64) << 32)) >> 1) as i64;
+
+    // only evaluate the (at most two) waveforms present in the blend
+    let mut out = 0;
+    if blend < ONE {
+        out += mul(ONE - blend, waveform(ORDER[segment]));
+    }
+    if blend > 0 {
+        out += mul(blend, waveform(ORDER[segment + 1]));
+    }
+    out
+}
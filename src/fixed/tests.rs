@@ -0,0 +1,239 @@
+use super::{FixedNaiveOsc, FixedOscillator, FixedPolyBlepOsc, FixedSample};
+use crate::{Oscillator, Waveform, naive::NaiveOsc, poly_blep::PolyBlepOsc};
+
+const EPSILON: f64 = 1e-6;
+
+fn approx_eq(a: f64, b: f64) -> bool {
+    (a - b).abs() < EPSILON
+}
+
+/// Converts a sample back to a float in [-1.0, 1.0).
+fn to_f64<S: FixedSample>(sample: S) -> f64 {
+    f64::from(sample.to_q31()) / f64::from(1u32 << 31)
+}
+
+const WAVEFORMS: [Waveform; 6] = [
+    Waveform::Sine,
+    Waveform::Saw,
+    Waveform::Square,
+    Waveform::Pulse,
+    Waveform::Triangle,
+    Waveform::Morph,
+];
+
+// sample type tests
+
+#[test]
+fn test_q15_drops_low_bits() {
+    assert_eq!(i16::from_q31(i32::MAX), i16::MAX);
+    assert_eq!(i16::from_q31(i32::MIN), i16::MIN);
+    assert_eq!(i16::from_q31(1 << 16), 1);
+    assert_eq!(i16::from_q31(0xffff), 0);
+}
+
+#[test]
+fn test_q15_round_trips() {
+    for sample in [i16::MIN, -1234, 0, 1, 4321, i16::MAX] {
+        assert_eq!(i16::from_q31(sample.to_q31()), sample);
+    }
+}
+
+#[test]
+fn test_q31_is_identity() {
+    for sample in [i32::MIN, -1, 0, 1, i32::MAX] {
+        assert_eq!(i32::from_q31(sample), sample);
+        assert_eq!(sample.to_q31(), sample);
+    }
+}
+
+// naive tests
+
+#[test]
+fn test_naive_at_known_phases() {
+    // 4 samples per cycle: phases 0.25, 0.5, 0.75, 0.0
+    let mut osc: FixedNaiveOsc<i32> = FixedNaiveOsc::new(4, 1 << 16, Waveform::Sine);
+    let samples: Vec<f64> = (0..4).map(|_| to_f64(osc.next_sample())).collect();
+    assert!(approx_eq(samples[0], 1.0));
+    assert!(approx_eq(samples[1], 0.0));
+    assert!(approx_eq(samples[2], -1.0));
+    assert!(approx_eq(samples[3], 0.0));
+
+    // Saw: 2 * phase - 1
+    let mut osc: FixedNaiveOsc<i32> = FixedNaiveOsc::new(4, 1 << 16, Waveform::Saw);
+    let samples: Vec<f64> = (0..4).map(|_| to_f64(osc.next_sample())).collect();
+    assert_eq!(samples, [-0.5, 0.0, 0.5, -1.0]);
+}
+
+#[test]
+fn test_naive_full_scale_saturates() {
+    // the square's 1.0 doesn't fit, so it clips to the largest sample
+    let mut osc: FixedNaiveOsc<i16> = FixedNaiveOsc::new(4, 1 << 16, Waveform::Square);
+    assert_eq!(osc.next_sample(), i16::MAX);
+    assert_eq!(osc.next_sample(), i16::MIN);
+}
+
+#[test]
+fn test_naive_matches_float() {
+    // a power-of-two sample rate keeps the increment exact in both, so
+    // samples landing right on an edge agree
+    for waveform in WAVEFORMS {
+        let mut fixed: FixedNaiveOsc<i32> = FixedNaiveOsc::new(65_536, 1000 << 16, waveform);
+        let mut float = NaiveOsc::<f64>::new(65_536.0, 1000.0, waveform);
+        fixed.set_pulse_width(u32::MAX / 10 * 3);
+        float.set_pulse_width(0.3);
+        fixed.set_shape(u32::MAX / 5 * 3);
+        float.set_shape(0.6);
+
+        for _ in 0..1000 {
+            let expected = float.next_sample();
+            assert!((to_f64(fixed.next_sample()) - expected).abs() < 1e-5);
+        }
+    }
+}
+
+#[test]
+fn test_sine_polynomial_error() {
+    let mut osc: FixedNaiveOsc<i32> = FixedNaiveOsc::new(48_000, 0, Waveform::Sine);
+
+    // sweep the phase in steps that don't line up with the quadrants
+    let mut max_error: f64 = 0.0;
+    for i in 0..10_000u32 {
+        let phase = i.wrapping_mul(429_497);
+        osc.set_phase(phase);
+        let expected = (f64::from(phase) / 4_294_967_296.0 * core::f64::consts::TAU).sin();
+        max_error = max_error.max((to_f64(osc.next_sample()) - expected).abs());
+    }
+    assert!(max_error < 1e-6, "max error {max_error}");
+}
+
+// polyBLEP tests
+
+#[test]
+fn test_poly_blep_matches_float() {
+    for waveform in WAVEFORMS {
+        let mut fixed: FixedPolyBlepOsc<i32> = FixedPolyBlepOsc::new(48_000, 1000 << 16, waveform);
+        let mut float = PolyBlepOsc::<f64>::new(48_000.0, 1000.0, waveform);
+        fixed.set_pulse_width(u32::MAX / 10 * 3);
+        float.set_pulse_width(0.3);
+        fixed.set_shape(u32::MAX / 5 * 3);
+        float.set_shape(0.6);
+
+        for _ in 0..1000 {
+            let expected = float.next_sample();
+            assert!((to_f64(fixed.next_sample()) - expected).abs() < 1e-5);
+        }
+    }
+}
+
+#[test]
+fn test_poly_blep_q15_matches_float() {
+    // an increment that's far from a whole number of samples per cycle,
+    // so the edges land at all sorts of fractions
+    for waveform in [Waveform::Saw, Waveform::Square, Waveform::Triangle] {
+        let mut fixed: FixedPolyBlepOsc<i16> =
+            FixedPolyBlepOsc::new(44_100, 3_456 << 16 | 0x8000, waveform);
+        let mut float = PolyBlepOsc::<f64>::new(44_100.0, 3_456.5, waveform);
+
+        for _ in 0..1000 {
+            let expected = float.next_sample();
+            assert!((to_f64(fixed.next_sample()) - expected).abs() < 1e-4);
+        }
+    }
+}
+
+#[test]
+fn test_poly_blep_residual_values() {
+    // a quarter cycle per sample
+    let osc: FixedPolyBlepOsc<i32> = FixedPolyBlepOsc::new(4, 1 << 16, Waveform::Saw);
+
+    assert_eq!(osc.poly_blep(0), -(1 << 31));
+    // half a sample after the edge, and half a sample before it
+    assert_eq!(osc.poly_blep(1 << 29), -(1 << 29));
+    assert_eq!(osc.poly_blep(0u32.wrapping_sub(1 << 29)), 1 << 29);
+    // more than a sample away
+    assert_eq!(osc.poly_blep(1 << 31), 0);
+}
+
+#[test]
+fn test_poly_blamp_residual_values() {
+    let osc: FixedPolyBlepOsc<i32> = FixedPolyBlepOsc::new(4, 1 << 16, Waveform::Triangle);
+
+    assert_eq!(osc.poly_blamp(0), (1 << 31) / 3);
+    assert_eq!(
+        osc.poly_blamp(1 << 29),
+        osc.poly_blamp(0u32.wrapping_sub(1 << 29))
+    );
+    assert_eq!(osc.poly_blamp(1 << 31), 0);
+}
+
+// trait impl tests
+
+#[test]
+fn test_set_frequency_changes_increment() {
+    let mut osc: FixedNaiveOsc<i32> = FixedNaiveOsc::new(4, 1 << 16, Waveform::Saw);
+    osc.set_frequency(2 << 16); // half a cycle per sample
+
+    osc.next_sample();
+    assert_eq!(osc.phase(), 1 << 31);
+}
+
+#[test]
+fn test_set_phase_increment() {
+    let mut osc: FixedPolyBlepOsc<i32> = FixedPolyBlepOsc::new(48_000, 440 << 16, Waveform::Saw);
+    let mut reference: FixedPolyBlepOsc<i32> =
+        FixedPolyBlepOsc::new(48_000, 1000 << 16, Waveform::Saw);
+    osc.set_phase_increment(((1000u64 << 32) / 48_000) as u32);
+
+    for _ in 0..100 {
+        assert_eq!(osc.next_sample(), reference.next_sample());
+    }
+}
+
+#[test]
+fn test_set_phase() {
+    let mut osc: FixedNaiveOsc<i32> = FixedNaiveOsc::new(4, 1 << 16, Waveform::Saw);
+    osc.set_phase(1 << 31);
+    assert_eq!(osc.phase(), 1 << 31);
+
+    // phase 0.75
+    assert!(approx_eq(to_f64(osc.next_sample()), 0.5));
+}
+
+#[test]
+fn test_reset_zeros_phase() {
+    let mut osc: FixedPolyBlepOsc<i16> = FixedPolyBlepOsc::new(4, 1 << 16, Waveform::Saw);
+    osc.next_sample();
+    osc.next_sample();
+    osc.reset();
+    assert_eq!(osc.phase(), 0);
+}
+
+#[test]
+fn test_phase_wraps() {
+    let mut osc: FixedNaiveOsc<i32> = FixedNaiveOsc::new(4, 1 << 16, Waveform::Saw);
+    for _ in 0..4 {
+        osc.next_sample();
+    }
+    assert_eq!(osc.phase(), 0);
+}
+
+#[test]
+fn test_fill_buffer() {
+    let mut osc: FixedNaiveOsc<i32> = FixedNaiveOsc::new(4, 1 << 16, Waveform::Saw);
+    let mut buffer = [0i32; 4];
+
+    osc.fill(&mut buffer);
+
+    assert_eq!(buffer, [-(1 << 30), 0, 1 << 30, i32::MIN]);
+}
+
+#[test]
+fn test_iterator() {
+    let osc: FixedPolyBlepOsc<i16> = FixedPolyBlepOsc::new(48_000, 440 << 16, Waveform::Saw);
+    let mut reference: FixedPolyBlepOsc<i16> =
+        FixedPolyBlepOsc::new(48_000, 440 << 16, Waveform::Saw);
+
+    for sample in osc.take(100) {
+        assert_eq!(sample, reference.next_sample());
+    }
+}
@@ -0,0 +1,284 @@
+//! Floating-point sample types the oscillators are generic over.
+
+use core::fmt::Debug;
+use core::iter::Sum;
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for f32 {}
+    impl Sealed for f64 {}
+}
+
+/// A floating-point sample type, implemented for [`f32`] and [`f64`].
+///
+/// Every oscillator is generic over its sample type, which is also the type
+/// of its frequency, phase and other parameters, and defaults to `f32`. Use
+/// `f64` for offline rendering or measurements that need the precision:
+///
+/// ```
+/// use oscy::{naive::NaiveOsc, Oscillator, Waveform};
+///
+/// let mut osc = NaiveOsc::<f64>::new(48000.0, 440.0, Waveform::Sine);
+/// let sample: f64 = osc.next_sample();
+/// ```
+///
+/// Type inference doesn't fall back to the default, so when nothing pins the
+/// sample type, float literals make it `f64`. Annotate the oscillator, e.g.
+/// `let osc: NaiveOsc = ...`, or a sample or buffer to stay in `f32`.
+///
+/// The trait is sealed, and only covers what the oscillators need.
+pub trait Float:
+    sealed::Sealed
+    + Copy
+    + Default
+    + Debug
+    + PartialOrd
+    + Send
+    + Sync
+    + 'static
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+    + AddAssign
+    + SubAssign
+    + MulAssign
+    + DivAssign
+    + Sum
+{
+    /// 0.0
+    const ZERO: Self;
+    /// 1.0
+    const ONE: Self;
+    /// 0.5
+    const HALF: Self;
+    /// 2.0
+    const TWO: Self;
+    /// Archimedes' constant, π.
+    const PI: Self;
+    /// The full circle constant, τ = 2π.
+    const TAU: Self;
+    /// The difference between 1.0 and the next larger value.
+    const EPSILON: Self;
+
+    /// Converts from an `f64`, rounding to the nearest value.
+    fn from_f64(x: f64) -> Self;
+    /// Converts from an `f32`, exactly.
+    fn from_f32(x: f32) -> Self;
+    /// Converts to an `f64`, exactly.
+    fn to_f64(self) -> f64;
+    /// Converts to an `f32`, rounding to the nearest value.
+    fn to_f32(self) -> f32;
+    /// Converts from a `usize`, rounding to the nearest value.
+    fn from_usize(n: usize) -> Self;
+    /// Converts to a `usize`, rounding towards zero and saturating at the
+    /// ends of its range.
+    fn to_usize(self) -> usize;
+    /// Maps 64 random bits to a uniform value in [0.0, 1.0), keeping as
+    /// many of the top bits as the mantissa holds.
+    fn from_unit_u64(bits: u64) -> Self;
+
+    /// See [`f32::abs`].
+    fn abs(self) -> Self;
+    /// See [`f32::floor`].
+    fn floor(self) -> Self;
+    /// See [`f32::ceil`].
+    fn ceil(self) -> Self;
+    /// See [`f32::round`].
+    fn round(self) -> Self;
+    /// See [`f32::fract`].
+    fn fract(self) -> Self;
+    /// See [`f32::rem_euclid`].
+    fn rem_euclid(self, rhs: Self) -> Self;
+    /// See [`f32::min`].
+    fn min(self, other: Self) -> Self;
+    /// See [`f32::max`].
+    fn max(self, other: Self) -> Self;
+    /// See [`f32::clamp`].
+    fn clamp(self, min: Self, max: Self) -> Self;
+    /// See [`f32::sqrt`].
+    fn sqrt(self) -> Self;
+    /// See [`f32::sin`].
+    fn sin(self) -> Self;
+    /// See [`f32::cos`].
+    fn cos(self) -> Self;
+    /// See [`f32::sin_cos`].
+    fn sin_cos(self) -> (Self, Self);
+    /// See [`f32::atan2`].
+    fn atan2(self, other: Self) -> Self;
+    /// See [`f32::hypot`].
+    fn hypot(self, other: Self) -> Self;
+    /// See [`f32::exp`].
+    fn exp(self) -> Self;
+    /// See [`f32::exp2`].
+    fn exp2(self) -> Self;
+    /// See [`f32::ln`].
+    fn ln(self) -> Self;
+    /// See [`f32::log2`].
+    fn log2(self) -> Self;
+}
+
+/// Calls the `std` method, or the `libm` function without `std`.
+#[cfg(feature = "std")]
+macro_rules! math {
+    ($t:ident::$method:ident, $libm:ident($($arg:expr),*)) => {
+        $t::$method($($arg),*)
+    };
+}
+
+#[cfg(not(feature = "std"))]
+macro_rules! math {
+    ($t:ident::$method:ident, $libm:ident($($arg:expr),*)) => {
+        libm::$libm($($arg),*)
+    };
+}
+
+macro_rules! impl_float {
+    (
+        $t:ident,
+        $abs:ident,
+        $floor:ident,
+        $ceil:ident,
+        $round:ident,
+        $trunc:ident,
+        $sqrt:ident,
+        $sin:ident,
+        $cos:ident,
+        $sincos:ident,
+        $atan2:ident,
+        $hypot:ident,
+        $exp:ident,
+        $exp2:ident,
+        $ln:ident,
+        $log2:ident
+    ) => {
+        impl Float for $t {
+            const ZERO: Self = 0.0;
+            const ONE: Self = 1.0;
+            const HALF: Self = 0.5;
+            const TWO: Self = 2.0;
+            const PI: Self = core::$t::consts::PI;
+            const TAU: Self = core::$t::consts::TAU;
+            const EPSILON: Self = $t::EPSILON;
+
+            fn from_f64(x: f64) -> Self {
+                x as $t
+            }
+
+            fn from_f32(x: f32) -> Self {
+                x as $t
+            }
+
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+
+            fn to_f32(self) -> f32 {
+                self as f32
+            }
+
+            fn from_usize(n: usize) -> Self {
+                n as $t
+            }
+
+            fn to_usize(self) -> usize {
+                self as usize
+            }
+
+            fn from_unit_u64(bits: u64) -> Self {
+                // the top bits fill the mantissa exactly
+                (bits >> (64 - $t::MANTISSA_DIGITS)) as $t / (1u64 << $t::MANTISSA_DIGITS) as $t
+            }
+
+            fn abs(self) -> Self {
+                math!($t::abs, $abs(self))
+            }
+
+            fn floor(self) -> Self {
+                math!($t::floor, $floor(self))
+            }
+
+            fn ceil(self) -> Self {
+                math!($t::ceil, $ceil(self))
+            }
+
+            fn round(self) -> Self {
+                math!($t::round, $round(self))
+            }
+
+            fn fract(self) -> Self {
+                self - math!($t::trunc, $trunc(self))
+            }
+
+            fn rem_euclid(self, rhs: Self) -> Self {
+                // same as std: the remainder, moved up when negative
+                let r = self % rhs;
+                if r < 0.0 { r + Float::abs(rhs) } else { r }
+            }
+
+            fn min(self, other: Self) -> Self {
+                $t::min(self, other)
+            }
+
+            fn max(self, other: Self) -> Self {
+                $t::max(self, other)
+            }
+
+            fn clamp(self, min: Self, max: Self) -> Self {
+                $t::clamp(self, min, max)
+            }
+
+            fn sqrt(self) -> Self {
+                math!($t::sqrt, $sqrt(self))
+            }
+
+            fn sin(self) -> Self {
+                math!($t::sin, $sin(self))
+            }
+
+            fn cos(self) -> Self {
+                math!($t::cos, $cos(self))
+            }
+
+            fn sin_cos(self) -> (Self, Self) {
+                math!($t::sin_cos, $sincos(self))
+            }
+
+            fn atan2(self, other: Self) -> Self {
+                math!($t::atan2, $atan2(self, other))
+            }
+
+            fn hypot(self, other: Self) -> Self {
+                math!($t::hypot, $hypot(self, other))
+            }
+
+            fn exp(self) -> Self {
+                math!($t::exp, $exp(self))
+            }
+
+            fn exp2(self) -> Self {
+                math!($t::exp2, $exp2(self))
+            }
+
+            fn ln(self) -> Self {
+                math!($t::ln, $ln(self))
+            }
+
+            fn log2(self) -> Self {
+                math!($t::log2, $log2(self))
+            }
+        }
+    };
+}
+
+impl_float!(
+    f32, fabsf, floorf, ceilf, roundf, truncf, sqrtf, sinf, cosf, sincosf, atan2f, hypotf, expf,
+    exp2f, logf, log2f
+);
+impl_float!(
+    f64, fabs, floor, ceil, round, trunc, sqrt, sin, cos, sincos, atan2, hypot, exp, exp2, log,
+    log2
+);
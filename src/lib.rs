@@ -1,8 +1,34 @@
 //! A library for audio oscillators, waveform generation, and noise.
 //!
 //! Provides the [`Oscillator`] trait for building audio oscillators, along with
-//! ready-to-use implementations in [`naive`] and [`poly_blep`]. For noise
-//! generation, see the [`noise`] module (requires the `noise` feature).
+//! ready-to-use implementations in [`naive`], [`poly_blep`], `min_blep`,
+//! [`blit`] and [`dpw`], wavetable playback in `wavetable`, additive
+//! synthesis in `additive`, detuned unison stacks in `unison`, SIMD
+//! banks of many voices in `bank`, all waveforms at once in [`multi`],
+//! sine/cosine pairs in [`quadrature`], cheap resonator sines in
+//! [`resonator`], phase-locked sub-oscillators in [`sub`], and hard sync
+//! between oscillators in [`sync`]. Parameter glides live in [`smooth`],
+//! and faster sine approximations in [`sine`].
+//! For noise generation, see the `noise` module (requires the `noise`
+//! feature).
+//!
+//! Every oscillator runs in `f32` by default, or in `f64` where the extra
+//! precision matters, see [`Float`]. For targets without an FPU, [`fixed`]
+//! has integer-only oscillators producing Q15 or Q31 samples.
+//!
+//! The crate is `no_std` compatible. The default `std` feature uses the
+//! standard library's math, and without it the `libm` feature provides the
+//! math instead. Only `min_blep`, `wavetable`, `additive`, `unison`
+//! and `bank` need an allocator, and they require the `alloc` feature,
+//! which `std` enables.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(not(any(feature = "std", feature = "libm")))]
+compile_error!("oscy needs either the `std` or the `libm` feature for its math");
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
 
 /// Naive oscillator implementations without anti-aliasing.
 ///
@@ -16,9 +42,96 @@ pub mod naive;
 /// near waveform discontinuities. When a waveform has a sharp transition
 /// (like the reset in a sawtooth or edges in a square wave), the correction
 /// smooths samples within one sample period of the discontinuity, reducing
-/// high-frequency artifacts.
+/// high-frequency artifacts. Slope discontinuities (the corners of a triangle
+/// wave) are smoothed the same way using the integrated polyBLAMP residual.
 pub mod poly_blep;
 
+/// Bandlimited oscillators using minimum-phase bandlimited steps (minBLEP).
+///
+/// MinBLEP replaces each discontinuity with a precomputed minimum-phase
+/// bandlimited step spanning many samples. It suppresses aliasing much
+/// further than polyBLEP at a higher cost, and its corrections are causal.
+/// Requires the `alloc` feature.
+#[cfg(feature = "alloc")]
+pub mod min_blep;
+
+/// Bandlimited oscillators using bandlimited impulse trains (BLIT).
+///
+/// BLIT draws each discontinuity as an impulse summed from every harmonic
+/// below Nyquist and integrates the impulse train into saw, square and
+/// triangle waves. The impulse train is also available on its own.
+pub mod blit;
+
+/// Bandlimited oscillators using differentiated polynomial waveforms (DPW).
+///
+/// DPW samples a polynomial whose repeated derivative is the waveform and
+/// differentiates it back with simple differences. The polynomial is
+/// smoother than the waveform, so it aliases less, at a very low cost.
+pub mod dpw;
+
+/// Wavetable oscillators with band-limited mipmaps.
+///
+/// A wavetable holds one or more cycles of any shape, each band-limited into
+/// one table per octave. Oscillators read the table whose harmonics stay
+/// below Nyquist at their frequency, and scan tables of several cycles by
+/// position. Requires the `alloc` feature, and loading WAV files the `wav`
+/// feature.
+#[cfg(feature = "alloc")]
+pub mod wavetable;
+
+/// Additive oscillators summing sine partials.
+///
+/// Every partial has its own frequency ratio, amplitude and phase, so the
+/// same oscillator builds harmonic series and inharmonic spectra such as
+/// bells. Partials above Nyquist are muted, so nothing aliases. Requires the
+/// `alloc` feature.
+#[cfg(feature = "alloc")]
+pub mod additive;
+
+/// Unison oscillators stacking detuned voices.
+///
+/// A unison oscillator plays several polyBLEP voices of one waveform,
+/// detuned around the pitch and spread across the stereo field, for
+/// supersaws and other thick leads and pads. Requires the `alloc` feature.
+#[cfg(feature = "alloc")]
+pub mod unison;
+
+/// Banks of polyBLEP oscillators processed with SIMD.
+///
+/// A bank runs many voices of one waveform, several lanes at a time, with
+/// the polyBLEP corrections masked in rather than branched on, for
+/// polyphonic synths with dozens of voices. Requires the `alloc` feature.
+#[cfg(feature = "alloc")]
+pub mod bank;
+
+/// Oscillators producing every waveform at once from one phase.
+///
+/// A multi-output oscillator computes sine, triangle, saw, square and pulse
+/// together, band-limited with polyBLEP, like the outputs of a modular
+/// oscillator core.
+pub mod multi;
+
+/// Quadrature oscillators producing sine/cosine pairs.
+///
+/// The sine and cosine are exactly 90° apart, for frequency shifters,
+/// single-sideband modulation and IQ signals. They come either straight
+/// from the phase or from a cheaper rotation recurrence.
+pub mod quadrature;
+
+/// Sine oscillators built on a recursive resonator.
+///
+/// A magic-circle resonator produces a sine with two multiply-adds per
+/// sample instead of a call to `sin`, with its amplitude corrected for
+/// drift, for running many sines cheaply.
+pub mod resonator;
+
+/// Sine evaluation strategies, trading accuracy for speed.
+///
+/// [`naive::NaiveOsc`] and [`poly_blep::PolyBlepOsc`] evaluate their sine
+/// exactly by default, or with a polynomial or a lookup table that skip the
+/// call to `sin`.
+pub mod sine;
+
 /// Noise generators for white, pink, and brown noise.
 ///
 /// Noise generators produce aperiodic signals with different spectral
@@ -26,32 +139,193 @@ pub mod poly_blep;
 #[cfg(feature = "noise")]
 pub mod noise;
 
+/// Phase-locked sub-oscillators one or two octaves below an oscillator.
+///
+/// The sub counts the wraps of the oscillator it wraps, like the flip-flop
+/// dividers of analog synths, so it never drifts out of phase. Its square,
+/// sine, saw or triangle is band-limited with polyBLEP and mixed into the
+/// output.
+pub mod sub;
+
+/// Smoothing of parameter changes, for portamento and click-free levels.
+///
+/// A smoother glides a value to each new target along a linear or
+/// exponential curve over a set time. [`naive::NaiveOsc`] and
+/// [`poly_blep::PolyBlepOsc`] use them for their frequency and amplitude.
+pub mod smooth;
+
+/// Hard sync between a master and a slave oscillator.
+///
+/// The slave restarts its cycle every time the master wraps, so its pitch
+/// follows the master while its own frequency shapes the timbre.
+pub mod sync;
+
+/// Fixed-point oscillators for targets without an FPU.
+///
+/// The phase is a 32-bit integer accumulator that wraps for free, and every
+/// sample is computed with integer arithmetic in Q31, then output as Q15
+/// (`i16`) or Q31 (`i32`). Naive and polyBLEP variants are available.
+pub mod fixed;
+
+#[cfg(feature = "alloc")]
+mod fft;
+mod float;
+#[cfg(any(feature = "alloc", feature = "noise"))]
+mod rng;
+
+pub use float::Float;
+
+#[cfg(test)]
+mod test_utils;
+
 /// A trait for audio oscillators that generate periodic waveforms.
-pub trait Oscillator {
+///
+/// Oscillators are generic over their sample type `T`, see [`Float`]. The
+/// frequency, phase and samples all use it.
+pub trait Oscillator<T: Float = f32> {
     /// Sets the oscillator frequency in hertz.
-    fn set_frequency(&mut self, hz: f32);
+    ///
+    /// Oscillators that support through-zero FM accept negative frequencies
+    /// and run the waveform backwards.
+    fn set_frequency(&mut self, hz: T);
 
     /// Sets the current phase of the oscillator.
     ///
     /// Phase is typically in the range [0.0, 1.0], where 1.0 represents
     /// a full cycle.
-    fn set_phase(&mut self, phase: f32);
+    fn set_phase(&mut self, phase: T);
+
+    /// Returns the current phase of the oscillator, in the range [0.0, 1.0).
+    fn phase(&self) -> T;
 
     /// Resets the oscillator to its initial state.
     fn reset(&mut self);
 
+    /// Returns where the phase wraps during the next step, if it does.
+    ///
+    /// The value is the fraction of the step, in [0.0, 1.0), that elapses
+    /// before the cycle restarts. Hard-sync masters report their wraps
+    /// through this, see [`sync::HardSync`]. Returns `None` by default.
+    fn next_wrap(&self) -> Option<T> {
+        None
+    }
+
+    /// Hard-syncs the oscillator to a master's wrap.
+    ///
+    /// `fraction` is the master's [`Oscillator::next_wrap`], queried right
+    /// after the master produced the sample this oscillator is about to
+    /// produce. The phase restarts at that point in the following step,
+    /// which leaves band-limited oscillators one sample to prepare the
+    /// correction on both sides of the reset.
+    ///
+    /// Oscillators that don't support hard sync ignore this by default.
+    fn sync(&mut self, _fraction: T) {}
+
+    /// Sets the waveform the oscillator plays.
+    ///
+    /// Oscillators without a [`Waveform`] ignore this by default.
+    fn set_waveform(&mut self, _waveform: Waveform) {}
+
     /// Generates and returns the next sample.
-    fn next_sample(&mut self) -> f32;
+    fn next_sample(&mut self) -> T;
 
     /// Fills a buffer with consecutive samples.
-    fn fill(&mut self, buffer: &mut [f32]) {
+    fn fill(&mut self, buffer: &mut [T]) {
         for sample in buffer.iter_mut() {
             *sample = self.next_sample();
         }
     }
+
+    /// Fills a buffer while modulating the frequency every sample.
+    ///
+    /// Each sample is generated at the matching frequency in hertz from
+    /// `frequencies`, which allows audio-rate (and through-zero) FM at block
+    /// level. The oscillator keeps the last frequency afterwards. Only as
+    /// many samples as the shorter slice holds are written.
+    fn fill_with_fm(&mut self, buffer: &mut [T], frequencies: &[T]) {
+        for (sample, &hz) in buffer.iter_mut().zip(frequencies) {
+            self.set_frequency(hz);
+            *sample = self.next_sample();
+        }
+    }
+
+    /// Fills a buffer while modulating the phase every sample.
+    ///
+    /// Each sample is read at the oscillator's phase shifted by the matching
+    /// offset from `phase_offsets`, in cycles. Offsets don't accumulate, so
+    /// the oscillator keeps running at its own frequency. Only as many
+    /// samples as the shorter slice holds are written.
+    fn fill_with_pm(&mut self, buffer: &mut [T], phase_offsets: &[T]) {
+        for (sample, &offset) in buffer.iter_mut().zip(phase_offsets) {
+            let phase = self.phase();
+            self.set_phase(phase + offset);
+            *sample = self.next_sample();
+            let phase = self.phase();
+            self.set_phase(phase - offset);
+        }
+    }
+
+    /// Fills a buffer, applying each event at its exact sample offset.
+    ///
+    /// `events` must be sorted by [`Event::offset`]. Every event takes
+    /// effect right before the sample at its offset is generated, so a
+    /// note change at offset 100 of a 512-sample block sounds from the
+    /// 101st sample on. Events at or past the end of the buffer are applied
+    /// after its last sample.
+    ///
+    /// The buffer is rendered with [`Oscillator::fill`] between events.
+    fn fill_with_events(&mut self, buffer: &mut [T], events: &[Event<T>]) {
+        let mut start = 0;
+        for event in events {
+            let offset = event.offset.clamp(start, buffer.len());
+            self.fill(&mut buffer[start..offset]);
+            event.apply(self);
+            start = offset;
+        }
+        self.fill(&mut buffer[start..]);
+    }
+}
+
+/// A parameter change at a sample offset within a block, see
+/// [`Oscillator::fill_with_events`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Event<T: Float = f32> {
+    /// The sample the change applies from, counted from the start of the
+    /// block.
+    pub offset: usize,
+    /// The change itself.
+    pub kind: EventKind<T>,
+}
+
+impl<T: Float> Event<T> {
+    /// Creates an event applying `kind` at `offset`.
+    pub fn new(offset: usize, kind: EventKind<T>) -> Self {
+        Self { offset, kind }
+    }
+
+    /// Applies the change to an oscillator right away.
+    pub fn apply<O: Oscillator<T> + ?Sized>(&self, osc: &mut O) {
+        match self.kind {
+            EventKind::Frequency(hz) => osc.set_frequency(hz),
+            EventKind::ResetPhase => osc.set_phase(T::ZERO),
+            EventKind::Waveform(waveform) => osc.set_waveform(waveform),
+        }
+    }
+}
+
+/// The parameter changes an [`Event`] can carry.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EventKind<T: Float = f32> {
+    /// Sets the frequency in hertz, see [`Oscillator::set_frequency`].
+    Frequency(T),
+    /// Restarts the cycle at phase 0.0, e.g. for a new note.
+    ResetPhase,
+    /// Switches the waveform, see [`Oscillator::set_waveform`].
+    Waveform(Waveform),
 }
 
 /// Standard waveform shapes for oscillators.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Waveform {
     /// A pure sinusoidal wave. Produces no harmonics.
     Sine,
@@ -59,6 +333,73 @@ pub enum Waveform {
     Saw,
     /// A square wave alternating between high and low values.
     Square,
+    /// A rectangular wave that stays high for a settable fraction of the
+    /// cycle (the pulse width). A pulse width of 0.5 matches [`Waveform::Square`].
+    Pulse,
     /// A triangle wave with linear slopes in both directions.
     Triangle,
+    /// A continuous blend of sine, triangle, saw and square, selected by the
+    /// oscillator's morph shape.
+    Morph,
+}
+
+/// Returns where the cycle restarts during a step of `phase_increment`
+/// from `phase`, as a fraction of the step, see [`Oscillator::next_wrap`].
+pub(crate) fn next_wrap<T: Float>(phase: T, phase_increment: T) -> Option<T> {
+    let next = phase + phase_increment;
+    if next >= T::ONE {
+        Some((T::ONE - phase) / phase_increment)
+    } else if next < T::ZERO {
+        // running backwards, the cycle restarts when crossing zero
+        Some(phase / -phase_increment)
+    } else {
+        None
+    }
+}
+
+/// Returns the largest phase below 1.0, the last point of a cycle.
+pub(crate) fn cycle_end<T: Float>() -> T {
+    T::ONE - T::EPSILON / T::TWO
+}
+
+/// Wraps any phase into [0.0, 1.0).
+pub(crate) fn wrap<T: Float>(phase: T) -> T {
+    let phase = phase.rem_euclid(T::ONE);
+    // rem_euclid can round tiny negative phases up to exactly 1.0, which
+    // still belong to the end of the cycle
+    if phase >= T::ONE { cycle_end() } else { phase }
+}
+
+/// Moves a phase in [0.0, 1.0) by at most one cycle, wrapping it back into
+/// [0.0, 1.0).
+pub(crate) fn advance_phase<T: Float>(phase: T, increment: T) -> T {
+    let phase = phase + increment;
+
+    // subtraction only when needed.
+    // cheaper than fract()/modulo every sample
+    if phase >= T::ONE {
+        phase - T::ONE
+    } else if phase < T::ZERO {
+        // negative frequencies run the phase backwards. A tiny negative
+        // phase rounds up to exactly 1.0, which is still the end of the cycle
+        (phase + T::ONE).min(cycle_end())
+    } else {
+        phase
+    }
+}
+
+/// Returns the crossfade weights of sine, triangle, saw and square for a
+/// morph `shape` in [0.0, 1.0].
+///
+/// The shapes sit at 0, 1/3, 2/3 and 1, and at most two neighbours have a
+/// non-zero weight. Weights always sum to 1.
+pub(crate) fn morph_weights<T: Float>(shape: T) -> [T; 4] {
+    let position = shape.clamp(T::ZERO, T::ONE) * T::from_f64(3.0);
+    let segment = position.to_usize().min(2);
+    let blend = position - T::from_usize(segment);
+
+    let mut weights = [T::ZERO; 4];
+    weights[segment] = T::ONE - blend;
+    weights[segment + 1] = blend;
+    weights
 }
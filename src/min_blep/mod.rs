@@ -0,0 +1,592 @@
+#[cfg(test)]
+mod tests;
+
+use alloc::{boxed::Box, vec, vec::Vec};
+use core::f64::consts::PI;
+use core::ptr;
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+use crate::{
+    Float, Oscillator, Waveform, advance_phase, cycle_end,
+    fft::fft,
+    morph_weights,
+    naive::{naive_pulse, naive_saw, naive_sine, naive_triangle},
+    next_wrap, wrap,
+};
+
+/// Zero crossings of the windowed sinc on each side of its centre.
+const ZERO_CROSSINGS: usize = 16;
+/// Table points per output sample.
+const OVERSAMPLING: usize = 64;
+/// Length of each correction in output samples.
+const LENGTH: usize = 2 * ZERO_CROSSINGS;
+/// Cutoff of the step relative to Nyquist.
+const CUTOFF: f64 = 0.9;
+/// FFT size used for the cepstrum, zero-padded well past the impulse.
+const FFT_SIZE: usize = 8192;
+/// Steps remembered for the delayed read, enough to cover the table delay.
+const HISTORY: usize = 4;
+
+/// A bandlimited oscillator using minimum-phase bandlimited steps (minBLEP).
+///
+/// Each discontinuity is replaced by a precomputed minimum-phase step (and
+/// its integral for slope discontinuities) spanning 32 samples, which
+/// suppresses aliasing far better than the 2-sample polyBLEP residual.
+/// Corrections are causal, so they are accumulated into a short buffer and
+/// added to upcoming samples.
+///
+/// A minimum-phase step settles with a small delay, so the output lags the
+/// oscillator phase by [`MinBlepOsc::latency`] samples (under 3). The same
+/// step also rings more than a linear-phase one, so edges overshoot by up
+/// to about 40% of their height.
+///
+/// # Example
+///
+/// ```
+/// use oscy::{min_blep::MinBlepOsc, Oscillator, Waveform};
+///
+/// let mut osc = MinBlepOsc::new(44100.0, 440.0, Waveform::Saw);
+/// let sample: f32 = osc.next_sample();
+/// assert!(sample.abs() < 1.5);
+/// ```
+pub struct MinBlepOsc<T: Float = f32> {
+    phase: T,
+    phase_increment: T,
+    sample_rate: T,
+    waveform: Waveform,
+    pulse_width: T,
+    shape: T,
+    table: &'static Table,
+    // pending corrections for the upcoming samples, read at `index`
+    residual: [T; LENGTH],
+    index: usize,
+    // master wrap reported by `sync`, applied during the step after the next sample
+    sync_next: Option<T>,
+    // fraction of the coming step at which a sync reset happens
+    sync_reset: Option<T>,
+    // latest steps taken by the phase, read back at `history_index`
+    history: [Step<T>; HISTORY],
+    history_index: usize,
+    // whether the correction buffer has been filled for the current settings
+    primed: bool,
+}
+
+impl<T: Float> MinBlepOsc<T> {
+    /// Creates a new minBLEP oscillator.
+    ///
+    /// The first oscillator builds the minimum-phase step table, which
+    /// takes a few FFTs, and every later one shares it.
+    pub fn new(sample_rate: T, frequency: T, waveform: Waveform) -> Self {
+        Self {
+            phase: T::ZERO,
+            phase_increment: frequency / sample_rate,
+            sample_rate,
+            waveform,
+            pulse_width: T::HALF,
+            shape: T::ZERO,
+            table: Table::shared(),
+            residual: [T::ZERO; LENGTH],
+            index: 0,
+            sync_next: None,
+            sync_reset: None,
+            history: [Step::default(); HISTORY],
+            history_index: 0,
+            primed: false,
+        }
+    }
+
+    /// Sets the pulse width used by [`Waveform::Pulse`].
+    ///
+    /// The width is the fraction of the cycle spent high, clamped to
+    /// [0.0, 1.0].
+    pub fn set_pulse_width(&mut self, width: T) {
+        self.pulse_width = width.clamp(T::ZERO, T::ONE);
+    }
+
+    /// Sets the morph shape used by [`Waveform::Morph`].
+    ///
+    /// The shape is clamped to [0.0, 1.0] and moves through sine (0.0),
+    /// triangle (1/3), saw (2/3) and square (1.0).
+    pub fn set_shape(&mut self, shape: T) {
+        self.shape = shape.clamp(T::ZERO, T::ONE);
+    }
+
+    /// Returns the delay of the output behind the oscillator phase, in
+    /// samples.
+    pub fn latency(&self) -> T {
+        T::from_f64(self.table.delay)
+    }
+
+    /// Fills the correction buffer as if the oscillator had been running
+    /// before the current phase, so the first samples don't glitch.
+    fn prime(&mut self) {
+        self.primed = true;
+        let phase = self.phase;
+        let sync_next = self.sync_next.take();
+        self.phase = wrap(phase - T::from_usize(LENGTH) * self.phase_increment);
+
+        for _ in 0..LENGTH {
+            self.next_sample();
+        }
+
+        // drop the rounding picked up along the way
+        self.phase = phase;
+        self.sync_next = sync_next;
+    }
+
+    /// Evaluates the uncorrected waveform at an arbitrary phase.
+    fn naive(&self, phase: T) -> T {
+        match self.waveform {
+            Waveform::Sine => naive_sine(phase),
+            Waveform::Saw => naive_saw(phase),
+            Waveform::Square => naive_pulse(phase, T::HALF),
+            Waveform::Pulse => naive_pulse(phase, self.pulse_width),
+            Waveform::Triangle => naive_triangle(phase),
+            Waveform::Morph => {
+                let [sine, triangle, saw, square] = morph_weights(self.shape);
+                sine * naive_sine(phase)
+                    + triangle * naive_triangle(phase)
+                    + saw * naive_saw(phase)
+                    + square * naive_pulse(phase, T::HALF)
+            }
+        }
+    }
+
+    /// Evaluates the slope of the uncorrected waveform, per unit of phase.
+    fn naive_slope(&self, phase: T) -> T {
+        match self.waveform {
+            Waveform::Sine => slope_sine(phase),
+            Waveform::Saw => T::TWO,
+            Waveform::Square | Waveform::Pulse => T::ZERO,
+            Waveform::Triangle => slope_triangle(phase),
+            Waveform::Morph => {
+                let [sine, triangle, saw, _] = morph_weights(self.shape);
+                sine * slope_sine(phase) + triangle * slope_triangle(phase) + saw * T::TWO
+            }
+        }
+    }
+
+    /// Adds corrections for every discontinuity the phase crosses while
+    /// moving by `delta` from `from`, during the part of the step that
+    /// starts at `start` and lasts `span` (both fractions of the step).
+    fn add_crossings(&mut self, from: T, delta: T, start: T, span: T) {
+        let segment = Segment {
+            from,
+            delta,
+            start,
+            span,
+        };
+
+        match self.waveform {
+            Waveform::Sine => {}
+            Waveform::Saw => self.step_at(&segment, T::ZERO, -T::TWO),
+            Waveform::Square => {
+                self.step_at(&segment, T::ZERO, T::TWO);
+                self.step_at(&segment, T::HALF, -T::TWO);
+            }
+            Waveform::Pulse => {
+                self.step_at(&segment, T::ZERO, T::TWO);
+                self.step_at(&segment, self.pulse_width, -T::TWO);
+            }
+            Waveform::Triangle => {
+                self.corner_at(&segment, T::ZERO, T::from_f64(8.0));
+                self.corner_at(&segment, T::HALF, -T::from_f64(8.0));
+            }
+            Waveform::Morph => {
+                let [_, triangle, saw, square] = morph_weights(self.shape);
+                if triangle > T::ZERO {
+                    self.corner_at(&segment, T::ZERO, T::from_f64(8.0) * triangle);
+                    self.corner_at(&segment, T::HALF, -T::from_f64(8.0) * triangle);
+                }
+                if saw > T::ZERO || square > T::ZERO {
+                    self.step_at(&segment, T::ZERO, T::TWO * (square - saw));
+                    self.step_at(&segment, T::HALF, -T::TWO * square);
+                }
+            }
+        }
+    }
+
+    /// Adds a step correction if `segment` crosses phase `at`, where the
+    /// waveform jumps by `height` (running forwards).
+    fn step_at(&mut self, segment: &Segment<T>, at: T, height: T) {
+        if let Some(since) = segment.crossing(at) {
+            // running backwards, the same edge is crossed the other way
+            let height = if segment.delta >= T::ZERO {
+                height
+            } else {
+                -height
+            };
+            self.add_step(since, height);
+        }
+    }
+
+    /// Adds a ramp correction if `segment` crosses phase `at`, where the
+    /// waveform's slope changes by `slope_change` per unit of phase.
+    fn corner_at(&mut self, segment: &Segment<T>, at: T, slope_change: T) {
+        if let Some(since) = segment.crossing(at) {
+            // mirroring both slope and time keeps the sign when running backwards
+            self.add_ramp(since, slope_change * self.phase_increment.abs());
+        }
+    }
+
+    /// Accumulates a step of `height` that happened `since` samples before
+    /// the current sample.
+    fn add_step(&mut self, since: T, height: T) {
+        for k in 0..LENGTH {
+            let slot = (self.index + k) % LENGTH;
+            self.residual[slot] += height * self.table.step_residual(since + T::from_usize(k));
+        }
+    }
+
+    /// Accumulates a slope change of `slope` per sample that happened
+    /// `since` samples before the current sample.
+    fn add_ramp(&mut self, since: T, slope: T) {
+        for k in 0..LENGTH {
+            let slot = (self.index + k) % LENGTH;
+            self.residual[slot] += slope * self.table.ramp_residual(since + T::from_usize(k));
+        }
+    }
+
+    /// Restarts the cycle `fraction` of the way through the current step.
+    fn sync_step(&mut self, fraction: T) {
+        let inc = self.phase_increment;
+        let from = self.phase;
+        self.add_crossings(from, fraction * inc, T::ZERO, fraction);
+
+        let before = wrap(from + fraction * inc);
+        let start = restart(inc);
+        let since = T::ONE - fraction;
+        self.add_step(since, self.naive(start) - self.naive(before));
+        self.add_ramp(
+            since,
+            (self.naive_slope(start) - self.naive_slope(before)) * inc,
+        );
+
+        self.add_crossings(start, since * inc, fraction, since);
+        self.phase = wrap(start + since * inc);
+    }
+
+    /// Returns the phase the output currently shows, which lags the
+    /// oscillator phase by the table delay.
+    ///
+    /// The phase is looked up in the steps actually taken, so the delayed
+    /// waveform crosses each discontinuity exactly when its correction
+    /// expects it, even under modulation or sync.
+    fn delayed_phase(&self) -> T {
+        let delay = T::from_f64(self.table.delay);
+        let back = delay.to_usize();
+
+        let step = self.history[(self.history_index + HISTORY - back) % HISTORY];
+        step.phase_at(T::ONE - (delay - T::from_usize(back)))
+    }
+}
+
+impl<T: Float> Oscillator<T> for MinBlepOsc<T> {
+    fn set_frequency(&mut self, hz: T) {
+        self.phase_increment = hz / self.sample_rate
+    }
+
+    fn set_phase(&mut self, phase: T) {
+        self.phase = wrap(phase);
+    }
+
+    fn phase(&self) -> T {
+        self.phase
+    }
+
+    fn reset(&mut self) {
+        self.phase = T::ZERO;
+        self.residual = [T::ZERO; LENGTH];
+        self.index = 0;
+        self.sync_next = None;
+        self.sync_reset = None;
+        self.history = [Step::default(); HISTORY];
+        self.history_index = 0;
+        self.primed = false;
+    }
+
+    /// Switches the waveform. Corrections already pending for the previous
+    /// waveform still play out over the next few samples.
+    fn set_waveform(&mut self, waveform: Waveform) {
+        self.waveform = waveform;
+    }
+
+    fn next_wrap(&self) -> Option<T> {
+        if let Some(fraction) = self.sync_reset {
+            return Some(fraction);
+        }
+
+        next_wrap(self.phase, self.phase_increment)
+    }
+
+    fn sync(&mut self, fraction: T) {
+        self.sync_next = Some(fraction.clamp(T::ZERO, T::ONE));
+    }
+
+    fn next_sample(&mut self) -> T {
+        // priming waits for the first sample so it picks up the settings
+        // made after construction
+        if !self.primed {
+            self.prime();
+        }
+
+        let reset = self.sync_reset.take();
+        self.history_index = (self.history_index + 1) % HISTORY;
+        self.history[self.history_index] = Step {
+            from: self.phase,
+            increment: self.phase_increment,
+            reset,
+        };
+
+        match reset {
+            Some(fraction) => self.sync_step(fraction),
+            None => {
+                let from = self.phase;
+                self.add_crossings(from, self.phase_increment, T::ZERO, T::ONE);
+                self.phase = advance_phase(from, self.phase_increment);
+            }
+        }
+        // minBLEP corrections are causal, so the reset can wait for its step
+        self.sync_reset = self.sync_next.take();
+
+        let correction = self.residual[self.index];
+        self.residual[self.index] = T::ZERO;
+        self.index = (self.index + 1) % LENGTH;
+
+        self.naive(self.delayed_phase()) + correction
+    }
+}
+
+impl<T: Float> Iterator for MinBlepOsc<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        Some(self.next_sample())
+    }
+}
+
+/// A step taken by the phase, kept for the delayed read.
+#[derive(Clone, Copy, Default)]
+struct Step<T: Float> {
+    from: T,
+    increment: T,
+    // fraction of the step at which a sync reset happened
+    reset: Option<T>,
+}
+
+impl<T: Float> Step<T> {
+    /// Returns the phase `position` of the way through the step.
+    fn phase_at(&self, position: T) -> T {
+        match self.reset {
+            Some(fraction) if position >= fraction => {
+                wrap(restart(self.increment) + (position - fraction) * self.increment)
+            }
+            _ => wrap(self.from + position * self.increment),
+        }
+    }
+}
+
+/// Part of a step during which the phase moves by `delta` from `from`.
+struct Segment<T: Float> {
+    from: T,
+    delta: T,
+    start: T,
+    span: T,
+}
+
+impl<T: Float> Segment<T> {
+    /// Returns how many samples before the end of the step the phase
+    /// crossed `at`, if it did.
+    ///
+    /// Works on the unwrapped end phase, so a phase landing exactly on a
+    /// discontinuity agrees with the waveform value read there: the
+    /// waveforms take their new value at the discontinuity itself.
+    fn crossing(&self, at: T) -> Option<T> {
+        if self.delta == T::ZERO {
+            return None;
+        }
+        let end = self.from + self.delta;
+
+        let distance = if self.delta >= T::ZERO {
+            let at = if at > self.from { at } else { at + T::ONE };
+            if at > end {
+                return None;
+            }
+            at - self.from
+        } else {
+            let at = if at <= self.from { at } else { at - T::ONE };
+            if at <= end {
+                return None;
+            }
+            self.from - at
+        };
+
+        Some(T::ONE - (self.start + self.span * distance / self.delta.abs()))
+    }
+}
+
+/// The table shared by every oscillator, null until the first one builds it.
+static SHARED: AtomicPtr<Table> = AtomicPtr::new(ptr::null_mut());
+
+/// Minimum-phase bandlimited step and ramp, sampled at `OVERSAMPLING`
+/// points per output sample.
+struct Table {
+    step: Vec<f64>,
+    ramp: Vec<f64>,
+    delay: f64,
+}
+
+impl Table {
+    /// Returns the shared table, building it on first use.
+    fn shared() -> &'static Table {
+        let table = SHARED.load(Ordering::Acquire);
+        if !table.is_null() {
+            // SAFETY: the pointer only ever holds a leaked table, which is
+            // never freed
+            return unsafe { &*table };
+        }
+
+        // oscillators created at the same time on several threads may each
+        // build one, and all but the first to publish theirs drop it again
+        let built = Box::into_raw(Box::new(Table::new()));
+        match SHARED.compare_exchange(ptr::null_mut(), built, Ordering::AcqRel, Ordering::Acquire) {
+            // SAFETY: published, the table is never freed
+            Ok(_) => unsafe { &*built },
+            Err(existing) => {
+                // SAFETY: `built` came from `Box::into_raw` and was never
+                // published, and `existing` is a leaked table as above
+                drop(unsafe { Box::from_raw(built) });
+                unsafe { &*existing }
+            }
+        }
+    }
+
+    fn new() -> Self {
+        let impulse = min_phase_impulse();
+        let len = LENGTH * OVERSAMPLING + 1;
+
+        // integrate the impulse into a step settling at exactly 1
+        let mut step = Vec::with_capacity(len);
+        let mut sum = 0.0;
+        for &x in impulse.iter().take(len) {
+            sum += x;
+            step.push(sum);
+        }
+        let total = sum;
+        step.iter_mut().for_each(|x| *x /= total);
+
+        // integrate the step into a ramp, in units of samples
+        let dx = 1.0 / OVERSAMPLING as f64;
+        let mut ramp = Vec::with_capacity(len);
+        let mut area = 0.0;
+        ramp.push(0.0);
+        for pair in step.windows(2) {
+            area += 0.5 * (pair[0] + pair[1]) * dx;
+            ramp.push(area);
+        }
+
+        // a ramp through the filter lags the ideal ramp by the step's
+        // centroid, which is where the naive waveform is read
+        let delay = LENGTH as f64 - area;
+
+        Self { step, ramp, delay }
+    }
+
+    /// Bandlimited step minus the ideal step delayed by the table delay,
+    /// `t` samples after the discontinuity.
+    fn step_residual<T: Float>(&self, t: T) -> T {
+        let ideal = if t >= T::from_f64(self.delay) {
+            T::ONE
+        } else {
+            T::ZERO
+        };
+        interpolate(&self.step, t).map_or(T::ZERO, |step| step - ideal)
+    }
+
+    /// Bandlimited ramp minus the ideal ramp delayed by the table delay,
+    /// `t` samples after the slope discontinuity.
+    fn ramp_residual<T: Float>(&self, t: T) -> T {
+        let ideal = (t - T::from_f64(self.delay)).max(T::ZERO);
+        interpolate(&self.ramp, t).map_or(T::ZERO, |ramp| ramp - ideal)
+    }
+}
+
+/// Linearly interpolates `table` at `t` samples, or returns `None` past its
+/// end, where the bandlimited shapes have settled onto the ideal ones.
+fn interpolate<T: Float>(table: &[f64], t: T) -> Option<T> {
+    let position = t * T::from_usize(OVERSAMPLING);
+    let i = position.to_usize();
+    if i + 1 >= table.len() {
+        return None;
+    }
+    let frac = position - T::from_usize(i);
+    let (a, b) = (T::from_f64(table[i]), T::from_f64(table[i + 1]));
+    Some(a + (b - a) * frac)
+}
+
+/// Builds a minimum-phase version of a Blackman-windowed sinc impulse
+/// using the real cepstrum.
+fn min_phase_impulse() -> Vec<f64> {
+    let taps = 2 * ZERO_CROSSINGS * OVERSAMPLING + 1;
+    let mut re = vec![0.0; FFT_SIZE];
+    let mut im = vec![0.0; FFT_SIZE];
+
+    for (i, x) in re.iter_mut().take(taps).enumerate() {
+        let t = (i as f64 / OVERSAMPLING as f64 - ZERO_CROSSINGS as f64) * CUTOFF;
+        let sinc = if t == 0.0 {
+            1.0
+        } else {
+            (PI * t).sin() / (PI * t)
+        };
+        let w = 2.0 * PI * i as f64 / (taps - 1) as f64;
+        let window = 0.42 - 0.5 * w.cos() + 0.08 * (2.0 * w).cos();
+        *x = sinc * window;
+    }
+
+    // real cepstrum: inverse FFT of the log magnitude
+    fft(&mut re, &mut im, false);
+    for (r, i) in re.iter_mut().zip(im.iter_mut()) {
+        *r = (*r * *r + *i * *i).sqrt().max(1e-12).ln();
+        *i = 0.0;
+    }
+    fft(&mut re, &mut im, true);
+
+    // fold the anti-causal part onto the causal part
+    re[1..FFT_SIZE / 2].iter_mut().for_each(|x| *x *= 2.0);
+    re[FFT_SIZE / 2 + 1..].iter_mut().for_each(|x| *x = 0.0);
+    im.iter_mut().for_each(|x| *x = 0.0);
+
+    // back to a spectrum, exponentiate, and return to the time domain
+    fft(&mut re, &mut im, false);
+    for (r, i) in re.iter_mut().zip(im.iter_mut()) {
+        let magnitude = r.exp();
+        let (sin, cos) = i.sin_cos();
+        *r = magnitude * cos;
+        *i = magnitude * sin;
+    }
+    fft(&mut re, &mut im, true);
+
+    re
+}
+
+/// Returns the phase a sync reset restarts the cycle from. Running
+/// backwards, the restarted cycle continues from its end.
+fn restart<T: Float>(phase_increment: T) -> T {
+    if phase_increment >= T::ZERO {
+        T::ZERO
+    } else {
+        cycle_end()
+    }
+}
+
+fn slope_sine<T: Float>(phase: T) -> T {
+    T::TAU * (phase * T::TAU).cos()
+}
+
+fn slope_triangle<T: Float>(phase: T) -> T {
+    if phase < T::HALF {
+        T::from_f64(4.0)
+    } else {
+        -T::from_f64(4.0)
+    }
+}
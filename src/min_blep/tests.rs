@@ -0,0 +1,278 @@
+use std::f32::consts::TAU;
+
+use super::MinBlepOsc;
+use crate::{
+    Event, EventKind, Oscillator, Waveform,
+    naive::NaiveOsc,
+    poly_blep::PolyBlepOsc,
+    sync::HardSync,
+    test_utils::{ANALYSIS_LEN, aliasing_ratio, analysis_frequency},
+};
+
+const EPSILON: f32 = 1e-5;
+
+fn approx_eq(a: f32, b: f32) -> bool {
+    (a - b).abs() < EPSILON
+}
+
+const WAVEFORMS: [Waveform; 6] = [
+    Waveform::Sine,
+    Waveform::Saw,
+    Waveform::Square,
+    Waveform::Pulse,
+    Waveform::Triangle,
+    Waveform::Morph,
+];
+
+fn min_blep(frequency: f32, waveform: Waveform) -> MinBlepOsc {
+    let mut osc = MinBlepOsc::new(44100.0, frequency, waveform);
+    osc.set_pulse_width(0.3);
+    osc.set_shape(0.5);
+    osc
+}
+
+fn poly_blep(frequency: f32, waveform: Waveform) -> PolyBlepOsc {
+    let mut osc = PolyBlepOsc::new(44100.0, frequency, waveform);
+    osc.set_pulse_width(0.3);
+    osc.set_shape(0.5);
+    osc
+}
+
+#[test]
+fn test_latency_is_a_few_samples() {
+    let osc = MinBlepOsc::new(44100.0, 440.0, Waveform::Saw);
+    assert!(osc.latency() > 1.0 && osc.latency() < 4.0);
+}
+
+#[test]
+fn test_sine_matches_delayed_naive() {
+    let mut osc = MinBlepOsc::new(100.0, 5.0, Waveform::Sine);
+    let mut naive = NaiveOsc::new(100.0, 5.0, Waveform::Sine);
+    naive.set_phase(-osc.latency() * 0.05);
+
+    for _ in 0..100 {
+        assert!(approx_eq(osc.next_sample(), naive.next_sample()));
+    }
+}
+
+#[test]
+fn test_saw_settles_onto_delayed_naive() {
+    // 100 samples per cycle, so the middle of the ramp is far from the reset
+    let mut osc = MinBlepOsc::new(100.0, 1.0, Waveform::Saw);
+    let mut naive = NaiveOsc::new(100.0, 1.0, Waveform::Saw);
+    naive.set_phase(-osc.latency() * 0.01);
+
+    let samples: Vec<f32> = (0..100).map(|_| osc.next_sample()).collect();
+    let naive_samples: Vec<f32> = (0..100).map(|_| naive.next_sample()).collect();
+
+    for i in 40..90 {
+        assert!((samples[i] - naive_samples[i]).abs() < 1e-3);
+    }
+}
+
+#[test]
+fn test_output_bounded() {
+    for waveform in WAVEFORMS {
+        let mut osc = min_blep(2000.0, waveform);
+
+        for _ in 0..2000 {
+            assert!(osc.next_sample().abs() < 1.5);
+        }
+    }
+}
+
+#[test]
+fn test_reset_restarts_output() {
+    let mut osc = MinBlepOsc::new(44100.0, 3000.0, Waveform::Square);
+    let first: Vec<f32> = (0..64).map(|_| osc.next_sample()).collect();
+
+    osc.reset();
+    let second: Vec<f32> = (0..64).map(|_| osc.next_sample()).collect();
+
+    for (a, b) in first.iter().zip(&second) {
+        assert!(approx_eq(*a, *b));
+    }
+}
+
+#[test]
+fn test_through_zero_fm_stays_bounded() {
+    for waveform in WAVEFORMS {
+        let mut osc = min_blep(0.0, waveform);
+
+        for i in 0..10_000 {
+            let modulator = (i as f32 / 300.0 * TAU).sin();
+            osc.set_frequency(200.0 + 5000.0 * modulator);
+            // closely spaced edges at the top of the sweep add up their ringing
+            assert!(osc.next_sample().abs() < 2.0);
+        }
+    }
+}
+
+#[test]
+fn test_aliases_less_than_poly_blep() {
+    // about 650Hz, where polyBLEP still leaves measurable aliasing
+    let cycles = 61;
+    let frequency = analysis_frequency(44100.0, cycles);
+
+    for waveform in WAVEFORMS.into_iter().skip(1) {
+        let min: Vec<f32> = min_blep(frequency, waveform).take(ANALYSIS_LEN).collect();
+        let poly: Vec<f32> = poly_blep(frequency, waveform).take(ANALYSIS_LEN).collect();
+
+        let min = aliasing_ratio(&min, cycles);
+        let poly = aliasing_ratio(&poly, cycles);
+        assert!(min < poly * 0.01, "{waveform:?}: {min} vs {poly}");
+    }
+}
+
+#[test]
+fn test_negative_frequency_aliases_less_than_poly_blep() {
+    let cycles = 61;
+    let frequency = -analysis_frequency(44100.0, cycles);
+
+    for waveform in [Waveform::Saw, Waveform::Square, Waveform::Triangle] {
+        let min: Vec<f32> = min_blep(frequency, waveform).take(ANALYSIS_LEN).collect();
+        let poly: Vec<f32> = poly_blep(frequency, waveform).take(ANALYSIS_LEN).collect();
+
+        assert!(aliasing_ratio(&min, cycles) < aliasing_ratio(&poly, cycles) * 0.01);
+    }
+}
+
+#[test]
+fn test_hard_sync_aliases_less_than_poly_blep() {
+    let cycles = 61;
+    let frequency = analysis_frequency(44100.0, cycles);
+
+    for ratio in [2.37, -2.37] {
+        for waveform in [
+            Waveform::Saw,
+            Waveform::Sine,
+            Waveform::Square,
+            Waveform::Triangle,
+        ] {
+            let min = HardSync::new(
+                NaiveOsc::new(44100.0, frequency, Waveform::Saw),
+                min_blep(frequency * ratio, waveform),
+            );
+            let poly = HardSync::new(
+                NaiveOsc::new(44100.0, frequency, Waveform::Saw),
+                poly_blep(frequency * ratio, waveform),
+            );
+
+            // the slave is primed as if it had been running free, so skip
+            // the start until the corrections follow the synced cycle
+            let min: Vec<f32> = min.skip(1000).take(ANALYSIS_LEN).collect();
+            let poly: Vec<f32> = poly.skip(1000).take(ANALYSIS_LEN).collect();
+
+            let min = aliasing_ratio(&min, cycles);
+            let poly = aliasing_ratio(&poly, cycles);
+            assert!(min < poly * 0.01, "{ratio} {waveform:?}: {min} vs {poly}");
+        }
+    }
+}
+
+#[test]
+fn test_hard_sync_on_slave_edge_matches_free_running() {
+    // the slave wraps exactly when it's reset, so sync changes nothing
+    let frequency = analysis_frequency(44100.0, 61);
+
+    for waveform in [Waveform::Saw, Waveform::Square] {
+        let mut synced = HardSync::new(
+            NaiveOsc::new(44100.0, frequency, Waveform::Saw),
+            MinBlepOsc::new(44100.0, frequency * 2.0, waveform),
+        );
+        let mut free = MinBlepOsc::new(44100.0, frequency * 2.0, waveform);
+
+        for _ in 0..10_000 {
+            assert!((synced.next_sample() - free.next_sample()).abs() < 1e-3);
+        }
+    }
+}
+
+#[test]
+fn test_oscillators_share_one_table() {
+    let a = MinBlepOsc::<f32>::new(44100.0, 440.0, Waveform::Saw);
+    let b = MinBlepOsc::<f64>::new(48000.0, 110.0, Waveform::Square);
+    assert!(std::ptr::eq(a.table, b.table));
+}
+
+#[test]
+fn test_tiny_negative_phase_stays_below_one() {
+    // a step from 1e-9 back by 2e-9 lands at -1e-9, which rounds to 1.0
+    let mut osc: MinBlepOsc = MinBlepOsc::new(1.0, -2e-9, Waveform::Saw);
+    osc.set_phase(1e-9);
+    osc.next_sample();
+    assert!(osc.phase() < 1.0);
+
+    osc.set_phase(-1e-9);
+    assert!(osc.phase() < 1.0);
+}
+
+// sample type tests
+
+#[test]
+fn test_f64_matches_f32() {
+    let mut single = MinBlepOsc::<f32>::new(44100.0, 1234.0, Waveform::Triangle);
+    let mut double = MinBlepOsc::<f64>::new(44100.0, 1234.0, Waveform::Triangle);
+
+    for _ in 0..1000 {
+        assert!((single.next_sample() as f64 - double.next_sample()).abs() < 1e-3);
+    }
+}
+
+// trait impl tests
+
+#[test]
+fn test_waveform_event_switches_waveform() {
+    let mut osc: MinBlepOsc = MinBlepOsc::new(44100.0, 441.0, Waveform::Saw);
+    for _ in 0..100 {
+        osc.next_sample();
+    }
+    let mut expected: MinBlepOsc = MinBlepOsc::new(44100.0, 441.0, Waveform::Square);
+    expected.set_phase(osc.phase());
+
+    let mut buffer = [0.0; 64];
+    osc.fill_with_events(
+        &mut buffer,
+        &[Event::new(0, EventKind::Waveform(Waveform::Square))],
+    );
+
+    for sample in buffer {
+        assert!((sample - expected.next_sample()).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn test_set_phase() {
+    let mut osc = MinBlepOsc::new(4.0, 1.0, Waveform::Sine);
+    osc.set_phase(0.6);
+    assert!(approx_eq(osc.phase(), 0.6));
+}
+
+#[test]
+fn test_next_wrap_reports_fraction() {
+    let mut osc = MinBlepOsc::new(10.0, 3.0, Waveform::Saw); // inc = 0.3
+    osc.set_phase(0.9);
+
+    let fraction: f32 = osc.next_wrap().unwrap();
+    assert!((fraction - 1.0 / 3.0).abs() < 1e-5);
+}
+
+#[test]
+fn test_fill_buffer() {
+    let mut osc = MinBlepOsc::new(44100.0, 440.0, Waveform::Saw);
+    let mut reference = MinBlepOsc::new(44100.0, 440.0, Waveform::Saw);
+    let mut buffer = [0.0f32; 64];
+
+    osc.fill(&mut buffer);
+
+    for sample in buffer {
+        assert!(approx_eq(sample, reference.next_sample()));
+    }
+}
+
+#[test]
+fn test_iterator() {
+    let osc = MinBlepOsc::new(44100.0, 440.0, Waveform::Saw);
+    let samples: Vec<f32> = osc.take(64).collect();
+    assert_eq!(samples.len(), 64);
+}
@@ -0,0 +1,277 @@
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    Float, Oscillator, Waveform, advance_phase, morph_weights,
+    naive::{naive_pulse, naive_saw, naive_sine, naive_triangle},
+    next_wrap,
+    poly_blep::{poly_blamp, poly_blep},
+    wrap,
+};
+
+/// One sample of every waveform of a [`MultiOsc`], all at the same phase.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Frame<T: Float = f32> {
+    /// The sine output.
+    pub sine: T,
+    /// The triangle output, band-limited with polyBLAMP.
+    pub triangle: T,
+    /// The sawtooth output, band-limited with polyBLEP.
+    pub saw: T,
+    /// The square output, band-limited with polyBLEP.
+    pub square: T,
+    /// The pulse output at the oscillator's pulse width, band-limited with
+    /// polyBLEP.
+    pub pulse: T,
+}
+
+impl<T: Float> Frame<T> {
+    /// Returns the output matching `waveform`.
+    ///
+    /// [`Waveform::Morph`] blends the frame with the morph `shape`, see
+    /// [`MultiOsc::set_shape`]. Other waveforms ignore it.
+    pub fn get(&self, waveform: Waveform, shape: T) -> T {
+        match waveform {
+            Waveform::Sine => self.sine,
+            Waveform::Saw => self.saw,
+            Waveform::Square => self.square,
+            Waveform::Pulse => self.pulse,
+            Waveform::Triangle => self.triangle,
+            Waveform::Morph => {
+                let [sine, triangle, saw, square] = morph_weights(shape);
+                sine * self.sine + triangle * self.triangle + saw * self.saw + square * self.square
+            }
+        }
+    }
+
+    /// Combines every output with the matching one of `other`.
+    fn zip(self, other: Self, f: impl Fn(T, T) -> T) -> Self {
+        Self {
+            sine: f(self.sine, other.sine),
+            triangle: f(self.triangle, other.triangle),
+            saw: f(self.saw, other.saw),
+            square: f(self.square, other.square),
+            pulse: f(self.pulse, other.pulse),
+        }
+    }
+}
+
+/// A polyBLEP oscillator producing every waveform at once.
+///
+/// All outputs are computed from one phase accumulator, so they stay
+/// perfectly phase-coherent, like the outputs of a modular oscillator, and
+/// cost a single phase update. Each one matches what a
+/// [`PolyBlepOsc`](crate::poly_blep::PolyBlepOsc) of that waveform would
+/// produce, including through-zero FM and hard sync.
+///
+/// [`MultiOsc::next_frame`] returns all outputs, as does iterating over
+/// the oscillator. Through the [`Oscillator`] trait it plays a single one,
+/// selected with [`Oscillator::set_waveform`] and [`Waveform::Saw`] by
+/// default, so it can also drive or follow other oscillators.
+///
+/// # Example
+///
+/// ```
+/// use oscy::{multi::MultiOsc, Oscillator};
+///
+/// let mut osc = MultiOsc::new(44100.0, 110.0);
+/// osc.set_pulse_width(0.25);
+///
+/// let frame = osc.next_frame();
+/// let (saw, square): (f32, f32) = (frame.saw, frame.square);
+/// ```
+pub struct MultiOsc<T: Float = f32> {
+    phase: T,
+    phase_increment: T,
+    sample_rate: T,
+    // output played through the `Oscillator` trait
+    waveform: Waveform,
+    pulse_width: T,
+    shape: T,
+    // master wrap reported by `sync`, applied during the step after the next sample
+    sync_next: Option<T>,
+    // fraction and remaining heights of a sync reset happening during the coming step
+    sync_reset: Option<(T, Frame<T>)>,
+}
+
+impl<T: Float> MultiOsc<T> {
+    /// Creates a new multi-output oscillator.
+    pub fn new(sample_rate: T, frequency: T) -> Self {
+        Self {
+            phase: T::ZERO,
+            phase_increment: frequency / sample_rate,
+            sample_rate,
+            waveform: Waveform::Saw,
+            pulse_width: T::HALF,
+            shape: T::ZERO,
+            sync_next: None,
+            sync_reset: None,
+        }
+    }
+
+    /// Sets the pulse width of [`Frame::pulse`].
+    ///
+    /// The width is the fraction of the cycle spent high, clamped to
+    /// [0.0, 1.0].
+    pub fn set_pulse_width(&mut self, width: T) {
+        self.pulse_width = width.clamp(T::ZERO, T::ONE);
+    }
+
+    /// Sets the morph shape played for [`Waveform::Morph`], clamped to
+    /// [0.0, 1.0].
+    ///
+    /// The shape moves through sine (0.0), triangle (1/3), saw (2/3) and
+    /// square (1.0), see
+    /// [`PolyBlepOsc::set_shape`](crate::poly_blep::PolyBlepOsc::set_shape).
+    pub fn set_shape(&mut self, shape: T) {
+        self.shape = shape.clamp(T::ZERO, T::ONE);
+    }
+
+    /// Generates the next sample of every waveform.
+    pub fn next_frame(&mut self) -> Frame<T> {
+        let reset = self.advance();
+        self.render(reset)
+    }
+
+    /// Fills a buffer with consecutive frames.
+    pub fn fill_frames(&mut self, frames: &mut [Frame<T>]) {
+        for frame in frames.iter_mut() {
+            *frame = self.next_frame();
+        }
+    }
+
+    /// Moves the phase one step forward, returning the sync reset that
+    /// happened during the step, if any.
+    fn advance(&mut self) -> Option<(T, Frame<T>)> {
+        let reset = self.sync_reset.take();
+
+        match reset {
+            // restart the cycle at the master's wrap, then run the rest of the step
+            Some((fraction, _)) => {
+                self.phase = advance_phase(T::ZERO, (T::ONE - fraction) * self.phase_increment)
+            }
+            None => self.phase = advance_phase(self.phase, self.phase_increment),
+        }
+
+        reset
+    }
+
+    /// Computes the band-limited frame at the current phase.
+    fn render(&mut self, reset: Option<(T, Frame<T>)>) -> Frame<T> {
+        let phase = self.phase;
+        let dt = self.phase_increment.abs();
+        let opposite = (phase + T::HALF).fract();
+
+        // the saw reset and the rising edges of square and pulse share
+        // their position, and so their correction
+        let rising = poly_blep(phase, dt);
+        let falling = (phase + T::ONE - self.pulse_width).fract();
+        // slope changes of the triangle per sample, see `PolyBlepOsc`
+        let slope_change = T::from_f64(8.0) * dt;
+
+        let mut frame = Frame {
+            sine: naive_sine(phase),
+            triangle: naive_triangle(phase)
+                + slope_change * (poly_blamp(phase, dt) - poly_blamp(opposite, dt)),
+            saw: naive_saw(phase) - rising,
+            square: naive_pulse(phase, T::HALF) + rising - poly_blep(opposite, dt),
+            pulse: naive_pulse(phase, self.pulse_width) + rising - poly_blep(falling, dt),
+        };
+
+        if let Some((fraction, heights)) = reset {
+            // second half of the polyBLEP residual for the sync reset
+            frame = frame.zip(heights, |sample, height| {
+                sample - T::HALF * height * fraction * fraction
+            });
+        }
+
+        if let Some(fraction) = self.sync_next.take() {
+            let correction = self.begin_sync(fraction);
+            frame = frame.zip(correction, |sample, correction| sample + correction);
+        }
+
+        frame
+    }
+
+    /// Evaluates every uncorrected waveform at an arbitrary phase.
+    fn naive(&self, phase: T) -> Frame<T> {
+        Frame {
+            sine: naive_sine(phase),
+            triangle: naive_triangle(phase),
+            saw: naive_saw(phase),
+            square: naive_pulse(phase, T::HALF),
+            pulse: naive_pulse(phase, self.pulse_width),
+        }
+    }
+
+    /// Schedules a sync reset `fraction` of the way into the next step and
+    /// returns the polyBLEP corrections for the current frame, which lies
+    /// just before the reset.
+    fn begin_sync(&mut self, fraction: T) -> Frame<T> {
+        let reset_phase = advance_phase(self.phase, fraction * self.phase_increment);
+        let before = self.naive(reset_phase);
+
+        // as in `PolyBlepOsc`, the cycle restarts at phase 0 when running
+        // forwards, or at the end of the cycle when running backwards
+        let (start, end) = if self.phase_increment >= T::ZERO {
+            (self.naive(T::ZERO), self.naive(T::ONE))
+        } else {
+            (self.naive(T::ONE), self.naive(T::ZERO))
+        };
+
+        self.sync_reset = Some((fraction, end.zip(before, |end, before| end - before)));
+
+        let t = T::ONE - fraction;
+        start.zip(before, |start, before| T::HALF * (start - before) * t * t)
+    }
+}
+
+impl<T: Float> Oscillator<T> for MultiOsc<T> {
+    fn set_frequency(&mut self, hz: T) {
+        self.phase_increment = hz / self.sample_rate;
+    }
+
+    fn set_phase(&mut self, phase: T) {
+        self.phase = wrap(phase);
+    }
+
+    fn phase(&self) -> T {
+        self.phase
+    }
+
+    fn reset(&mut self) {
+        self.phase = T::ZERO;
+        self.sync_next = None;
+        self.sync_reset = None;
+    }
+
+    fn next_wrap(&self) -> Option<T> {
+        if let Some((fraction, _)) = self.sync_reset {
+            return Some(fraction);
+        }
+
+        next_wrap(self.phase, self.phase_increment)
+    }
+
+    fn sync(&mut self, fraction: T) {
+        self.sync_next = Some(fraction.clamp(T::ZERO, T::ONE));
+    }
+
+    /// Selects the output played by [`Oscillator::next_sample`].
+    /// [`MultiOsc::next_frame`] still returns all of them.
+    fn set_waveform(&mut self, waveform: Waveform) {
+        self.waveform = waveform;
+    }
+
+    fn next_sample(&mut self) -> T {
+        self.next_frame().get(self.waveform, self.shape)
+    }
+}
+
+impl<T: Float> Iterator for MultiOsc<T> {
+    type Item = Frame<T>;
+
+    fn next(&mut self) -> Option<Frame<T>> {
+        Some(self.next_frame())
+    }
+}
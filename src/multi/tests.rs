@@ -0,0 +1,219 @@
+use std::f32::consts::TAU;
+
+use super::{Frame, MultiOsc};
+use crate::{Oscillator, Waveform, poly_blep::PolyBlepOsc, sync::HardSync};
+
+const EPSILON: f32 = 1e-6;
+
+fn approx_eq(a: f32, b: f32) -> bool {
+    (a - b).abs() < EPSILON
+}
+
+// reads one output of a frame
+type Output = fn(&Frame) -> f32;
+
+const OUTPUTS: [(Waveform, Output); 5] = [
+    (Waveform::Sine, |frame| frame.sine),
+    (Waveform::Triangle, |frame| frame.triangle),
+    (Waveform::Saw, |frame| frame.saw),
+    (Waveform::Square, |frame| frame.square),
+    (Waveform::Pulse, |frame| frame.pulse),
+];
+
+// frame tests
+
+#[test]
+fn test_outputs_match_poly_blep() {
+    for (waveform, output) in OUTPUTS {
+        let mut osc = MultiOsc::new(44100.0, 1234.5);
+        let mut expected = PolyBlepOsc::new(44100.0, 1234.5, waveform);
+
+        for _ in 0..1000 {
+            assert!(approx_eq(output(&osc.next_frame()), expected.next_sample()));
+        }
+    }
+}
+
+#[test]
+fn test_pulse_follows_pulse_width() {
+    let mut osc = MultiOsc::new(44100.0, 880.0);
+    let mut expected = PolyBlepOsc::new(44100.0, 880.0, Waveform::Pulse);
+
+    for i in 0..1000 {
+        // modulate every sample
+        let width = 0.1 + (i % 80) as f32 / 100.0;
+        osc.set_pulse_width(width);
+        expected.set_pulse_width(width);
+        assert!(approx_eq(osc.next_frame().pulse, expected.next_sample()));
+    }
+}
+
+#[test]
+fn test_negative_frequency_matches_poly_blep() {
+    for (waveform, output) in OUTPUTS {
+        let mut osc = MultiOsc::new(44100.0, -660.0);
+        let mut expected = PolyBlepOsc::new(44100.0, -660.0, waveform);
+
+        for _ in 0..1000 {
+            assert!(approx_eq(output(&osc.next_frame()), expected.next_sample()));
+        }
+    }
+}
+
+#[test]
+fn test_outputs_share_phase() {
+    // 16 samples per cycle, halfway between sample grid and edges
+    let mut osc = MultiOsc::new(1600.0, 100.0);
+    osc.set_phase(0.03125);
+
+    for _ in 0..32 {
+        let frame = osc.next_frame();
+        let phase = osc.phase();
+
+        assert!(approx_eq(frame.sine, (phase * TAU).sin()));
+        // away from the corrections around the edges
+        if phase > 0.0625 && phase < 0.9375 && (phase - 0.5).abs() > 0.0625 {
+            assert!(approx_eq(frame.saw, 2.0 * phase - 1.0));
+            assert!(approx_eq(
+                frame.square,
+                if phase < 0.5 { 1.0 } else { -1.0 }
+            ));
+        }
+    }
+}
+
+#[test]
+fn test_fill_frames() {
+    let mut filled = MultiOsc::new(44100.0, 440.0);
+    let mut reference = MultiOsc::new(44100.0, 440.0);
+    let mut frames = [Frame::default(); 64];
+
+    filled.fill_frames(&mut frames);
+
+    for frame in frames {
+        assert_eq!(frame, reference.next_frame());
+    }
+}
+
+#[test]
+fn test_get_selects_output() {
+    let frame = Frame {
+        sine: 0.1,
+        triangle: 0.2,
+        saw: 0.3,
+        square: 0.4,
+        pulse: 0.5,
+    };
+
+    for (waveform, output) in OUTPUTS {
+        assert_eq!(frame.get(waveform, 0.0), output(&frame));
+    }
+    assert!(approx_eq(frame.get(Waveform::Morph, 0.5), 0.25)); // half triangle, half saw
+}
+
+#[test]
+fn test_tiny_negative_phase_stays_below_one() {
+    // a step from 1e-9 back by 2e-9 lands at -1e-9, which rounds to 1.0
+    let mut osc: MultiOsc = MultiOsc::new(1.0, -2e-9);
+    osc.set_phase(1e-9);
+    osc.next_sample();
+    assert!(osc.phase() < 1.0);
+
+    osc.set_phase(-1e-9);
+    assert!(osc.phase() < 1.0);
+}
+
+// hard sync tests
+
+#[test]
+fn test_synced_outputs_match_poly_blep() {
+    for (waveform, output) in OUTPUTS {
+        let master = PolyBlepOsc::new(44100.0, 110.0, Waveform::Saw);
+        let mut osc = HardSync::new(master, MultiOsc::new(44100.0, 347.0));
+        let master = PolyBlepOsc::new(44100.0, 110.0, Waveform::Saw);
+        let slave = PolyBlepOsc::new(44100.0, 347.0, waveform);
+        let mut expected = HardSync::new(master, slave);
+
+        for _ in 0..2000 {
+            // drive the pair by hand to read the slave's whole frame
+            osc.master_mut().next_sample();
+            if let Some(fraction) = osc.master_mut().next_wrap() {
+                osc.slave_mut().sync(fraction);
+            }
+            let frame = osc.slave_mut().next_frame();
+
+            // the square finds its falling edge with one rounding less, which
+            // the polyBLEP magnifies by the inverse increment
+            assert!((output(&frame) - expected.next_sample()).abs() < 1e-4);
+        }
+    }
+}
+
+// trait impl tests
+
+#[test]
+fn test_next_sample_plays_selected_waveform() {
+    for waveform in [Waveform::Saw, Waveform::Triangle, Waveform::Morph] {
+        let mut osc = MultiOsc::new(44100.0, 220.0);
+        let mut expected = PolyBlepOsc::new(44100.0, 220.0, waveform);
+        osc.set_waveform(waveform);
+        osc.set_shape(0.8);
+        expected.set_shape(0.8);
+
+        for _ in 0..1000 {
+            assert!(approx_eq(osc.next_sample(), expected.next_sample()));
+        }
+    }
+}
+
+#[test]
+fn test_set_frequency_changes_increment() {
+    let mut osc = MultiOsc::new(1000.0, 100.0);
+    osc.set_frequency(250.0);
+    osc.next_frame();
+    assert!(approx_eq(osc.phase(), 0.25));
+}
+
+#[test]
+fn test_set_phase() {
+    let mut osc = MultiOsc::new(1000.0, 100.0);
+    osc.set_phase(-0.25);
+    assert!(approx_eq(osc.phase(), 0.75));
+}
+
+#[test]
+fn test_reset_zeros_phase() {
+    let mut osc = MultiOsc::new(1000.0, 100.0);
+    for _ in 0..5 {
+        osc.next_frame();
+    }
+    osc.reset();
+    assert!(approx_eq(osc.phase(), 0.0));
+}
+
+#[test]
+fn test_next_wrap_reports_fraction() {
+    let mut osc: MultiOsc = MultiOsc::new(10.0, 3.0); // inc = 0.3
+    osc.set_phase(0.9);
+    assert!((osc.next_wrap().unwrap() - 1.0 / 3.0).abs() < 1e-5);
+}
+
+#[test]
+fn test_fill_buffer() {
+    let mut filled = MultiOsc::new(44100.0, 440.0);
+    let mut reference = MultiOsc::new(44100.0, 440.0);
+    let mut buffer = [0.0f32; 64];
+
+    filled.fill(&mut buffer);
+
+    for sample in buffer {
+        assert!(approx_eq(sample, reference.next_frame().saw));
+    }
+}
+
+#[test]
+fn test_iterator() {
+    let osc = MultiOsc::new(44100.0, 440.0);
+    let frames: Vec<Frame> = osc.take(64).collect();
+    assert_eq!(frames.len(), 64);
+}
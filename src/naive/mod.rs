@@ -1,9 +1,12 @@
 #[cfg(test)]
 mod tests;
 
-use std::f32::consts::TAU;
-
-use crate::{Oscillator, Waveform};
+use crate::{
+    Float, Oscillator, Waveform, advance_phase, morph_weights, next_wrap,
+    sine::SineMode,
+    smooth::{GlideCurve, GlideTime, Smoother},
+    wrap,
+};
 
 /// A naive oscillator with no anti-aliasing.
 ///
@@ -17,75 +20,264 @@ use crate::{Oscillator, Waveform};
 ///
 /// // At quarter sample rate, first sample hits peak of sine wave
 /// let mut osc = NaiveOsc::new(100.0, 25.0, Waveform::Sine); // 100Hz SR, 25Hz freq
-/// let sample = osc.next_sample();
+/// let sample: f32 = osc.next_sample();
 /// assert!((sample - 1.0).abs() < 1e-6); // sin(TAU/4) = 1.0
 /// ```
-pub struct NaiveOsc {
-    phase: f32,
-    phase_increment: f32,
-    sample_rate: f32,
+pub struct NaiveOsc<T: Float = f32> {
+    phase: T,
+    phase_increment: T,
+    sample_rate: T,
     waveform: Waveform,
+    pulse_width: T,
+    shape: T,
+    sine_mode: SineMode,
+    // glided frequency in hertz and output amplitude
+    frequency: Smoother<T>,
+    amplitude: Smoother<T>,
+    // master wrap reported by `sync`, applied during the step after the next sample
+    sync_next: Option<T>,
+    // fraction of the coming step at which a sync reset happens
+    sync_reset: Option<T>,
 }
 
-impl NaiveOsc {
+impl<T: Float> NaiveOsc<T> {
     /// Creates a new naive oscillator.
-    pub fn new(sample_rate: f32, frequency: f32, waveform: Waveform) -> Self {
+    pub fn new(sample_rate: T, frequency: T, waveform: Waveform) -> Self {
         Self {
-            phase: 0.0,
+            phase: T::ZERO,
             phase_increment: frequency / sample_rate,
             sample_rate,
             waveform,
+            pulse_width: T::HALF,
+            shape: T::ZERO,
+            sine_mode: SineMode::Exact,
+            frequency: Smoother::new(frequency),
+            amplitude: Smoother::new(T::ONE),
+            sync_next: None,
+            sync_reset: None,
         }
     }
+
+    /// Sets the pulse width used by [`Waveform::Pulse`].
+    ///
+    /// The width is the fraction of the cycle spent high, clamped to
+    /// [0.0, 1.0]. It's cheap to call every sample, so it can be driven by
+    /// an LFO or audio-rate signal for pulse width modulation.
+    pub fn set_pulse_width(&mut self, width: T) {
+        self.pulse_width = width.clamp(T::ZERO, T::ONE);
+    }
+
+    /// Sets the morph shape used by [`Waveform::Morph`].
+    ///
+    /// The shape is clamped to [0.0, 1.0] and moves through sine (0.0),
+    /// triangle (1/3), saw (2/3) and square (1.0), crossfading between
+    /// neighbouring waveforms in between.
+    pub fn set_shape(&mut self, shape: T) {
+        self.shape = shape.clamp(T::ZERO, T::ONE);
+    }
+
+    /// Sets how [`Waveform::Sine`] is evaluated, exactly by default.
+    ///
+    /// The sine in [`Waveform::Morph`] follows it too. See [`SineMode`] for
+    /// the error and distortion of each.
+    pub fn set_sine_mode(&mut self, mode: SineMode) {
+        self.sine_mode = mode;
+    }
+
+    /// Sets how long frequency and amplitude changes glide for.
+    ///
+    /// Zero, the default, makes [`Oscillator::set_frequency`] and
+    /// [`NaiveOsc::set_amplitude`] take effect at once. Otherwise each new
+    /// frequency or amplitude glides from the current one over this time,
+    /// for portamento and click-free level changes. A glide in progress
+    /// continues from where it is over the new time.
+    pub fn set_glide_time(&mut self, time: GlideTime<T>) {
+        let samples = time.to_samples(self.sample_rate);
+        self.frequency.set_time(samples);
+        self.amplitude.set_time(samples);
+    }
+
+    /// Sets the curve of frequency and amplitude glides, linear by default.
+    pub fn set_glide_curve(&mut self, curve: GlideCurve) {
+        self.frequency.set_curve(curve);
+        self.amplitude.set_curve(curve);
+    }
+
+    /// Sets the amplitude the output is scaled by, 1.0 by default.
+    ///
+    /// Changes glide like the frequency, see [`NaiveOsc::set_glide_time`].
+    pub fn set_amplitude(&mut self, amplitude: T) {
+        self.amplitude.set_target(amplitude);
+    }
+
+    /// Returns the current amplitude, which lags the one set while gliding.
+    pub fn amplitude(&self) -> T {
+        self.amplitude.value()
+    }
+
+    /// Returns the current frequency in hertz, which lags the one set
+    /// while gliding.
+    pub fn frequency(&self) -> T {
+        self.frequency.value()
+    }
+
+    /// Moves frequency and amplitude glides one sample further.
+    fn glide(&mut self) {
+        if self.frequency.is_gliding() {
+            self.phase_increment = self.frequency.next_value() / self.sample_rate;
+        }
+        self.amplitude.next_value();
+    }
+
+    /// Moves the phase one step forward.
+    fn advance(&mut self) {
+        match self.sync_reset.take() {
+            // restart the cycle at the master's wrap, then run the rest of the step
+            Some(fraction) => {
+                self.phase = advance_phase(T::ZERO, (T::ONE - fraction) * self.phase_increment)
+            }
+            None => self.phase = advance_phase(self.phase, self.phase_increment),
+        }
+        self.sync_reset = self.sync_next.take();
+
+        // glide afterwards, so `next_wrap` sees the increment of the next step
+        self.glide();
+    }
+
+    /// Computes the sample at the current phase.
+    fn render(&self) -> T {
+        let sample = match self.waveform {
+            Waveform::Sine => self.sine(),
+            Waveform::Saw => naive_saw(self.phase),
+            Waveform::Square => naive_pulse(self.phase, T::HALF),
+            Waveform::Pulse => naive_pulse(self.phase, self.pulse_width),
+            Waveform::Triangle => naive_triangle(self.phase),
+            Waveform::Morph => self.morph(),
+        };
+        sample * self.amplitude.value()
+    }
+
+    fn sine(&self) -> T {
+        self.sine_mode.sine(self.phase)
+    }
+
+    fn morph(&self) -> T {
+        let [sine, triangle, saw, square] = morph_weights(self.shape);
+
+        // only evaluate the (at most two) waveforms present in the blend
+        let mut out = T::ZERO;
+        if sine > T::ZERO {
+            out += sine * self.sine();
+        }
+        if triangle > T::ZERO {
+            out += triangle * naive_triangle(self.phase);
+        }
+        if saw > T::ZERO {
+            out += saw * naive_saw(self.phase);
+        }
+        if square > T::ZERO {
+            out += square * naive_pulse(self.phase, T::HALF);
+        }
+        out
+    }
 }
 
-impl Oscillator for NaiveOsc {
-    fn set_frequency(&mut self, hz: f32) {
-        self.phase_increment = hz / self.sample_rate
+impl<T: Float> Oscillator<T> for NaiveOsc<T> {
+    /// Sets the frequency in hertz, gliding there if a glide time is set.
+    fn set_frequency(&mut self, hz: T) {
+        self.frequency.set_target(hz);
+        self.phase_increment = self.frequency.value() / self.sample_rate
+    }
+
+    fn set_phase(&mut self, phase: T) {
+        self.phase = wrap(phase);
     }
 
-    fn set_phase(&mut self, phase: f32) {
-        self.phase = phase.fract();
+    fn phase(&self) -> T {
+        self.phase
     }
 
+    /// Restarts the cycle and finishes any glide at once.
     fn reset(&mut self) {
-        self.phase = 0.0;
+        self.phase = T::ZERO;
+        self.sync_next = None;
+        self.sync_reset = None;
+        self.frequency.settle();
+        self.amplitude.settle();
+        self.phase_increment = self.frequency.value() / self.sample_rate;
+    }
+
+    fn next_wrap(&self) -> Option<T> {
+        if let Some(fraction) = self.sync_reset {
+            return Some(fraction);
+        }
+
+        next_wrap(self.phase, self.phase_increment)
+    }
+
+    fn sync(&mut self, fraction: T) {
+        self.sync_next = Some(fraction.clamp(T::ZERO, T::ONE));
+    }
+
+    fn set_waveform(&mut self, waveform: Waveform) {
+        self.waveform = waveform;
     }
 
-    fn next_sample(&mut self) -> f32 {
-        self.phase += self.phase_increment;
+    fn next_sample(&mut self) -> T {
+        self.advance();
+        self.render()
+    }
+
+    fn fill_with_fm(&mut self, buffer: &mut [T], frequencies: &[T]) {
+        let inv_sample_rate = T::ONE / self.sample_rate;
 
-        // subtraction only when needed.
-        // cheaper than fract()/modulo every sample
-        if self.phase >= 1.0 {
-            self.phase -= 1.0;
+        for (sample, &hz) in buffer.iter_mut().zip(frequencies) {
+            // audio-rate modulation doesn't glide
+            self.frequency.jump(hz);
+            self.phase_increment = hz * inv_sample_rate;
+            self.advance();
+            *sample = self.render();
         }
+    }
 
-        match self.waveform {
-            Waveform::Sine => (self.phase * TAU).sin(),
-            Waveform::Saw => 2.0 * self.phase - 1.0,
-            Waveform::Square => {
-                if self.phase < 0.5 {
-                    1.0
-                } else {
-                    -1.0
-                }
-            }
-            Waveform::Triangle => {
-                if self.phase < 0.5 {
-                    4.0 * self.phase - 1.0
-                } else {
-                    -4.0 * self.phase + 3.0
-                }
-            }
+    fn fill_with_pm(&mut self, buffer: &mut [T], phase_offsets: &[T]) {
+        for (sample, &offset) in buffer.iter_mut().zip(phase_offsets) {
+            self.advance();
+
+            // read the waveform at the shifted phase, leaving the
+            // accumulator itself untouched
+            let phase = self.phase;
+            self.phase = wrap(phase + offset);
+            *sample = self.render();
+            self.phase = phase;
         }
     }
 }
 
-impl Iterator for NaiveOsc {
-    type Item = f32;
+impl<T: Float> Iterator for NaiveOsc<T> {
+    type Item = T;
 
-    fn next(&mut self) -> Option<f32> {
+    fn next(&mut self) -> Option<T> {
         Some(self.next_sample())
     }
 }
+
+pub(crate) fn naive_sine<T: Float>(phase: T) -> T {
+    (phase * T::TAU).sin()
+}
+
+pub(crate) fn naive_saw<T: Float>(phase: T) -> T {
+    T::TWO * phase - T::ONE
+}
+
+pub(crate) fn naive_pulse<T: Float>(phase: T, width: T) -> T {
+    if phase < width { T::ONE } else { -T::ONE }
+}
+
+pub(crate) fn naive_triangle<T: Float>(phase: T) -> T {
+    if phase < T::HALF {
+        T::from_f64(4.0) * phase - T::ONE
+    } else {
+        -T::from_f64(4.0) * phase + T::from_f64(3.0)
+    }
+}
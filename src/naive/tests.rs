@@ -1,7 +1,11 @@
 use std::f32::consts::TAU;
 
 use super::NaiveOsc;
-use crate::{Oscillator, Waveform};
+use crate::{
+    Event, EventKind, Oscillator, Waveform,
+    sine::SineMode,
+    smooth::{GlideCurve, GlideTime},
+};
 
 const EPSILON: f32 = 1e-6;
 
@@ -20,6 +24,20 @@ fn test_sine_at_known_phases() {
     assert!(approx_eq(osc.next_sample(), 0.0)); // sin(TAU * 1.0) = 0
 }
 
+#[test]
+fn test_sine_mode_follows_approximation() {
+    for mode in [SineMode::Polynomial, SineMode::Table] {
+        let mut osc = NaiveOsc::new(44100.0, 440.0, Waveform::Sine);
+        osc.set_sine_mode(mode);
+
+        for _ in 0..1000 {
+            let sample = osc.next_sample();
+            assert_eq!(sample, mode.sine(osc.phase()));
+            assert!((sample - (osc.phase() * TAU).sin()).abs() < 1e-5);
+        }
+    }
+}
+
 #[test]
 fn test_saw_ramps_up() {
     let mut osc = NaiveOsc::new(4.0, 1.0, Waveform::Saw);
@@ -42,6 +60,43 @@ fn test_square_alternates() {
     assert!(approx_eq(osc.next_sample(), 1.0)); // phase 0.0 (wrapped)
 }
 
+#[test]
+fn test_pulse_follows_width() {
+    let mut osc = NaiveOsc::new(4.0, 1.0, Waveform::Pulse);
+    osc.set_pulse_width(0.8);
+
+    // Pulse: 1 if phase < 0.8, else -1
+    assert!(approx_eq(osc.next_sample(), 1.0)); // phase 0.25
+    assert!(approx_eq(osc.next_sample(), 1.0)); // phase 0.5
+    assert!(approx_eq(osc.next_sample(), 1.0)); // phase 0.75
+    assert!(approx_eq(osc.next_sample(), 1.0)); // phase 0.0 (wrapped)
+
+    osc.set_pulse_width(0.3);
+    assert!(approx_eq(osc.next_sample(), 1.0)); // phase 0.25
+    assert!(approx_eq(osc.next_sample(), -1.0)); // phase 0.5
+}
+
+#[test]
+fn test_pulse_width_half_matches_square() {
+    let mut pulse = NaiveOsc::new(100.0, 3.0, Waveform::Pulse);
+    let mut square = NaiveOsc::new(100.0, 3.0, Waveform::Square);
+
+    for _ in 0..100 {
+        assert!(approx_eq(pulse.next_sample(), square.next_sample()));
+    }
+}
+
+#[test]
+fn test_pulse_width_is_clamped() {
+    let mut osc = NaiveOsc::new(4.0, 1.0, Waveform::Pulse);
+
+    osc.set_pulse_width(2.0);
+    assert!((0..4).all(|_| approx_eq(osc.next_sample(), 1.0)));
+
+    osc.set_pulse_width(-1.0);
+    assert!((0..4).all(|_| approx_eq(osc.next_sample(), -1.0)));
+}
+
 #[test]
 fn test_triangle_peaks_at_midpoint() {
     let mut osc = NaiveOsc::new(4.0, 1.0, Waveform::Triangle);
@@ -86,6 +141,76 @@ fn test_reset_zeros_phase() {
     assert!(approx_eq(osc.next_sample(), 1.0));
 }
 
+#[test]
+fn test_negative_frequency_runs_backwards() {
+    let mut osc = NaiveOsc::new(4.0, -1.0, Waveform::Saw);
+
+    // phases: 0.75, 0.5, 0.25, 0.0
+    assert!(approx_eq(osc.next_sample(), 0.5));
+    assert!(approx_eq(osc.next_sample(), 0.0));
+    assert!(approx_eq(osc.next_sample(), -0.5));
+    assert!(approx_eq(osc.next_sample(), -1.0));
+    assert!(approx_eq(osc.next_sample(), 0.5)); // wrapped back to 0.75
+}
+
+#[test]
+fn test_through_zero_fm_keeps_phase_in_range() {
+    let mut osc = NaiveOsc::new(44100.0, 0.0, Waveform::Saw);
+
+    for i in 0..10_000 {
+        let modulator = (i as f32 / 300.0 * TAU).sin();
+        osc.set_frequency(5000.0 * modulator);
+        let sample = osc.next_sample();
+        assert!((-1.0..=1.0).contains(&sample));
+    }
+}
+
+#[test]
+fn test_set_phase_wraps_negative() {
+    let mut osc = NaiveOsc::new(4.0, 1.0, Waveform::Saw);
+    osc.set_phase(-0.5);
+
+    // -0.5 wraps to 0.5, next sample adds 0.25 -> phase 0.75
+    assert!(approx_eq(osc.next_sample(), 0.5));
+}
+
+#[test]
+fn test_tiny_negative_phase_stays_below_one() {
+    // a step from 1e-9 back by 2e-9 lands at -1e-9, which rounds to 1.0
+    let mut osc: NaiveOsc = NaiveOsc::new(1.0, -2e-9, Waveform::Saw);
+    osc.set_phase(1e-9);
+    osc.next_sample();
+    assert!(osc.phase() < 1.0);
+
+    osc.set_phase(-1e-9);
+    assert!(osc.phase() < 1.0);
+}
+
+#[test]
+fn test_next_wrap_backwards() {
+    let mut osc = NaiveOsc::new(10.0, -3.0, Waveform::Saw); // inc = -0.3
+    osc.set_phase(0.1);
+
+    // 0.1 -> -0.2 crosses zero a third of the way into the step
+    let fraction: f32 = osc.next_wrap().unwrap();
+    assert!((fraction - 1.0 / 3.0).abs() < 1e-5);
+}
+
+#[test]
+fn test_next_wrap_reports_fraction() {
+    let mut osc = NaiveOsc::new(10.0, 3.0, Waveform::Saw); // inc = 0.3
+
+    assert_eq!(osc.next_wrap(), None); // 0.0 -> 0.3
+    osc.next_sample();
+    osc.next_sample();
+    assert_eq!(osc.next_wrap(), None); // 0.6 -> 0.9
+    osc.next_sample();
+
+    // 0.9 -> 1.2 wraps a third of the way into the step
+    let fraction: f32 = osc.next_wrap().unwrap();
+    assert!((fraction - 1.0 / 3.0).abs() < 1e-5);
+}
+
 #[test]
 fn test_fill_buffer() {
     let mut osc = NaiveOsc::new(4.0, 1.0, Waveform::Sine);
@@ -99,6 +224,123 @@ fn test_fill_buffer() {
     assert!(approx_eq(buffer[3], 0.0));
 }
 
+#[test]
+fn test_phase_getter() {
+    let mut osc = NaiveOsc::new(4.0, 1.0, Waveform::Sine);
+    osc.next_sample();
+    assert!(approx_eq(osc.phase(), 0.25));
+
+    osc.set_phase(0.6);
+    assert!(approx_eq(osc.phase(), 0.6));
+}
+
+#[test]
+fn test_fill_with_fm_matches_per_sample() {
+    let mut block = NaiveOsc::new(44100.0, 440.0, Waveform::Saw);
+    let mut single = NaiveOsc::new(44100.0, 440.0, Waveform::Saw);
+
+    // through-zero sweep from -2kHz to 2kHz
+    let frequencies: Vec<f32> = (0..512).map(|i| -2000.0 + i as f32 * 8.0).collect();
+    let mut buffer = [0.0f32; 512];
+    block.fill_with_fm(&mut buffer, &frequencies);
+
+    for (sample, &hz) in buffer.iter().zip(&frequencies) {
+        single.set_frequency(hz);
+        assert!((sample - single.next_sample()).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn test_fill_with_pm_offsets_phase() {
+    let mut osc = NaiveOsc::new(4.0, 1.0, Waveform::Sine);
+    let mut buffer = [0.0f32; 4];
+
+    // phases 0.25, 0.5, 0.75, 0.0 shifted by a quarter cycle
+    osc.fill_with_pm(&mut buffer, &[0.25; 4]);
+
+    assert!(approx_eq(buffer[0], 0.0)); // sin(TAU * 0.5)
+    assert!(approx_eq(buffer[1], -1.0)); // sin(TAU * 0.75)
+    assert!(approx_eq(buffer[2], 0.0)); // sin(TAU * 1.0)
+    assert!(approx_eq(buffer[3], 1.0)); // sin(TAU * 1.25)
+}
+
+#[test]
+fn test_fill_with_pm_does_not_accumulate() {
+    let mut modulated = NaiveOsc::new(44100.0, 440.0, Waveform::Saw);
+    let mut plain = NaiveOsc::new(44100.0, 440.0, Waveform::Saw);
+
+    let offsets: Vec<f32> = (0..256).map(|i| 3.0 * (i as f32 * 0.1).sin()).collect();
+    let mut buffer = [0.0f32; 256];
+    modulated.fill_with_pm(&mut buffer, &offsets);
+    plain.fill(&mut buffer);
+
+    assert!(approx_eq(modulated.phase(), plain.phase()));
+}
+
+#[test]
+fn test_set_waveform() {
+    let mut osc = NaiveOsc::new(4.0, 1.0, Waveform::Sine);
+    osc.set_waveform(Waveform::Saw);
+
+    assert!(approx_eq(osc.next_sample(), -0.5)); // 2 * 0.25 - 1
+}
+
+#[test]
+fn test_fill_with_events_applies_at_offsets() {
+    let mut osc = NaiveOsc::new(4.0, 1.0, Waveform::Saw);
+    let mut buffer = [0.0f32; 6];
+    let events = [
+        Event::new(2, EventKind::ResetPhase),
+        Event::new(2, EventKind::Waveform(Waveform::Square)),
+        Event::new(4, EventKind::Frequency(2.0)),
+    ];
+
+    osc.fill_with_events(&mut buffer, &events);
+
+    // saw at phases 0.25 and 0.5
+    assert!(approx_eq(buffer[0], -0.5));
+    assert!(approx_eq(buffer[1], 0.0));
+    // restarted as a square: phases 0.25 and 0.5
+    assert!(approx_eq(buffer[2], 1.0));
+    assert!(approx_eq(buffer[3], -1.0));
+    // twice as fast: phases 0.0 and 0.5
+    assert!(approx_eq(buffer[4], 1.0));
+    assert!(approx_eq(buffer[5], -1.0));
+}
+
+#[test]
+fn test_fill_with_events_matches_setters() {
+    let mut block = NaiveOsc::new(44100.0, 440.0, Waveform::Saw);
+    let mut single = NaiveOsc::new(44100.0, 440.0, Waveform::Saw);
+    let events = [
+        Event::new(0, EventKind::Frequency(220.0)),
+        Event::new(100, EventKind::Frequency(330.0)),
+        Event::new(333, EventKind::ResetPhase),
+        Event::new(511, EventKind::Waveform(Waveform::Triangle)),
+    ];
+
+    let mut buffer = [0.0f32; 512];
+    block.fill_with_events(&mut buffer, &events);
+
+    for (offset, sample) in buffer.iter().enumerate() {
+        for event in events.iter().filter(|event| event.offset == offset) {
+            event.apply(&mut single);
+        }
+        assert!(approx_eq(*sample, single.next_sample()));
+    }
+}
+
+#[test]
+fn test_fill_with_events_past_end_applies_after() {
+    let mut osc = NaiveOsc::new(4.0, 1.0, Waveform::Saw);
+    let mut buffer = [0.0f32; 2];
+
+    osc.fill_with_events(&mut buffer, &[Event::new(5, EventKind::ResetPhase)]);
+
+    assert!(approx_eq(buffer[1], 0.0)); // phase 0.5, before the reset
+    assert!(approx_eq(osc.phase(), 0.0));
+}
+
 #[test]
 fn test_iterator() {
     let osc = NaiveOsc::new(4.0, 1.0, Waveform::Sine);
@@ -109,3 +351,173 @@ fn test_iterator() {
     assert!(approx_eq(samples[2], -1.0));
     assert!(approx_eq(samples[3], 0.0));
 }
+
+#[test]
+fn test_morph_endpoints_match_waveforms() {
+    let shapes = [
+        (0.0, Waveform::Sine),
+        (1.0 / 3.0, Waveform::Triangle),
+        (2.0 / 3.0, Waveform::Saw),
+        (1.0, Waveform::Square),
+    ];
+
+    for (shape, waveform) in shapes {
+        let mut morph = NaiveOsc::new(100.0, 3.0, Waveform::Morph);
+        let mut reference: NaiveOsc = NaiveOsc::new(100.0, 3.0, waveform);
+        morph.set_shape(shape);
+
+        for _ in 0..100 {
+            assert!((morph.next_sample() - reference.next_sample()).abs() < 1e-5);
+        }
+    }
+}
+
+#[test]
+fn test_morph_crossfades_neighbours() {
+    let mut morph = NaiveOsc::new(100.0, 3.0, Waveform::Morph);
+    let mut saw = NaiveOsc::new(100.0, 3.0, Waveform::Saw);
+    let mut square: NaiveOsc = NaiveOsc::new(100.0, 3.0, Waveform::Square);
+    morph.set_shape(5.0 / 6.0); // halfway between saw and square
+
+    for _ in 0..100 {
+        let expected = 0.5 * saw.next_sample() + 0.5 * square.next_sample();
+        assert!((morph.next_sample() - expected).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn test_glide_reaches_frequency_on_time() {
+    let mut osc = NaiveOsc::new(1000.0, 100.0, Waveform::Saw);
+    osc.set_glide_time(GlideTime::Samples(10.0));
+    osc.set_frequency(200.0);
+    assert!(approx_eq(osc.frequency(), 100.0));
+
+    // 10 Hz more every sample, the phase following a step behind
+    let mut phase = 0.0;
+    for step in 1..=10 {
+        osc.next_sample();
+        phase = (phase + (100.0 + 10.0 * (step - 1) as f32) / 1000.0) % 1.0;
+        assert!(approx_eq(osc.frequency(), 100.0 + 10.0 * step as f32));
+        assert!((osc.phase() - phase).abs() < 1e-5);
+    }
+    osc.next_sample();
+    assert!(approx_eq(osc.frequency(), 200.0));
+}
+
+#[test]
+fn test_next_wrap_predicts_wraps_while_gliding() {
+    let mut osc = NaiveOsc::new(44100.0, 200.0, Waveform::Saw);
+    osc.set_glide_time(GlideTime::Samples(8.0));
+
+    for i in 0..2000 {
+        // short glides up and down, retriggered every 50 samples
+        if i % 50 == 0 {
+            osc.set_frequency(if i % 100 == 0 { 15000.0 } else { 200.0 });
+        }
+
+        let predicted = osc.next_wrap().is_some();
+        let before = osc.phase();
+        osc.next_sample();
+        assert_eq!(predicted, osc.phase() < before, "sample {i}");
+    }
+}
+
+#[test]
+fn test_glide_in_millis() {
+    let mut osc: NaiveOsc = NaiveOsc::new(48000.0, 100.0, Waveform::Saw);
+    osc.set_glide_time(GlideTime::Millis(10.0)); // 480 samples
+    osc.set_frequency(580.0);
+
+    for _ in 0..240 {
+        osc.next_sample();
+    }
+    assert!((osc.frequency() - 340.0).abs() < 1e-2);
+}
+
+#[test]
+fn test_exponential_glide_moves_fast_first() {
+    let mut osc = NaiveOsc::new(1000.0, 100.0, Waveform::Saw);
+    osc.set_glide_curve(GlideCurve::Exponential);
+    osc.set_glide_time(GlideTime::Samples(100.0));
+    osc.set_frequency(200.0);
+
+    for _ in 0..50 {
+        osc.next_sample();
+    }
+    // well past halfway at half the time
+    assert!(osc.frequency() > 190.0 && osc.frequency() < 200.0);
+}
+
+#[test]
+fn test_amplitude_glides() {
+    let mut osc = NaiveOsc::new(100.0, 1.0, Waveform::Square);
+    osc.set_glide_time(GlideTime::Samples(4.0));
+    osc.set_amplitude(0.0);
+
+    let samples: Vec<f32> = (0..5).map(|_| osc.next_sample()).collect();
+    assert!(approx_eq(samples[0], 0.75));
+    assert!(approx_eq(samples[1], 0.5));
+    assert!(approx_eq(samples[2], 0.25));
+    assert!(approx_eq(samples[3], 0.0));
+    assert!(approx_eq(samples[4], 0.0));
+}
+
+#[test]
+fn test_amplitude_without_glide_is_instant() {
+    let mut osc = NaiveOsc::new(4.0, 1.0, Waveform::Sine);
+    osc.set_amplitude(0.5);
+
+    assert!(approx_eq(osc.next_sample(), 0.5));
+    assert!(approx_eq(osc.amplitude(), 0.5));
+}
+
+#[test]
+fn test_reset_finishes_glide() {
+    let mut osc = NaiveOsc::new(1000.0, 100.0, Waveform::Saw);
+    osc.set_glide_time(GlideTime::Millis(100.0));
+    osc.set_frequency(250.0); // a quarter cycle per sample
+    osc.set_amplitude(0.5);
+    osc.next_sample();
+
+    osc.reset();
+    assert!(approx_eq(osc.frequency(), 250.0));
+    assert!(approx_eq(osc.next_sample(), -0.25)); // 0.5 * (2 * 0.25 - 1)
+}
+
+#[test]
+fn test_fill_with_fm_skips_glide() {
+    let mut osc = NaiveOsc::new(1000.0, 100.0, Waveform::Saw);
+    osc.set_glide_time(GlideTime::Millis(100.0));
+    osc.set_frequency(300.0);
+
+    let mut buffer = [0.0f32; 2];
+    osc.fill_with_fm(&mut buffer, &[250.0, 250.0]);
+    assert!(approx_eq(osc.frequency(), 250.0));
+    assert!(approx_eq(osc.phase(), 0.5));
+}
+
+#[test]
+fn test_f64_matches_f32() {
+    let mut single = NaiveOsc::<f32>::new(44100.0, 1234.0, Waveform::Sine);
+    let mut double = NaiveOsc::<f64>::new(44100.0, 1234.0, Waveform::Sine);
+
+    for _ in 0..1000 {
+        assert!((single.next_sample() as f64 - double.next_sample()).abs() < 1e-3);
+    }
+}
+
+#[test]
+fn test_f64_phase_keeps_precision() {
+    // a 0.1 Hz increment isn't exact in either type, but f32 drifts by
+    // far more over the ten seconds of one cycle
+    let mut single = NaiveOsc::<f32>::new(48000.0, 0.1, Waveform::Saw);
+    let mut double = NaiveOsc::<f64>::new(48000.0, 0.1, Waveform::Saw);
+    for _ in 0..480_000 {
+        single.next_sample();
+        double.next_sample();
+    }
+
+    let drift = |phase: f64| phase.min(1.0 - phase);
+    assert!(drift(double.phase()) < 1e-9);
+    assert!(drift(single.phase() as f64) > 1e-4);
+}
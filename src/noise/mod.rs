@@ -1,15 +1,23 @@
-enum NoiseType {
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    Float,
+    rng::{Rng, next_seed},
+};
+
+enum NoiseType<T: Float> {
     White,
     Pink {
-        b0: f32,
-        b1: f32,
-        b2: f32,
-        b3: f32,
-        b4: f32,
-        b5: f32,
+        b0: T,
+        b1: T,
+        b2: T,
+        b3: T,
+        b4: T,
+        b5: T,
     },
     Brown {
-        prev: f32,
+        prev: T,
     },
 }
 
@@ -19,28 +27,31 @@ enum NoiseType {
 /// defined frequency or phase. Each noise type has different spectral
 /// characteristics useful for various audio applications.
 ///
+/// Every generator draws from its own pseudo-random sequence, seeded
+/// differently for each new generator. [`NoiseGen::set_seed`] makes the
+/// sequence repeatable.
+///
 /// # Example
 ///
 /// ```
 /// use oscy::noise::NoiseGen;
 ///
-/// let mut noise = NoiseGen::white();
+/// let mut noise: NoiseGen = NoiseGen::white();
 /// let sample = noise.next_sample();
 /// assert!(sample >= -1.0 && sample <= 1.0);
 /// ```
-pub struct NoiseGen {
-    noise_type: NoiseType,
+pub struct NoiseGen<T: Float = f32> {
+    noise_type: NoiseType<T>,
+    rng: Rng,
 }
 
-impl NoiseGen {
+impl<T: Float> NoiseGen<T> {
     /// Creates a white noise generator.
     ///
     /// White noise has equal energy across all frequencies, producing
     /// a bright, hissing sound.
     pub fn white() -> Self {
-        Self {
-            noise_type: NoiseType::White,
-        }
+        Self::new(NoiseType::White)
     }
 
     /// Creates a pink noise generator using Paul Kellet's economy method.
@@ -48,16 +59,14 @@ impl NoiseGen {
     /// Pink noise has equal energy per octave (power decreases at 3dB/octave),
     /// producing a more natural, balanced sound often used for audio testing.
     pub fn pink() -> Self {
-        Self {
-            noise_type: NoiseType::Pink {
-                b0: 0.0,
-                b1: 0.0,
-                b2: 0.0,
-                b3: 0.0,
-                b4: 0.0,
-                b5: 0.0,
-            },
-        }
+        Self::new(NoiseType::Pink {
+            b0: T::ZERO,
+            b1: T::ZERO,
+            b2: T::ZERO,
+            b3: T::ZERO,
+            b4: T::ZERO,
+            b5: T::ZERO,
+        })
     }
 
     /// Creates a brown (Brownian) noise generator.
@@ -65,15 +74,27 @@ impl NoiseGen {
     /// Brown noise has power decreasing at 6dB/octave, producing a deep,
     /// rumbling sound similar to a waterfall or strong wind.
     pub fn brown() -> Self {
+        Self::new(NoiseType::Brown { prev: T::ZERO })
+    }
+
+    fn new(noise_type: NoiseType<T>) -> Self {
         Self {
-            noise_type: NoiseType::Brown { prev: 0.0 },
+            noise_type,
+            rng: Rng::new(next_seed()),
         }
     }
 
+    /// Seeds the generator, so the same seed repeats the same noise.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = Rng::new(seed);
+    }
+
     /// Generates and returns the next sample.
-    pub fn next_sample(&mut self) -> f32 {
+    pub fn next_sample(&mut self) -> T {
+        let white = self.rng.next_unit::<T>() * T::TWO - T::ONE;
+
         match &mut self.noise_type {
-            NoiseType::White => fastrand::f32() * 2.0 - 1.0,
+            NoiseType::White => white,
 
             NoiseType::Pink {
                 b0,
@@ -83,29 +104,28 @@ impl NoiseGen {
                 b4,
                 b5,
             } => {
-                let white = fastrand::f32() * 2.0 - 1.0;
-                *b0 = 0.99886 * *b0 + white * 0.0555179;
-                *b1 = 0.99332 * *b1 + white * 0.0750759;
-                *b2 = 0.96900 * *b2 + white * 0.1538520;
-                *b3 = 0.86650 * *b3 + white * 0.3104856;
-                *b4 = 0.55000 * *b4 + white * 0.5329522;
-                *b5 = -0.7616 * *b5 - white * 0.0168980;
-                (*b0 + *b1 + *b2 + *b3 + *b4 + *b5 + white * 0.5362) * 0.11
+                let c = T::from_f64;
+                *b0 = c(0.99886) * *b0 + white * c(0.0555179);
+                *b1 = c(0.99332) * *b1 + white * c(0.0750759);
+                *b2 = c(0.96900) * *b2 + white * c(0.1538520);
+                *b3 = c(0.86650) * *b3 + white * c(0.3104856);
+                *b4 = c(0.55000) * *b4 + white * c(0.5329522);
+                *b5 = c(-0.7616) * *b5 - white * c(0.0168980);
+                (*b0 + *b1 + *b2 + *b3 + *b4 + *b5 + white * c(0.5362)) * c(0.11)
             }
 
             NoiseType::Brown { prev } => {
-                let white = fastrand::f32() * 2.0 - 1.0;
-                *prev = (*prev + 0.02 * white) / 1.02;
-                *prev * 3.5
+                *prev = (*prev + T::from_f64(0.02) * white) / T::from_f64(1.02);
+                *prev * T::from_f64(3.5)
             }
         }
     }
 }
 
-impl Iterator for NoiseGen {
-    type Item = f32;
+impl<T: Float> Iterator for NoiseGen<T> {
+    type Item = T;
 
-    fn next(&mut self) -> Option<f32> {
+    fn next(&mut self) -> Option<T> {
         Some(self.next_sample())
     }
 }
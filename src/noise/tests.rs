@@ -0,0 +1,48 @@
+use super::NoiseGen;
+
+#[test]
+fn test_white_stays_in_range() {
+    let noise: NoiseGen = NoiseGen::white();
+    assert!(
+        noise
+            .take(10_000)
+            .all(|sample| (-1.0..1.0).contains(&sample))
+    );
+}
+
+#[test]
+fn test_seed_repeats_noise() {
+    let mut a: NoiseGen = NoiseGen::pink();
+    let mut b: NoiseGen = NoiseGen::pink();
+    a.set_seed(7);
+    b.set_seed(7);
+
+    for _ in 0..1000 {
+        assert_eq!(a.next_sample(), b.next_sample());
+    }
+}
+
+#[test]
+fn test_generators_differ() {
+    let a: NoiseGen = NoiseGen::white();
+    let b: NoiseGen = NoiseGen::white();
+    assert!(a.zip(b).take(100).any(|(a, b)| a != b));
+}
+
+#[test]
+fn test_f64_white_uses_full_precision() {
+    // f32 resolution would leave every sample on a multiple of 2^-23
+    let noise: NoiseGen<f64> = NoiseGen::white();
+    let step = 2f64.powi(-23);
+    assert!(noise.take(100).any(|sample| sample % step != 0.0));
+}
+
+#[test]
+fn test_brown_has_no_dc_drift() {
+    let mut noise: NoiseGen<f64> = NoiseGen::brown();
+    noise.set_seed(1);
+
+    // the leak keeps the walk near zero
+    let mean = noise.take(100_000).sum::<f64>() / 100_000.0;
+    assert!(mean.abs() < 0.2);
+}
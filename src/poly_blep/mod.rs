@@ -1,9 +1,14 @@
 #[cfg(test)]
 mod tests;
 
-use std::f32::consts::TAU;
-
-use crate::{Oscillator, Waveform};
+use crate::{
+    Float, Oscillator, Waveform, advance_phase, morph_weights,
+    naive::{naive_pulse, naive_saw, naive_triangle},
+    next_wrap,
+    sine::SineMode,
+    smooth::{GlideCurve, GlideTime, Smoother},
+    wrap,
+};
 
 /// A bandlimited oscillator using polynomial bandlimited step (polyBLEP).
 ///
@@ -11,8 +16,8 @@ use crate::{Oscillator, Waveform};
 /// waveform discontinuities. This produces cleaner sound than naive
 /// oscillators, especially at higher frequencies.
 ///
-/// Note: Triangle waves have no step discontinuities, only derivative
-/// discontinuities, so they don't benefit from polyBLEP correction.
+/// Triangle waves have no step discontinuities, only slope discontinuities,
+/// so they are corrected with the integrated form of polyBLEP (polyBLAMP).
 ///
 /// # Example
 ///
@@ -23,24 +28,106 @@ use crate::{Oscillator, Waveform};
 /// let sample = osc.next_sample();
 /// assert!(sample >= -1.0 && sample <= 1.0);
 /// ```
-pub struct PolyBlepOsc {
-    phase: f32,
-    phase_increment: f32,
-    sample_rate: f32,
+pub struct PolyBlepOsc<T: Float = f32> {
+    phase: T,
+    phase_increment: T,
+    sample_rate: T,
     waveform: Waveform,
+    pulse_width: T,
+    shape: T,
+    sine_mode: SineMode,
+    // glided frequency in hertz and output amplitude
+    frequency: Smoother<T>,
+    amplitude: Smoother<T>,
+    // master wrap reported by `sync`, applied during the step after the next sample
+    sync_next: Option<T>,
+    // (fraction, remaining height) of a sync reset happening during the coming step
+    sync_reset: Option<(T, T)>,
 }
 
-impl PolyBlepOsc {
+impl<T: Float> PolyBlepOsc<T> {
     /// Creates a new polyBLEP oscillator.
-    pub fn new(sample_rate: f32, frequency: f32, waveform: Waveform) -> Self {
+    pub fn new(sample_rate: T, frequency: T, waveform: Waveform) -> Self {
         Self {
-            phase: 0.0,
+            phase: T::ZERO,
             phase_increment: frequency / sample_rate,
             sample_rate,
             waveform,
+            pulse_width: T::HALF,
+            shape: T::ZERO,
+            sine_mode: SineMode::Exact,
+            frequency: Smoother::new(frequency),
+            amplitude: Smoother::new(T::ONE),
+            sync_next: None,
+            sync_reset: None,
         }
     }
 
+    /// Sets the pulse width used by [`Waveform::Pulse`].
+    ///
+    /// The width is the fraction of the cycle spent high, clamped to
+    /// [0.0, 1.0]. It's cheap to call every sample, so it can be driven by
+    /// an LFO or audio-rate signal for pulse width modulation.
+    pub fn set_pulse_width(&mut self, width: T) {
+        self.pulse_width = width.clamp(T::ZERO, T::ONE);
+    }
+
+    /// Sets the morph shape used by [`Waveform::Morph`].
+    ///
+    /// The shape is clamped to [0.0, 1.0] and moves through sine (0.0),
+    /// triangle (1/3), saw (2/3) and square (1.0), crossfading between
+    /// neighbouring waveforms in between. Each waveform is band-limited
+    /// before blending, so every correction is scaled by the amount of its
+    /// discontinuity present in the mix.
+    pub fn set_shape(&mut self, shape: T) {
+        self.shape = shape.clamp(T::ZERO, T::ONE);
+    }
+
+    /// Sets how [`Waveform::Sine`] is evaluated, exactly by default.
+    ///
+    /// The sine in [`Waveform::Morph`] follows it too. See [`SineMode`] for
+    /// the error and distortion of each.
+    pub fn set_sine_mode(&mut self, mode: SineMode) {
+        self.sine_mode = mode;
+    }
+
+    /// Sets how long frequency and amplitude changes glide for.
+    ///
+    /// Zero, the default, makes [`Oscillator::set_frequency`] and
+    /// [`PolyBlepOsc::set_amplitude`] take effect at once. Otherwise each new
+    /// frequency or amplitude glides from the current one over this time,
+    /// for portamento and click-free level changes. A glide in progress
+    /// continues from where it is over the new time.
+    pub fn set_glide_time(&mut self, time: GlideTime<T>) {
+        let samples = time.to_samples(self.sample_rate);
+        self.frequency.set_time(samples);
+        self.amplitude.set_time(samples);
+    }
+
+    /// Sets the curve of frequency and amplitude glides, linear by default.
+    pub fn set_glide_curve(&mut self, curve: GlideCurve) {
+        self.frequency.set_curve(curve);
+        self.amplitude.set_curve(curve);
+    }
+
+    /// Sets the amplitude the output is scaled by, 1.0 by default.
+    ///
+    /// Changes glide like the frequency, see [`PolyBlepOsc::set_glide_time`].
+    pub fn set_amplitude(&mut self, amplitude: T) {
+        self.amplitude.set_target(amplitude);
+    }
+
+    /// Returns the current amplitude, which lags the one set while gliding.
+    pub fn amplitude(&self) -> T {
+        self.amplitude.value()
+    }
+
+    /// Returns the current frequency in hertz, which lags the one set
+    /// while gliding.
+    pub fn frequency(&self) -> T {
+        self.frequency.value()
+    }
+
     /// Computes the polyBLEP correction for a given phase.
     ///
     /// Returns a correction value to smooth discontinuities:
@@ -49,70 +136,274 @@ impl PolyBlepOsc {
     ///
     /// The correction uses a 2-sample polynomial residual that integrates
     /// to zero, preserving the waveform's DC offset.
-    pub fn poly_blep(&self, phase: f32) -> f32 {
-        // Just after discontinuity: phase in (0, phase_increment)
-        if phase < self.phase_increment {
-            let t = phase / self.phase_increment;
-            return 2.0 * t - t * t - 1.0;
+    ///
+    /// The residual is symmetric in time, so the same correction holds for
+    /// negative frequencies, where the phase runs backwards.
+    pub fn poly_blep(&self, phase: T) -> T {
+        poly_blep(phase, self.phase_increment.abs())
+    }
+
+    /// Computes the polyBLAMP correction for a given phase.
+    ///
+    /// PolyBLAMP (bandlimited ramp) is the integral of the polyBLEP residual
+    /// and smooths slope discontinuities, such as the corners of a triangle
+    /// wave. The returned value is the residual for a unit slope change
+    /// measured in samples, so callers scale it by the slope change per
+    /// sample.
+    pub fn poly_blamp(&self, phase: T) -> T {
+        poly_blamp(phase, self.phase_increment.abs())
+    }
+
+    fn sine(&self) -> T {
+        self.sine_mode.sine(self.phase)
+    }
+
+    fn saw(&self) -> T {
+        naive_saw(self.phase) - self.poly_blep(self.phase)
+    }
+
+    fn pulse(&self, width: T) -> T {
+        // falling edge sits at the pulse width
+        let falling = (self.phase + T::ONE - width).fract();
+        naive_pulse(self.phase, width) + self.poly_blep(self.phase) - self.poly_blep(falling)
+    }
+
+    fn triangle(&self) -> T {
+        // slope changes by +8 at phase 0 and by -8 at phase 0.5,
+        // expressed per sample for the polyBLAMP residual. Running
+        // backwards mirrors both the slopes and time, so the sign holds.
+        let slope_change = T::from_f64(8.0) * self.phase_increment.abs();
+        naive_triangle(self.phase)
+            + slope_change
+                * (self.poly_blamp(self.phase) - self.poly_blamp((self.phase + T::HALF).fract()))
+    }
+
+    fn morph(&self) -> T {
+        let [sine, triangle, saw, square] = morph_weights(self.shape);
+
+        // only evaluate the (at most two) waveforms present in the blend
+        let mut out = T::ZERO;
+        if sine > T::ZERO {
+            out += sine * self.sine();
+        }
+        if triangle > T::ZERO {
+            out += triangle * self.triangle();
+        }
+        if saw > T::ZERO {
+            out += saw * self.saw();
+        }
+        if square > T::ZERO {
+            out += square * self.pulse(T::HALF);
+        }
+        out
+    }
+
+    /// Moves frequency and amplitude glides one sample further.
+    fn glide(&mut self) {
+        if self.frequency.is_gliding() {
+            self.phase_increment = self.frequency.next_value() / self.sample_rate;
+        }
+        self.amplitude.next_value();
+    }
+
+    /// Moves the phase one step forward, returning the sync reset that
+    /// happened during the step, if any.
+    fn advance(&mut self) -> Option<(T, T)> {
+        let reset = self.sync_reset.take();
+
+        match reset {
+            // restart the cycle at the master's wrap, then run the rest of the step
+            Some((fraction, _)) => {
+                self.phase = advance_phase(T::ZERO, (T::ONE - fraction) * self.phase_increment)
+            }
+            None => self.phase = advance_phase(self.phase, self.phase_increment),
+        }
+
+        // glide afterwards, so `next_wrap` sees the increment of the next step
+        self.glide();
+
+        reset
+    }
+
+    /// Computes the band-limited sample at the current phase.
+    fn render(&mut self, reset: Option<(T, T)>) -> T {
+        let mut sample = match self.waveform {
+            Waveform::Sine => self.sine(),
+            Waveform::Saw => self.saw(),
+            Waveform::Square => self.pulse(T::HALF),
+            Waveform::Pulse => self.pulse(self.pulse_width),
+            Waveform::Triangle => self.triangle(),
+            Waveform::Morph => self.morph(),
+        };
+
+        if let Some((fraction, height)) = reset {
+            // second half of the polyBLEP residual for the sync reset
+            sample -= T::HALF * height * fraction * fraction;
+        }
+
+        if let Some(fraction) = self.sync_next.take() {
+            sample += self.begin_sync(fraction);
         }
 
-        // Just before discontinuity: phase in (1 - phase_increment, 1)
-        if phase > 1.0 - self.phase_increment {
-            let t = (phase - 1.0) / self.phase_increment;
-            return t * t + 2.0 * t + 1.0;
+        sample * self.amplitude.value()
+    }
+
+    /// Evaluates the uncorrected waveform at an arbitrary phase.
+    fn naive(&self, phase: T) -> T {
+        match self.waveform {
+            Waveform::Sine => self.sine_mode.sine(phase),
+            Waveform::Saw => naive_saw(phase),
+            Waveform::Square => naive_pulse(phase, T::HALF),
+            Waveform::Pulse => naive_pulse(phase, self.pulse_width),
+            Waveform::Triangle => naive_triangle(phase),
+            Waveform::Morph => {
+                let [sine, triangle, saw, square] = morph_weights(self.shape);
+                sine * self.sine_mode.sine(phase)
+                    + triangle * naive_triangle(phase)
+                    + saw * naive_saw(phase)
+                    + square * naive_pulse(phase, T::HALF)
+            }
         }
+    }
+
+    /// Schedules a sync reset `fraction` of the way into the next step and
+    /// returns the polyBLEP correction for the current sample, which lies
+    /// just before the reset.
+    fn begin_sync(&mut self, fraction: T) -> T {
+        let reset_phase = advance_phase(self.phase, fraction * self.phase_increment);
+        let before = self.naive(reset_phase);
 
-        0.0
+        // the cycle restarts at phase 0 and continues towards 1 when running
+        // forwards, or wraps straight to the end of the cycle when running
+        // backwards
+        let (start, end) = if self.phase_increment >= T::ZERO {
+            (self.naive(T::ZERO), self.naive(T::ONE))
+        } else {
+            (self.naive(T::ONE), self.naive(T::ZERO))
+        };
+
+        // right after the reset the regular correction already smooths the
+        // step across the cycle boundary, so only the remaining height is
+        // carried over to the next sample
+        self.sync_reset = Some((fraction, end - before));
+
+        let t = T::ONE - fraction;
+        T::HALF * (start - before) * t * t
     }
 }
 
-impl Oscillator for PolyBlepOsc {
-    fn set_frequency(&mut self, hz: f32) {
-        self.phase_increment = hz / self.sample_rate
+impl<T: Float> Oscillator<T> for PolyBlepOsc<T> {
+    /// Sets the frequency in hertz, gliding there if a glide time is set.
+    fn set_frequency(&mut self, hz: T) {
+        self.frequency.set_target(hz);
+        self.phase_increment = self.frequency.value() / self.sample_rate
+    }
+
+    fn set_phase(&mut self, phase: T) {
+        self.phase = wrap(phase);
     }
 
-    fn set_phase(&mut self, phase: f32) {
-        self.phase = phase.fract();
+    fn phase(&self) -> T {
+        self.phase
     }
 
+    /// Restarts the cycle and finishes any glide at once.
     fn reset(&mut self) {
-        self.phase = 0.0;
+        self.phase = T::ZERO;
+        self.sync_next = None;
+        self.sync_reset = None;
+        self.frequency.settle();
+        self.amplitude.settle();
+        self.phase_increment = self.frequency.value() / self.sample_rate;
+    }
+
+    fn next_wrap(&self) -> Option<T> {
+        if let Some((fraction, _)) = self.sync_reset {
+            return Some(fraction);
+        }
+
+        next_wrap(self.phase, self.phase_increment)
     }
 
-    fn next_sample(&mut self) -> f32 {
-        self.phase += self.phase_increment;
+    fn sync(&mut self, fraction: T) {
+        self.sync_next = Some(fraction.clamp(T::ZERO, T::ONE));
+    }
 
-        // subtraction only when needed.
-        // cheaper than fract()/modulo every sample
-        if self.phase >= 1.0 {
-            self.phase -= 1.0;
+    fn set_waveform(&mut self, waveform: Waveform) {
+        self.waveform = waveform;
+    }
+
+    fn next_sample(&mut self) -> T {
+        let reset = self.advance();
+        self.render(reset)
+    }
+
+    fn fill_with_fm(&mut self, buffer: &mut [T], frequencies: &[T]) {
+        let inv_sample_rate = T::ONE / self.sample_rate;
+
+        for (sample, &hz) in buffer.iter_mut().zip(frequencies) {
+            // audio-rate modulation doesn't glide
+            self.frequency.jump(hz);
+            self.phase_increment = hz * inv_sample_rate;
+            let reset = self.advance();
+            *sample = self.render(reset);
         }
+    }
 
-        match self.waveform {
-            Waveform::Sine => (self.phase * TAU).sin(),
-            Waveform::Saw => {
-                let naive = 2.0 * self.phase - 1.0;
-                naive - self.poly_blep(self.phase)
-            }
-            Waveform::Square => {
-                let naive = if self.phase < 0.5 { 1.0 } else { -1.0 };
-                naive + self.poly_blep(self.phase) - self.poly_blep((self.phase + 0.5).fract())
-            }
-            Waveform::Triangle => {
-                if self.phase < 0.5 {
-                    4.0 * self.phase - 1.0
-                } else {
-                    -4.0 * self.phase + 3.0
-                }
-            }
+    fn fill_with_pm(&mut self, buffer: &mut [T], phase_offsets: &[T]) {
+        for (sample, &offset) in buffer.iter_mut().zip(phase_offsets) {
+            let reset = self.advance();
+
+            // read the waveform (and its corrections) at the shifted phase,
+            // leaving the accumulator itself untouched
+            let phase = self.phase;
+            self.phase = wrap(phase + offset);
+            *sample = self.render(reset);
+            self.phase = phase;
         }
     }
 }
 
-impl Iterator for PolyBlepOsc {
-    type Item = f32;
+impl<T: Float> Iterator for PolyBlepOsc<T> {
+    type Item = T;
 
-    fn next(&mut self) -> Option<f32> {
+    fn next(&mut self) -> Option<T> {
         Some(self.next_sample())
     }
 }
+
+/// Computes the polyBLEP correction at `phase` for a phase increment of
+/// `dt`, see [`PolyBlepOsc::poly_blep`].
+pub(crate) fn poly_blep<T: Float>(phase: T, dt: T) -> T {
+    // Just after discontinuity: phase in (0, dt)
+    if phase < dt {
+        let t = phase / dt;
+        return T::TWO * t - t * t - T::ONE;
+    }
+
+    // Just before discontinuity: phase in (1 - dt, 1)
+    if phase > T::ONE - dt {
+        let t = (phase - T::ONE) / dt;
+        return t * t + T::TWO * t + T::ONE;
+    }
+
+    T::ZERO
+}
+
+/// Computes the polyBLAMP correction at `phase` for a phase increment of
+/// `dt`, see [`PolyBlepOsc::poly_blamp`].
+pub(crate) fn poly_blamp<T: Float>(phase: T, dt: T) -> T {
+    // Just after discontinuity: phase in (0, dt)
+    if phase < dt {
+        let t = phase / dt - T::ONE;
+        return -t * t * t / T::from_f64(3.0);
+    }
+
+    // Just before discontinuity: phase in (1 - dt, 1)
+    if phase > T::ONE - dt {
+        let t = (phase - T::ONE) / dt + T::ONE;
+        return t * t * t / T::from_f64(3.0);
+    }
+
+    T::ZERO
+}
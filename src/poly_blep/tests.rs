@@ -1,7 +1,13 @@
 use std::f32::consts::TAU;
 
 use super::PolyBlepOsc;
-use crate::{Oscillator, Waveform, naive::NaiveOsc};
+use crate::{
+    Event, EventKind, Oscillator, Waveform,
+    naive::NaiveOsc,
+    sine::SineMode,
+    smooth::{GlideCurve, GlideTime},
+    test_utils::{ANALYSIS_LEN, aliasing_ratio, analysis_frequency},
+};
 
 const EPSILON: f32 = 1e-6;
 
@@ -81,6 +87,20 @@ fn test_sine_matches_naive() {
     }
 }
 
+#[test]
+fn test_sine_mode_matches_naive() {
+    for mode in [SineMode::Polynomial, SineMode::Table] {
+        let mut poly = PolyBlepOsc::new(44100.0, 440.0, Waveform::Sine);
+        let mut naive = NaiveOsc::new(44100.0, 440.0, Waveform::Sine);
+        poly.set_sine_mode(mode);
+        naive.set_sine_mode(mode);
+
+        for _ in 0..1000 {
+            assert_eq!(poly.next_sample(), naive.next_sample());
+        }
+    }
+}
+
 // Saw wave tests
 
 #[test]
@@ -89,7 +109,7 @@ fn test_saw_output_range() {
 
     for _ in 0..1000 {
         let sample = osc.next_sample();
-        assert!(sample >= -1.0 && sample <= 1.0);
+        assert!((-1.0..=1.0).contains(&sample));
     }
 }
 
@@ -139,7 +159,7 @@ fn test_square_output_range() {
 
     for _ in 0..1000 {
         let sample = osc.next_sample();
-        assert!(sample >= -1.0 && sample <= 1.0);
+        assert!((-1.0..=1.0).contains(&sample));
     }
 }
 
@@ -175,25 +195,380 @@ fn test_square_high_low_regions() {
     assert!(low_count > 30 && low_count < 70);
 }
 
-// Triangle wave tests (no polyBLEP, same as naive)
+// Pulse wave tests
 
 #[test]
-fn test_triangle_peaks_at_midpoint() {
-    let mut osc = PolyBlepOsc::new(4.0, 1.0, Waveform::Triangle);
+fn test_pulse_output_range() {
+    let mut osc = PolyBlepOsc::new(44100.0, 440.0, Waveform::Pulse);
+    osc.set_pulse_width(0.2);
 
-    assert!(approx_eq(osc.next_sample(), 0.0)); // 4 * 0.25 - 1
-    assert!(approx_eq(osc.next_sample(), 1.0)); // -4 * 0.5 + 3
-    assert!(approx_eq(osc.next_sample(), 0.0)); // -4 * 0.75 + 3
-    assert!(approx_eq(osc.next_sample(), -1.0)); // 4 * 0.0 - 1
+    for _ in 0..1000 {
+        let sample = osc.next_sample();
+        assert!((-1.0..=1.0).contains(&sample));
+    }
 }
 
 #[test]
-fn test_triangle_matches_naive() {
-    let mut poly = PolyBlepOsc::new(100.0, 5.0, Waveform::Triangle);
-    let mut naive = NaiveOsc::new(100.0, 5.0, Waveform::Triangle);
+fn test_pulse_width_half_matches_square() {
+    let mut pulse = PolyBlepOsc::new(44100.0, 1234.0, Waveform::Pulse);
+    let mut square = PolyBlepOsc::new(44100.0, 1234.0, Waveform::Square);
 
-    for _ in 0..100 {
-        assert!(approx_eq(poly.next_sample(), naive.next_sample()));
+    for _ in 0..1000 {
+        assert!(approx_eq(pulse.next_sample(), square.next_sample()));
+    }
+}
+
+#[test]
+fn test_pulse_smooths_moving_falling_edge() {
+    // 20 samples per cycle (inc = 0.05)
+    let mut poly = PolyBlepOsc::new(20.0, 1.0, Waveform::Pulse);
+    let mut naive = NaiveOsc::new(20.0, 1.0, Waveform::Pulse);
+    poly.set_pulse_width(0.25);
+    naive.set_pulse_width(0.25);
+
+    let poly_samples: Vec<f32> = (0..20).map(|_| poly.next_sample()).collect();
+    let naive_samples: Vec<f32> = (0..20).map(|_| naive.next_sample()).collect();
+
+    // Falling edge at phase 0.25: sample 4 lands right on it
+    assert!((poly_samples[4] - naive_samples[4]).abs() > 0.1);
+
+    // Phase 0.5 is no longer an edge, so sample 9 is untouched
+    assert!(approx_eq(poly_samples[9], naive_samples[9]));
+}
+
+#[test]
+fn test_pulse_width_modulation_per_sample() {
+    let mut osc = PolyBlepOsc::new(44100.0, 220.0, Waveform::Pulse);
+
+    for i in 0..2000 {
+        let lfo = (i as f32 / 2000.0 * TAU).sin();
+        osc.set_pulse_width(0.5 + 0.4 * lfo);
+        let sample = osc.next_sample();
+        assert!((-1.0..=1.0).contains(&sample));
+    }
+}
+
+// Morph tests
+
+#[test]
+fn test_morph_endpoints_match_waveforms() {
+    let shapes = [
+        (0.0, Waveform::Sine),
+        (1.0 / 3.0, Waveform::Triangle),
+        (2.0 / 3.0, Waveform::Saw),
+        (1.0, Waveform::Square),
+    ];
+
+    for (shape, waveform) in shapes {
+        let mut morph = PolyBlepOsc::new(44100.0, 1234.0, Waveform::Morph);
+        let mut reference: PolyBlepOsc = PolyBlepOsc::new(44100.0, 1234.0, waveform);
+        morph.set_shape(shape);
+
+        for _ in 0..1000 {
+            assert!((morph.next_sample() - reference.next_sample()).abs() < 1e-5);
+        }
+    }
+}
+
+#[test]
+fn test_morph_is_continuous_in_shape() {
+    // A tiny change of shape should only nudge the output
+    let mut a = PolyBlepOsc::new(44100.0, 440.0, Waveform::Morph);
+    let mut b: PolyBlepOsc = PolyBlepOsc::new(44100.0, 440.0, Waveform::Morph);
+    a.set_shape(0.5);
+    b.set_shape(0.501);
+
+    for _ in 0..1000 {
+        assert!((a.next_sample() - b.next_sample()).abs() < 0.01);
+    }
+}
+
+#[test]
+fn test_morph_sweep_output_range() {
+    let mut osc = PolyBlepOsc::new(44100.0, 440.0, Waveform::Morph);
+
+    for i in 0..4410 {
+        osc.set_shape(i as f32 / 4410.0);
+        let sample = osc.next_sample();
+        assert!((-1.0..=1.0).contains(&sample));
+    }
+}
+
+// Through-zero tests
+
+#[test]
+fn test_negative_frequency_mirrors_saw() {
+    let mut forwards = PolyBlepOsc::new(44100.0, 1234.0, Waveform::Saw);
+    let mut backwards: PolyBlepOsc = PolyBlepOsc::new(44100.0, -1234.0, Waveform::Saw);
+
+    // running backwards from phase 0 flips the ramp and its corrections
+    for _ in 0..1000 {
+        assert!((forwards.next_sample() + backwards.next_sample()).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn test_through_zero_fm_stays_bounded() {
+    let waveforms = [
+        Waveform::Sine,
+        Waveform::Saw,
+        Waveform::Square,
+        Waveform::Pulse,
+        Waveform::Triangle,
+        Waveform::Morph,
+    ];
+
+    for waveform in waveforms {
+        let mut osc = PolyBlepOsc::new(44100.0, 0.0, waveform);
+        osc.set_shape(0.8);
+
+        for i in 0..10_000 {
+            // deep linear FM: carrier 200Hz, deviation 5kHz
+            let modulator = (i as f32 / 300.0 * TAU).sin();
+            osc.set_frequency(200.0 + 5000.0 * modulator);
+            let sample = osc.next_sample();
+            assert!(sample.abs() <= 1.0 + 1e-4);
+        }
+    }
+}
+
+// polyBLAMP tests
+
+#[test]
+fn test_poly_blamp_zero_outside_discontinuity() {
+    let osc = PolyBlepOsc::new(10.0, 1.0, Waveform::Triangle); // inc = 0.1
+
+    assert!(approx_eq(osc.poly_blamp(0.5), 0.0));
+    assert!(approx_eq(osc.poly_blamp(0.3), 0.0));
+    assert!(approx_eq(osc.poly_blamp(0.7), 0.0));
+}
+
+#[test]
+fn test_poly_blamp_boundary_values() {
+    let osc = PolyBlepOsc::new(10.0, 1.0, Waveform::Triangle); // inc = 0.1
+
+    // At t=0 (phase=0): -(0 - 1)^3 / 3 = 1/3
+    assert!(approx_eq(osc.poly_blamp(0.0), 1.0 / 3.0));
+
+    // At t=-0.5 and t=0.5: 0.5^3 / 3
+    assert!(approx_eq(osc.poly_blamp(0.05), 0.125 / 3.0));
+    assert!(approx_eq(osc.poly_blamp(0.95), 0.125 / 3.0));
+}
+
+// Triangle wave tests
+
+#[test]
+fn test_triangle_output_range() {
+    let mut osc = PolyBlepOsc::new(44100.0, 440.0, Waveform::Triangle);
+
+    for _ in 0..1000 {
+        let sample = osc.next_sample();
+        assert!((-1.0..=1.0).contains(&sample));
+    }
+}
+
+#[test]
+fn test_triangle_matches_naive_away_from_corners() {
+    // 20 samples per cycle (inc = 0.05), corners at phase 0.0 and 0.5
+    let mut poly = PolyBlepOsc::new(20.0, 1.0, Waveform::Triangle);
+    let mut naive = NaiveOsc::new(20.0, 1.0, Waveform::Triangle);
+
+    let poly_samples: Vec<f32> = (0..20).map(|_| poly.next_sample()).collect();
+    let naive_samples: Vec<f32> = (0..20).map(|_| naive.next_sample()).collect();
+
+    // Sample 4 is at phase 0.25, mid-slope
+    assert!(approx_eq(poly_samples[4], naive_samples[4]));
+    // Sample 14 is at phase 0.75, mid-slope
+    assert!(approx_eq(poly_samples[14], naive_samples[14]));
+}
+
+#[test]
+fn test_triangle_rounds_corners() {
+    let mut poly = PolyBlepOsc::new(20.0, 1.0, Waveform::Triangle);
+    let mut naive = NaiveOsc::new(20.0, 1.0, Waveform::Triangle);
+
+    let poly_samples: Vec<f32> = (0..20).map(|_| poly.next_sample()).collect();
+    let naive_samples: Vec<f32> = (0..20).map(|_| naive.next_sample()).collect();
+
+    // Peak at phase 0.5 (sample 9) is pulled down
+    assert!(poly_samples[9] < naive_samples[9]);
+    // Trough at phase 0.0 (sample 19) is pulled up
+    assert!(poly_samples[19] > naive_samples[19]);
+}
+
+// Aliasing tests
+
+fn aliasing(waveform_poly: Waveform, waveform_naive: Waveform) -> (f32, f32) {
+    // 233 cycles in 4096 samples at 44.1kHz is roughly 2.5kHz
+    let cycles = 233;
+    let frequency = analysis_frequency(44100.0, cycles);
+
+    let poly = PolyBlepOsc::new(44100.0, frequency, waveform_poly);
+    let naive = NaiveOsc::new(44100.0, frequency, waveform_naive);
+
+    let poly_samples: Vec<f32> = poly.take(ANALYSIS_LEN).collect();
+    let naive_samples: Vec<f32> = naive.take(ANALYSIS_LEN).collect();
+
+    (
+        aliasing_ratio(&poly_samples, cycles),
+        aliasing_ratio(&naive_samples, cycles),
+    )
+}
+
+#[test]
+fn test_saw_aliases_less_than_naive() {
+    let (poly, naive) = aliasing(Waveform::Saw, Waveform::Saw);
+    assert!(poly < naive * 0.1);
+}
+
+#[test]
+fn test_pulse_aliases_less_than_naive() {
+    let cycles = 233;
+    let frequency = analysis_frequency(44100.0, cycles);
+
+    let mut poly = PolyBlepOsc::new(44100.0, frequency, Waveform::Pulse);
+    let mut naive = NaiveOsc::new(44100.0, frequency, Waveform::Pulse);
+    poly.set_pulse_width(0.3);
+    naive.set_pulse_width(0.3);
+
+    let poly_samples: Vec<f32> = poly.take(ANALYSIS_LEN).collect();
+    let naive_samples: Vec<f32> = naive.take(ANALYSIS_LEN).collect();
+
+    let poly = aliasing_ratio(&poly_samples, cycles);
+    let naive = aliasing_ratio(&naive_samples, cycles);
+    assert!(poly < naive * 0.1);
+}
+
+#[test]
+fn test_morph_aliases_less_than_naive() {
+    let cycles = 233;
+    let frequency = analysis_frequency(44100.0, cycles);
+
+    // between saw and square both step corrections are weighted
+    for shape in [0.5, 0.8] {
+        let mut poly = PolyBlepOsc::new(44100.0, frequency, Waveform::Morph);
+        let mut naive = NaiveOsc::new(44100.0, frequency, Waveform::Morph);
+        poly.set_shape(shape);
+        naive.set_shape(shape);
+
+        let poly_samples: Vec<f32> = poly.take(ANALYSIS_LEN).collect();
+        let naive_samples: Vec<f32> = naive.take(ANALYSIS_LEN).collect();
+
+        let poly = aliasing_ratio(&poly_samples, cycles);
+        let naive = aliasing_ratio(&naive_samples, cycles);
+        assert!(poly < naive * 0.1);
+    }
+}
+
+#[test]
+fn test_tiny_negative_phase_stays_below_one() {
+    // a step from 1e-9 back by 2e-9 lands at -1e-9, which rounds to 1.0
+    let mut osc: PolyBlepOsc = PolyBlepOsc::new(1.0, -2e-9, Waveform::Saw);
+    osc.set_phase(1e-9);
+    osc.next_sample();
+    assert!(osc.phase() < 1.0);
+
+    osc.set_phase(-1e-9);
+    assert!(osc.phase() < 1.0);
+}
+
+#[test]
+fn test_negative_frequency_aliases_less_than_naive() {
+    let cycles = 233;
+    let frequency = -analysis_frequency(44100.0, cycles);
+
+    for waveform in [Waveform::Saw, Waveform::Square, Waveform::Pulse] {
+        let mut poly = PolyBlepOsc::new(44100.0, frequency, waveform);
+        let mut naive = NaiveOsc::new(44100.0, frequency, waveform);
+        poly.set_pulse_width(0.3);
+        naive.set_pulse_width(0.3);
+
+        let poly_samples: Vec<f32> = poly.take(ANALYSIS_LEN).collect();
+        let naive_samples: Vec<f32> = naive.take(ANALYSIS_LEN).collect();
+
+        let poly = aliasing_ratio(&poly_samples, cycles);
+        let naive = aliasing_ratio(&naive_samples, cycles);
+        assert!(poly < naive * 0.1);
+    }
+
+    let poly = PolyBlepOsc::new(44100.0, frequency, Waveform::Triangle);
+    let naive = NaiveOsc::new(44100.0, frequency, Waveform::Triangle);
+    let poly_samples: Vec<f32> = poly.take(ANALYSIS_LEN).collect();
+    let naive_samples: Vec<f32> = naive.take(ANALYSIS_LEN).collect();
+    assert!(aliasing_ratio(&poly_samples, cycles) < aliasing_ratio(&naive_samples, cycles) * 0.5);
+}
+
+#[test]
+fn test_triangle_aliases_less_than_naive() {
+    // Triangle harmonics fall off at 12dB/octave, so most of the remaining
+    // aliasing comes from partials folding back just below Nyquist, which a
+    // 2-sample residual barely attenuates.
+    let (poly, naive) = aliasing(Waveform::Triangle, Waveform::Triangle);
+    assert!(poly < naive * 0.5);
+}
+
+// glide tests
+
+#[test]
+fn test_next_wrap_predicts_wraps_while_gliding() {
+    let mut osc = PolyBlepOsc::new(44100.0, 200.0, Waveform::Saw);
+    osc.set_glide_time(GlideTime::Samples(8.0));
+
+    for i in 0..2000 {
+        // short glides up and down, retriggered every 50 samples
+        if i % 50 == 0 {
+            osc.set_frequency(if i % 100 == 0 { 15000.0 } else { 200.0 });
+        }
+
+        let predicted = osc.next_wrap().is_some();
+        let before = osc.phase();
+        osc.next_sample();
+        assert_eq!(predicted, osc.phase() < before, "sample {i}");
+    }
+}
+
+#[test]
+fn test_glide_matches_naive() {
+    let mut blep = PolyBlepOsc::new(44100.0, 220.0, Waveform::Sine);
+    let mut naive = NaiveOsc::new(44100.0, 220.0, Waveform::Sine);
+    for curve in [GlideCurve::Linear, GlideCurve::Exponential] {
+        blep.set_glide_curve(curve);
+        naive.set_glide_curve(curve);
+        blep.set_glide_time(GlideTime::Millis(50.0));
+        naive.set_glide_time(GlideTime::Millis(50.0));
+        blep.set_frequency(1760.0);
+        naive.set_frequency(1760.0);
+        blep.set_amplitude(0.3);
+        naive.set_amplitude(0.3);
+
+        for _ in 0..4000 {
+            assert!(approx_eq(blep.next_sample(), naive.next_sample()));
+        }
+        assert!(approx_eq(blep.frequency(), 1760.0));
+        assert!(approx_eq(blep.amplitude(), 0.3));
+    }
+}
+
+#[test]
+fn test_amplitude_scales_corrections() {
+    let mut full = PolyBlepOsc::new(44100.0, 1234.0, Waveform::Square);
+    let mut half = PolyBlepOsc::new(44100.0, 1234.0, Waveform::Square);
+    half.set_amplitude(0.5);
+
+    for _ in 0..256 {
+        assert!(approx_eq(0.5 * full.next_sample(), half.next_sample()));
+    }
+}
+
+// sample type tests
+
+#[test]
+fn test_f64_matches_f32() {
+    let mut single = PolyBlepOsc::<f32>::new(44100.0, 1234.0, Waveform::Triangle);
+    let mut double = PolyBlepOsc::<f64>::new(44100.0, 1234.0, Waveform::Triangle);
+
+    for _ in 0..1000 {
+        assert!((single.next_sample() as f64 - double.next_sample()).abs() < 1e-3);
     }
 }
 
@@ -231,6 +606,21 @@ fn test_reset_zeros_phase() {
     assert!(approx_eq(osc.next_sample(), 1.0)); // sin(TAU * 0.25)
 }
 
+#[test]
+fn test_next_wrap_reports_fraction() {
+    let mut osc = PolyBlepOsc::new(10.0, 3.0, Waveform::Saw); // inc = 0.3
+
+    assert_eq!(osc.next_wrap(), None); // 0.0 -> 0.3
+    osc.next_sample();
+    osc.next_sample();
+    assert_eq!(osc.next_wrap(), None); // 0.6 -> 0.9
+    osc.next_sample();
+
+    // 0.9 -> 1.2 wraps a third of the way into the step
+    let fraction: f32 = osc.next_wrap().unwrap();
+    assert!((fraction - 1.0 / 3.0).abs() < 1e-5);
+}
+
 #[test]
 fn test_fill_buffer() {
     let mut osc = PolyBlepOsc::new(4.0, 1.0, Waveform::Sine);
@@ -244,6 +634,100 @@ fn test_fill_buffer() {
     assert!(approx_eq(buffer[3], 0.0));
 }
 
+#[test]
+fn test_phase_getter() {
+    let mut osc = PolyBlepOsc::new(4.0, 1.0, Waveform::Sine);
+    osc.next_sample();
+    assert!(approx_eq(osc.phase(), 0.25));
+
+    osc.set_phase(0.6);
+    assert!(approx_eq(osc.phase(), 0.6));
+}
+
+#[test]
+fn test_fill_with_fm_matches_per_sample() {
+    let mut block = PolyBlepOsc::new(44100.0, 440.0, Waveform::Saw);
+    let mut single = PolyBlepOsc::new(44100.0, 440.0, Waveform::Saw);
+
+    // through-zero sweep from -2kHz to 2kHz
+    let frequencies: Vec<f32> = (0..512).map(|i| -2000.0 + i as f32 * 8.0).collect();
+    let mut buffer = [0.0f32; 512];
+    block.fill_with_fm(&mut buffer, &frequencies);
+
+    for (sample, &hz) in buffer.iter().zip(&frequencies) {
+        single.set_frequency(hz);
+        assert!((sample - single.next_sample()).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn test_fill_with_pm_offsets_phase() {
+    let mut osc = PolyBlepOsc::new(4.0, 1.0, Waveform::Sine);
+    let mut buffer = [0.0f32; 4];
+
+    // phases 0.25, 0.5, 0.75, 0.0 shifted by a quarter cycle
+    osc.fill_with_pm(&mut buffer, &[0.25; 4]);
+
+    assert!(approx_eq(buffer[0], 0.0)); // sin(TAU * 0.5)
+    assert!(approx_eq(buffer[1], -1.0)); // sin(TAU * 0.75)
+    assert!(approx_eq(buffer[2], 0.0)); // sin(TAU * 1.0)
+    assert!(approx_eq(buffer[3], 1.0)); // sin(TAU * 1.25)
+}
+
+#[test]
+fn test_fill_with_pm_does_not_accumulate() {
+    let mut modulated = PolyBlepOsc::new(44100.0, 440.0, Waveform::Saw);
+    let mut plain = PolyBlepOsc::new(44100.0, 440.0, Waveform::Saw);
+
+    let offsets: Vec<f32> = (0..256).map(|i| 3.0 * (i as f32 * 0.1).sin()).collect();
+    let mut buffer = [0.0f32; 256];
+    modulated.fill_with_pm(&mut buffer, &offsets);
+    plain.fill(&mut buffer);
+
+    assert!(approx_eq(modulated.phase(), plain.phase()));
+}
+
+#[test]
+fn test_fill_with_pm_zero_offsets_matches_fill() {
+    let mut modulated = PolyBlepOsc::new(44100.0, 1234.0, Waveform::Square);
+    let mut plain = PolyBlepOsc::new(44100.0, 1234.0, Waveform::Square);
+
+    let mut pm_buffer = [0.0f32; 256];
+    let mut buffer = [0.0f32; 256];
+    modulated.fill_with_pm(&mut pm_buffer, &[0.0; 256]);
+    plain.fill(&mut buffer);
+
+    for (a, b) in pm_buffer.iter().zip(&buffer) {
+        assert!(approx_eq(*a, *b));
+    }
+}
+
+#[test]
+fn test_fill_with_events_switches_waveform() {
+    let mut block = PolyBlepOsc::new(44100.0, 1234.0, Waveform::Saw);
+    let mut single = PolyBlepOsc::new(44100.0, 1234.0, Waveform::Saw);
+    let events = [
+        Event::new(64, EventKind::Waveform(Waveform::Triangle)),
+        Event::new(64, EventKind::Frequency(2345.0)),
+        Event::new(200, EventKind::Waveform(Waveform::Square)),
+    ];
+
+    let mut buffer = [0.0f32; 256];
+    block.fill_with_events(&mut buffer, &events);
+
+    for (offset, sample) in buffer.iter().enumerate() {
+        match offset {
+            64 => {
+                single.set_waveform(Waveform::Triangle);
+                single.set_frequency(2345.0);
+            }
+            200 => single.set_waveform(Waveform::Square),
+            _ => {}
+        }
+        assert!(approx_eq(*sample, single.next_sample()));
+    }
+}
+
 #[test]
 fn test_iterator() {
     let osc = PolyBlepOsc::new(4.0, 1.0, Waveform::Sine);
@@ -0,0 +1,231 @@
+#[cfg(test)]
+mod tests;
+
+use crate::{Float, Oscillator, advance_phase, next_wrap, wrap};
+
+/// How many samples the recurrence of a [`RecursiveQuadratureOsc`] runs
+/// between amplitude renormalizations.
+const RENORMALIZE_INTERVAL: usize = 64;
+
+/// A sine/cosine pair computed directly from the phase.
+///
+/// Every sample evaluates both functions at the phase accumulator, so the
+/// pair is exactly 90° apart and the amplitude never drifts, for frequency
+/// shifters, single-sideband modulation and IQ test signals. Through the
+/// [`Oscillator`] trait it plays the sine.
+///
+/// See [`RecursiveQuadratureOsc`] for a cheaper recurrence.
+///
+/// # Example
+///
+/// ```
+/// use oscy::{quadrature::QuadratureOsc, Oscillator};
+///
+/// // at a quarter of the sample rate, the first pair is a quarter cycle in
+/// let mut osc = QuadratureOsc::new(100.0, 25.0);
+/// let (sin, cos): (f32, f32) = osc.next_pair();
+/// assert!((sin - 1.0).abs() < 1e-6);
+/// assert!(cos.abs() < 1e-6);
+/// ```
+pub struct QuadratureOsc<T: Float = f32> {
+    phase: T,
+    phase_increment: T,
+    sample_rate: T,
+}
+
+impl<T: Float> QuadratureOsc<T> {
+    /// Creates a new direct-phase quadrature oscillator.
+    pub fn new(sample_rate: T, frequency: T) -> Self {
+        Self {
+            phase: T::ZERO,
+            phase_increment: frequency / sample_rate,
+            sample_rate,
+        }
+    }
+
+    /// Generates the next sine and cosine.
+    pub fn next_pair(&mut self) -> (T, T) {
+        self.phase = advance_phase(self.phase, self.phase_increment);
+
+        (self.phase * T::TAU).sin_cos()
+    }
+
+    /// Fills two buffers with consecutive sines and cosines.
+    ///
+    /// Only as many pairs as the shorter buffer holds are written.
+    pub fn fill_pairs(&mut self, sines: &mut [T], cosines: &mut [T]) {
+        for (sin, cos) in sines.iter_mut().zip(cosines) {
+            (*sin, *cos) = self.next_pair();
+        }
+    }
+}
+
+impl<T: Float> Oscillator<T> for QuadratureOsc<T> {
+    fn set_frequency(&mut self, hz: T) {
+        self.phase_increment = hz / self.sample_rate;
+    }
+
+    fn set_phase(&mut self, phase: T) {
+        self.phase = wrap(phase);
+    }
+
+    fn phase(&self) -> T {
+        self.phase
+    }
+
+    fn reset(&mut self) {
+        self.phase = T::ZERO;
+    }
+
+    fn next_wrap(&self) -> Option<T> {
+        next_wrap(self.phase, self.phase_increment)
+    }
+
+    /// Generates the next sine, see [`QuadratureOsc::next_pair`] for the
+    /// cosine with it.
+    fn next_sample(&mut self) -> T {
+        self.next_pair().0
+    }
+}
+
+impl<T: Float> Iterator for QuadratureOsc<T> {
+    type Item = (T, T);
+
+    fn next(&mut self) -> Option<(T, T)> {
+        Some(self.next_pair())
+    }
+}
+
+/// A sine/cosine pair computed with a rotation recurrence.
+///
+/// Every sample rotates the pair by the phase increment with a complex
+/// multiplication, four multiplies and two adds instead of two
+/// trigonometric functions. A rotation keeps the pair exactly 90° apart,
+/// and rounding errors only let the amplitude drift, very slowly, so it is
+/// brought back to 1.0 every 64 samples. Frequency changes don't touch the
+/// amplitude or the phase, so sweeps stay click-free.
+///
+/// The phase is still tracked, for [`Oscillator::phase`] and hard sync
+/// masters, and setting it restarts the recurrence from there. Rounding
+/// slowly turns the pair away from it, about as much as it moves the phase
+/// itself away from the exact one, so use `f64` where either matters over
+/// minutes.
+///
+/// # Example
+///
+/// ```
+/// use oscy::{quadrature::RecursiveQuadratureOsc, Oscillator};
+///
+/// let mut osc = RecursiveQuadratureOsc::new(48000.0, 1000.0);
+/// let (sin, cos): (f32, f32) = osc.next_pair();
+/// assert!((sin * sin + cos * cos - 1.0).abs() < 1e-6);
+/// ```
+pub struct RecursiveQuadratureOsc<T: Float = f32> {
+    phase: T,
+    phase_increment: T,
+    sample_rate: T,
+    // the current pair
+    sin: T,
+    cos: T,
+    // sine and cosine of the angle turned every sample
+    rotation_sin: T,
+    rotation_cos: T,
+    // samples left until the next renormalization
+    countdown: usize,
+}
+
+impl<T: Float> RecursiveQuadratureOsc<T> {
+    /// Creates a new recursive quadrature oscillator.
+    pub fn new(sample_rate: T, frequency: T) -> Self {
+        let mut osc = Self {
+            phase: T::ZERO,
+            phase_increment: T::ZERO,
+            sample_rate,
+            sin: T::ZERO,
+            cos: T::ONE,
+            rotation_sin: T::ZERO,
+            rotation_cos: T::ONE,
+            countdown: RENORMALIZE_INTERVAL,
+        };
+        osc.set_frequency(frequency);
+        osc
+    }
+
+    /// Generates the next sine and cosine.
+    pub fn next_pair(&mut self) -> (T, T) {
+        self.phase = advance_phase(self.phase, self.phase_increment);
+
+        let sin = self.sin * self.rotation_cos + self.cos * self.rotation_sin;
+        let cos = self.cos * self.rotation_cos - self.sin * self.rotation_sin;
+        self.sin = sin;
+        self.cos = cos;
+
+        self.countdown -= 1;
+        if self.countdown == 0 {
+            self.countdown = RENORMALIZE_INTERVAL;
+            self.renormalize();
+        }
+
+        (self.sin, self.cos)
+    }
+
+    /// Fills two buffers with consecutive sines and cosines.
+    ///
+    /// Only as many pairs as the shorter buffer holds are written.
+    pub fn fill_pairs(&mut self, sines: &mut [T], cosines: &mut [T]) {
+        for (sin, cos) in sines.iter_mut().zip(cosines) {
+            (*sin, *cos) = self.next_pair();
+        }
+    }
+
+    /// Scales the pair back onto the unit circle.
+    fn renormalize(&mut self) {
+        // one Newton step towards 1 / sqrt(magnitude), which is exact
+        // enough while the pair only drifted by rounding errors
+        let magnitude = self.sin * self.sin + self.cos * self.cos;
+        let gain = T::HALF * (T::from_f64(3.0) - magnitude);
+        self.sin *= gain;
+        self.cos *= gain;
+    }
+}
+
+impl<T: Float> Oscillator<T> for RecursiveQuadratureOsc<T> {
+    /// Sets the frequency in hertz, continuing from the current pair.
+    fn set_frequency(&mut self, hz: T) {
+        self.phase_increment = hz / self.sample_rate;
+        (self.rotation_sin, self.rotation_cos) = (self.phase_increment * T::TAU).sin_cos();
+    }
+
+    /// Sets the phase, restarting the recurrence from it.
+    fn set_phase(&mut self, phase: T) {
+        self.phase = wrap(phase);
+        (self.sin, self.cos) = (self.phase * T::TAU).sin_cos();
+        self.countdown = RENORMALIZE_INTERVAL;
+    }
+
+    fn phase(&self) -> T {
+        self.phase
+    }
+
+    fn reset(&mut self) {
+        self.set_phase(T::ZERO);
+    }
+
+    fn next_wrap(&self) -> Option<T> {
+        next_wrap(self.phase, self.phase_increment)
+    }
+
+    /// Generates the next sine, see [`RecursiveQuadratureOsc::next_pair`]
+    /// for the cosine with it.
+    fn next_sample(&mut self) -> T {
+        self.next_pair().0
+    }
+}
+
+impl<T: Float> Iterator for RecursiveQuadratureOsc<T> {
+    type Item = (T, T);
+
+    fn next(&mut self) -> Option<(T, T)> {
+        Some(self.next_pair())
+    }
+}
@@ -0,0 +1,244 @@
+use super::{QuadratureOsc, RecursiveQuadratureOsc};
+use crate::{Oscillator, Waveform, naive::NaiveOsc};
+
+const EPSILON: f32 = 1e-5;
+
+fn approx_eq(a: f32, b: f32) -> bool {
+    (a - b).abs() < EPSILON
+}
+
+// direct phase tests
+
+#[test]
+fn test_pair_at_known_phases() {
+    // quarter of the sample rate: a quarter cycle per sample
+    let mut osc = QuadratureOsc::new(100.0, 25.0);
+    let expected = [(1.0, 0.0), (0.0, -1.0), (-1.0, 0.0), (0.0, 1.0)];
+
+    for (sin, cos) in expected {
+        let pair = osc.next_pair();
+        assert!(approx_eq(pair.0, sin) && approx_eq(pair.1, cos));
+    }
+}
+
+#[test]
+fn test_sine_matches_naive() {
+    let mut osc = QuadratureOsc::new(44100.0, 440.0);
+    let mut naive = NaiveOsc::new(44100.0, 440.0, Waveform::Sine);
+
+    for _ in 0..1000 {
+        assert!(approx_eq(osc.next_sample(), naive.next_sample()));
+    }
+}
+
+#[test]
+fn test_cosine_leads_by_quarter_cycle() {
+    let mut osc = QuadratureOsc::new(44100.0, 997.0);
+    let mut leading = QuadratureOsc::new(44100.0, 997.0);
+    leading.set_phase(0.25);
+
+    for _ in 0..1000 {
+        assert!(approx_eq(osc.next_pair().1, leading.next_pair().0));
+    }
+}
+
+#[test]
+fn test_negative_frequency_negates_sine() {
+    let mut osc: QuadratureOsc = QuadratureOsc::new(44100.0, -440.0);
+    let mut mirrored = QuadratureOsc::new(44100.0, 440.0);
+
+    for _ in 0..1000 {
+        let (sin, cos) = osc.next_pair();
+        let (mirrored_sin, mirrored_cos) = mirrored.next_pair();
+        // the two accumulators round differently
+        assert!((sin + mirrored_sin).abs() < 1e-3 && (cos - mirrored_cos).abs() < 1e-3);
+    }
+}
+
+// recurrence tests
+
+#[test]
+fn test_recurrence_matches_direct() {
+    let mut osc: RecursiveQuadratureOsc = RecursiveQuadratureOsc::new(44100.0, 1234.5);
+    let mut direct = QuadratureOsc::new(44100.0, 1234.5);
+
+    for _ in 0..1000 {
+        let (sin, cos) = osc.next_pair();
+        let (direct_sin, direct_cos) = direct.next_pair();
+        assert!((sin - direct_sin).abs() < 1e-4 && (cos - direct_cos).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn test_recurrence_matches_direct_in_f64_for_a_minute() {
+    let mut osc = RecursiveQuadratureOsc::<f64>::new(48000.0, 440.0);
+    let mut direct = QuadratureOsc::<f64>::new(48000.0, 440.0);
+
+    let mut worst: f64 = 0.0;
+    for _ in 0..48000 * 60 {
+        let (sin, cos) = osc.next_pair();
+        let (direct_sin, direct_cos) = direct.next_pair();
+        worst = worst
+            .max((sin - direct_sin).abs())
+            .max((cos - direct_cos).abs());
+    }
+    assert!(worst < 1e-8, "{worst}");
+}
+
+#[test]
+fn test_renormalization_holds_amplitude() {
+    let mut osc: RecursiveQuadratureOsc = RecursiveQuadratureOsc::new(44100.0, 3517.0);
+
+    for i in 0..5_000_000 {
+        let (sin, cos) = osc.next_pair();
+        if i % 1000 == 0 {
+            assert!((sin.hypot(cos) - 1.0).abs() < 1e-5);
+        }
+    }
+}
+
+#[test]
+fn test_f32_recurrence_stays_close_to_exact() {
+    let mut osc: RecursiveQuadratureOsc = RecursiveQuadratureOsc::new(44100.0, 3517.0);
+    let mut exact = QuadratureOsc::<f64>::new(44100.0, 3517.0);
+
+    // a second in, rounding has turned the pair by about as much as it
+    // moves a direct f32 phase accumulator
+    for _ in 0..44100 {
+        let (sin, cos) = osc.next_pair();
+        let (exact_sin, exact_cos) = exact.next_pair();
+        assert!((sin - exact_sin as f32).abs() < 5e-3);
+        assert!((cos - exact_cos as f32).abs() < 5e-3);
+    }
+}
+
+#[test]
+fn test_frequency_change_keeps_amplitude_and_phase() {
+    let mut osc = RecursiveQuadratureOsc::new(44100.0, 440.0);
+    let mut direct = QuadratureOsc::new(44100.0, 440.0);
+
+    for i in 0..10_000 {
+        // sweep every sample
+        let hz = 440.0 + (i % 500) as f32;
+        osc.set_frequency(hz);
+        direct.set_frequency(hz);
+
+        let (sin, cos) = osc.next_pair();
+        let (direct_sin, direct_cos) = direct.next_pair();
+        assert!((sin - direct_sin).abs() < 1e-3 && (cos - direct_cos).abs() < 1e-3);
+        assert!((sin.hypot(cos) - 1.0).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn test_set_phase_restarts_recurrence() {
+    let mut osc = RecursiveQuadratureOsc::new(44100.0, 440.0);
+    for _ in 0..100 {
+        osc.next_pair();
+    }
+    osc.set_phase(0.25);
+
+    let mut direct = QuadratureOsc::new(44100.0, 440.0);
+    direct.set_phase(0.25);
+    for _ in 0..100 {
+        let (sin, cos) = osc.next_pair();
+        let (direct_sin, direct_cos) = direct.next_pair();
+        assert!(approx_eq(sin, direct_sin) && approx_eq(cos, direct_cos));
+    }
+}
+
+#[test]
+fn test_fill_pairs() {
+    let mut osc = RecursiveQuadratureOsc::new(44100.0, 440.0);
+    let mut reference = RecursiveQuadratureOsc::new(44100.0, 440.0);
+    let mut sines = [0.0f32; 64];
+    let mut cosines = [0.0f32; 64];
+
+    osc.fill_pairs(&mut sines, &mut cosines);
+
+    for (sin, cos) in sines.into_iter().zip(cosines) {
+        assert_eq!((sin, cos), reference.next_pair());
+    }
+}
+
+#[test]
+fn test_tiny_negative_phase_stays_below_one() {
+    // a step from 1e-9 back by 2e-9 lands at -1e-9, which rounds to 1.0
+    let mut osc: QuadratureOsc = QuadratureOsc::new(1.0, -2e-9);
+    osc.set_phase(1e-9);
+    osc.next_pair();
+    assert!(osc.phase() < 1.0);
+
+    osc.set_phase(-1e-9);
+    assert!(osc.phase() < 1.0);
+
+    let mut osc: RecursiveQuadratureOsc = RecursiveQuadratureOsc::new(1.0, -2e-9);
+    osc.set_phase(1e-9);
+    osc.next_pair();
+    assert!(osc.phase() < 1.0);
+
+    osc.set_phase(-1e-9);
+    assert!(osc.phase() < 1.0);
+}
+
+// trait impl tests
+
+#[test]
+fn test_set_frequency_changes_increment() {
+    let mut osc = QuadratureOsc::new(1000.0, 100.0);
+    let mut recursive = RecursiveQuadratureOsc::new(1000.0, 100.0);
+    osc.set_frequency(250.0);
+    recursive.set_frequency(250.0);
+    osc.next_sample();
+    recursive.next_sample();
+
+    assert!(approx_eq(osc.phase(), 0.25));
+    assert!(approx_eq(recursive.phase(), 0.25));
+}
+
+#[test]
+fn test_reset_zeros_phase() {
+    let mut osc = QuadratureOsc::new(1000.0, 100.0);
+    let mut recursive = RecursiveQuadratureOsc::new(1000.0, 100.0);
+    for _ in 0..5 {
+        osc.next_sample();
+        recursive.next_sample();
+    }
+    osc.reset();
+    recursive.reset();
+
+    assert!(approx_eq(osc.phase(), 0.0));
+    assert!(approx_eq(recursive.phase(), 0.0));
+    assert_eq!(osc.next_pair(), recursive.next_pair());
+}
+
+#[test]
+fn test_next_wrap_reports_fraction() {
+    let mut osc: QuadratureOsc = QuadratureOsc::new(10.0, 3.0); // inc = 0.3
+    osc.set_phase(0.9);
+    assert!((osc.next_wrap().unwrap() - 1.0 / 3.0).abs() < 1e-5);
+
+    let mut recursive: RecursiveQuadratureOsc = RecursiveQuadratureOsc::new(10.0, -3.0);
+    recursive.set_phase(0.15);
+    assert!((recursive.next_wrap().unwrap() - 0.5).abs() < 1e-5);
+}
+
+#[test]
+fn test_fill_buffer() {
+    let mut osc = QuadratureOsc::new(44100.0, 440.0);
+    let mut reference = QuadratureOsc::new(44100.0, 440.0);
+    let mut buffer = [0.0f32; 64];
+
+    osc.fill(&mut buffer);
+
+    for sample in buffer {
+        assert!(approx_eq(sample, reference.next_pair().0));
+    }
+}
+
+#[test]
+fn test_iterator() {
+    let osc = RecursiveQuadratureOsc::new(44100.0, 440.0);
+    let pairs: Vec<(f32, f32)> = osc.take(64).collect();
+    assert_eq!(pairs.len(), 64);
+}
@@ -0,0 +1,154 @@
+#[cfg(test)]
+mod tests;
+
+use crate::{Float, Oscillator, advance_phase, next_wrap, wrap};
+
+/// How many samples the resonator runs between drift corrections.
+const CORRECTION_INTERVAL: usize = 64;
+
+/// A sine oscillator built on a magic-circle resonator.
+///
+/// Instead of evaluating a sine every sample, the resonator turns a pair of
+/// state variables a little further each step, with two multiply-adds. The
+/// magic circle (or coupled form) stays stable at any frequency below
+/// Nyquist, and its sine comes out exactly in phase with the
+/// [`NaiveOsc`](crate::naive::NaiveOsc) sine. That makes it a cheap source
+/// when hundreds of sines run at once.
+///
+/// Rounding errors slowly change the amplitude, so every 64 samples it is
+/// measured from the state and brought back to 1.0. Frequency changes
+/// adjust the state to the new step, keeping both amplitude and phase, so
+/// glides and vibrato don't click.
+///
+/// The frequency must stay below Nyquist. The phase is tracked for
+/// [`Oscillator::phase`] and hard sync masters, and setting it restarts
+/// the resonator from there.
+///
+/// # Example
+///
+/// ```
+/// use oscy::{resonator::ResonatorOsc, Oscillator};
+///
+/// // at a quarter of the sample rate, the first sample is the peak
+/// let mut osc = ResonatorOsc::new(100.0, 25.0);
+/// let sample: f32 = osc.next_sample();
+/// assert!((sample - 1.0).abs() < 1e-6);
+/// ```
+pub struct ResonatorOsc<T: Float = f32> {
+    phase: T,
+    phase_increment: T,
+    sample_rate: T,
+    // sine at the current phase, and cosine half a step behind it
+    sin: T,
+    cos: T,
+    // sine, cosine and secant of half the angle turned every sample
+    half_sin: T,
+    half_cos: T,
+    half_sec: T,
+    // the resonator's coefficient, twice the half-angle sine
+    coefficient: T,
+    // samples left until the next drift correction
+    countdown: usize,
+}
+
+impl<T: Float> ResonatorOsc<T> {
+    /// Creates a new resonator sine oscillator.
+    pub fn new(sample_rate: T, frequency: T) -> Self {
+        let mut osc = Self {
+            phase: T::ZERO,
+            phase_increment: T::ZERO,
+            sample_rate,
+            sin: T::ZERO,
+            cos: T::ONE,
+            half_sin: T::ZERO,
+            half_cos: T::ONE,
+            half_sec: T::ONE,
+            coefficient: T::ZERO,
+            countdown: CORRECTION_INTERVAL,
+        };
+        osc.set_frequency(frequency);
+        osc.reset();
+        osc
+    }
+
+    /// Returns the cosine at the current phase, measured from the state.
+    fn full_cos(&self) -> T {
+        // the state's cosine is half a step behind:
+        // cos(θ - ω/2) = cos θ · cos(ω/2) + sin θ · sin(ω/2)
+        (self.cos - self.sin * self.half_sin) * self.half_sec
+    }
+
+    /// Brings the amplitude back to 1.0.
+    fn correct_drift(&mut self) {
+        let cos = self.full_cos();
+        let magnitude = self.sin * self.sin + cos * cos;
+
+        // one Newton step towards 1 / sqrt(magnitude), which is exact
+        // enough while the amplitude only drifted by rounding errors
+        let gain = T::HALF * (T::from_f64(3.0) - magnitude);
+        self.sin *= gain;
+        self.cos *= gain;
+    }
+}
+
+impl<T: Float> Oscillator<T> for ResonatorOsc<T> {
+    /// Sets the frequency in hertz, continuing with the current amplitude
+    /// and phase.
+    fn set_frequency(&mut self, hz: T) {
+        let cos = self.full_cos();
+
+        self.phase_increment = hz / self.sample_rate;
+        (self.half_sin, self.half_cos) = (self.phase_increment * T::PI).sin_cos();
+        self.half_sec = T::ONE / self.half_cos;
+        self.coefficient = T::TWO * self.half_sin;
+
+        // move the cosine to half of the new step behind
+        self.cos = cos * self.half_cos + self.sin * self.half_sin;
+    }
+
+    /// Sets the phase, restarting the resonator from it.
+    fn set_phase(&mut self, phase: T) {
+        self.phase = wrap(phase);
+
+        let angle = self.phase * T::TAU;
+        self.sin = angle.sin();
+        self.cos = (angle - self.phase_increment * T::PI).cos();
+        self.countdown = CORRECTION_INTERVAL;
+    }
+
+    fn phase(&self) -> T {
+        self.phase
+    }
+
+    fn reset(&mut self) {
+        self.set_phase(T::ZERO);
+    }
+
+    fn next_wrap(&self) -> Option<T> {
+        next_wrap(self.phase, self.phase_increment)
+    }
+
+    fn next_sample(&mut self) -> T {
+        self.phase = advance_phase(self.phase, self.phase_increment);
+
+        // the magic circle: the sine steps with the already updated cosine
+        self.cos -= self.coefficient * self.sin;
+        self.sin += self.coefficient * self.cos;
+
+        self.countdown -= 1;
+        if self.countdown == 0 {
+            self.countdown = CORRECTION_INTERVAL;
+            self.correct_drift();
+        }
+
+        self.sin
+    }
+}
+
+impl<T: Float> Iterator for ResonatorOsc<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        Some(self.next_sample())
+    }
+}
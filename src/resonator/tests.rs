@@ -0,0 +1,175 @@
+use super::ResonatorOsc;
+use crate::{Oscillator, Waveform, naive::NaiveOsc};
+
+const EPSILON: f32 = 1e-5;
+
+fn approx_eq(a: f32, b: f32) -> bool {
+    (a - b).abs() < EPSILON
+}
+
+/// Returns the largest difference from the `NaiveOsc` sine over `len`
+/// samples.
+fn max_error(sample_rate: f32, hz: f32, len: usize) -> f32 {
+    let mut osc = ResonatorOsc::new(sample_rate, hz);
+    let mut naive = NaiveOsc::new(sample_rate, hz, Waveform::Sine);
+
+    (0..len)
+        .map(|_| (osc.next_sample() - naive.next_sample()).abs())
+        .fold(0.0, f32::max)
+}
+
+// accuracy tests
+
+#[test]
+fn test_first_samples_match_naive() {
+    for hz in [20.0, 440.0, 5000.0, 19000.0] {
+        assert!(max_error(44100.0, hz, 64) < 1e-5);
+    }
+}
+
+#[test]
+fn test_error_bounded_against_naive() {
+    // a second of f32, where both sides round their phase differently
+    for hz in [20.0, 440.0, 5000.0, 19000.0] {
+        let error = max_error(44100.0, hz, 44100);
+        assert!(error < 2e-3, "{hz} Hz: {error}");
+    }
+}
+
+#[test]
+fn test_f64_error_bounded_for_a_minute() {
+    let mut osc = ResonatorOsc::<f64>::new(48000.0, 440.0);
+    let mut naive = NaiveOsc::<f64>::new(48000.0, 440.0, Waveform::Sine);
+
+    for _ in 0..48000 * 60 {
+        assert!((osc.next_sample() - naive.next_sample()).abs() < 1e-8);
+    }
+}
+
+#[test]
+fn test_drift_correction_holds_amplitude() {
+    let mut osc: ResonatorOsc = ResonatorOsc::new(44100.0, 441.0); // 100 samples per cycle
+
+    for _ in 0..50_000 {
+        let peak = (0..100)
+            .map(|_| osc.next_sample().abs())
+            .fold(0.0, f32::max);
+        assert!((peak - 1.0).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn test_frequency_change_keeps_amplitude_and_phase() {
+    let mut osc = ResonatorOsc::new(44100.0, 440.0);
+    let mut naive = NaiveOsc::new(44100.0, 440.0, Waveform::Sine);
+
+    for i in 0..20_000 {
+        // vibrato, changing the frequency every sample
+        let hz = 440.0 + 30.0 * ((i % 400) as f32 / 400.0 * std::f32::consts::TAU).sin();
+        osc.set_frequency(hz);
+        naive.set_frequency(hz);
+
+        assert!((osc.next_sample() - naive.next_sample()).abs() < 1e-3);
+    }
+}
+
+#[test]
+fn test_jump_in_frequency_keeps_amplitude() {
+    let mut osc: ResonatorOsc = ResonatorOsc::new(44100.0, 100.0);
+    for _ in 0..1000 {
+        osc.next_sample();
+    }
+    osc.set_frequency(4410.0); // 10 samples per cycle
+
+    for _ in 0..100 {
+        let peak = (0..10).map(|_| osc.next_sample().abs()).fold(0.0, f32::max);
+        // sampled peaks of a 10-sample cycle fall at most 18° from the top
+        assert!(peak <= 1.0 + 1e-5 && peak > 0.95);
+    }
+}
+
+#[test]
+fn test_negative_frequency_matches_naive() {
+    let mut osc: ResonatorOsc = ResonatorOsc::new(44100.0, -1000.0);
+    let mut naive = NaiveOsc::new(44100.0, -1000.0, Waveform::Sine);
+
+    for _ in 0..1000 {
+        assert!((osc.next_sample() - naive.next_sample()).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn test_tiny_negative_phase_stays_below_one() {
+    // a step from 1e-9 back by 2e-9 lands at -1e-9, which rounds to 1.0
+    let mut osc: ResonatorOsc = ResonatorOsc::new(1.0, -2e-9);
+    osc.set_phase(1e-9);
+    osc.next_sample();
+    assert!(osc.phase() < 1.0);
+
+    osc.set_phase(-1e-9);
+    assert!(osc.phase() < 1.0);
+}
+
+// trait impl tests
+
+#[test]
+fn test_set_phase_restarts_resonator() {
+    let mut osc = ResonatorOsc::new(44100.0, 440.0);
+    let mut naive = NaiveOsc::new(44100.0, 440.0, Waveform::Sine);
+    for _ in 0..123 {
+        osc.next_sample();
+    }
+    osc.set_phase(0.3);
+    naive.set_phase(0.3);
+
+    for _ in 0..100 {
+        assert!(approx_eq(osc.next_sample(), naive.next_sample()));
+    }
+}
+
+#[test]
+fn test_reset_zeros_phase() {
+    let mut osc = ResonatorOsc::new(1000.0, 100.0);
+    let first: Vec<f32> = (0..10).map(|_| osc.next_sample()).collect();
+    osc.reset();
+
+    assert!(approx_eq(osc.phase(), 0.0));
+    for sample in first {
+        assert!(approx_eq(osc.next_sample(), sample));
+    }
+}
+
+#[test]
+fn test_set_frequency_changes_increment() {
+    let mut osc = ResonatorOsc::new(1000.0, 100.0);
+    osc.set_frequency(250.0);
+    assert!(approx_eq(osc.next_sample(), 1.0));
+    assert!(approx_eq(osc.phase(), 0.25));
+}
+
+#[test]
+fn test_next_wrap_reports_fraction() {
+    let mut osc: ResonatorOsc = ResonatorOsc::new(10.0, 3.0); // inc = 0.3
+    osc.set_phase(0.9);
+    assert!((osc.next_wrap().unwrap() - 1.0 / 3.0).abs() < 1e-5);
+}
+
+#[test]
+fn test_fill_buffer() {
+    let mut osc = ResonatorOsc::new(44100.0, 440.0);
+    let mut reference = ResonatorOsc::new(44100.0, 440.0);
+    let mut buffer = [0.0f32; 128];
+
+    osc.fill(&mut buffer);
+
+    for sample in buffer {
+        assert_eq!(sample, reference.next_sample());
+    }
+}
+
+#[test]
+fn test_iterator() {
+    let osc = ResonatorOsc::new(44100.0, 440.0);
+    let samples: Vec<f32> = osc.take(64).collect();
+    assert_eq!(samples.len(), 64);
+}
@@ -0,0 +1,50 @@
+//! Small pseudo-random generator, so randomness needs no dependency.
+
+use crate::Float;
+
+/// A wyrand pseudo-random generator.
+///
+/// Fast and good enough for audio, not for anything needing secure or
+/// high-quality randomness.
+pub(crate) struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// Creates a generator from a seed. Equal seeds give equal sequences.
+    pub(crate) fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Returns the next 64 random bits.
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0xa076_1d64_78bd_642f);
+        let t = u128::from(self.state) * u128::from(self.state ^ 0xe703_7ed1_a0b4_28db);
+        (t >> 64) as u64 ^ t as u64
+    }
+
+    /// Returns a uniform value in [0.0, 1.0), from the top 24 bits for `f32`
+    /// and the top 53 for `f64`.
+    pub(crate) fn next_unit<T: Float>(&mut self) -> T {
+        T::from_unit_u64(self.next_u64())
+    }
+}
+
+/// Returns a different seed for every new generator.
+#[cfg(feature = "std")]
+pub(crate) fn next_seed() -> u64 {
+    use std::hash::{BuildHasher, Hasher, RandomState};
+
+    // every RandomState is keyed differently, and randomly per thread
+    RandomState::new().build_hasher().finish()
+}
+
+/// Returns a different seed for every new generator.
+#[cfg(not(feature = "std"))]
+pub(crate) fn next_seed() -> u64 {
+    use core::sync::atomic::{AtomicU32, Ordering};
+
+    // without an entropy source, count the generators instead
+    static COUNT: AtomicU32 = AtomicU32::new(0);
+    u64::from(COUNT.fetch_add(1, Ordering::Relaxed)).wrapping_mul(0x9e37_79b9_7f4a_7c15)
+}
@@ -0,0 +1,139 @@
+#[cfg(test)]
+mod tests;
+
+use crate::Float;
+
+/// Odd polynomial approximating sin(π/2·x) on [-1.0, 1.0], highest order
+/// first. Fitted for a maximum error of about 6e-7.
+///
+/// `OscBank` evaluates it across SIMD lanes.
+pub(crate) const POLYNOMIAL: [f32; 4] = [-0.004_333_095, 0.079_434_34, -0.645_892_85, 1.570_791];
+
+/// Number of intervals the lookup table splits a cycle into.
+const TABLE_LEN: usize = 1024;
+
+/// One cycle of sine, with the first value repeated at the end so that
+/// interpolation never wraps.
+static TABLE: [f32; TABLE_LEN + 1] = table();
+
+/// How the sine of [`Waveform::Sine`](crate::Waveform::Sine) is evaluated.
+///
+/// The exact sine calls `sin` every sample, which dominates the cost of
+/// oscillators running many sines. The approximations skip the call and are
+/// accurate to a few millionths, far below what 16-bit audio
+/// resolves. The errors below are the largest differences from the exact
+/// sine, and the THD is measured with the noise, as everything but the
+/// fundamental, relative to it.
+///
+/// # Example
+///
+/// ```
+/// use oscy::{naive::NaiveOsc, sine::SineMode, Oscillator, Waveform};
+///
+/// let mut osc = NaiveOsc::new(100.0, 25.0, Waveform::Sine);
+/// osc.set_sine_mode(SineMode::Table);
+/// let sample: f32 = osc.next_sample();
+/// assert!((sample - 1.0).abs() < 1e-5);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SineMode {
+    /// Calls `sin`, exact to the rounding of the sample type, with a THD
+    /// below -135 dB in `f32`.
+    #[default]
+    Exact,
+    /// An odd 7th-order polynomial on a quarter cycle, folded to the others.
+    /// At most 1e-6 off, with a THD below -120 dB.
+    Polynomial,
+    /// Linear interpolation in a table of 1024 points per cycle, shared by
+    /// every oscillator. At most 5e-6 off, with a THD below -110 dB.
+    Table,
+}
+
+impl SineMode {
+    /// Returns the sine at `phase`, in cycles.
+    ///
+    /// The phase must lie in [0.0, 1.0), as the oscillators keep it.
+    pub fn sine<T: Float>(self, phase: T) -> T {
+        match self {
+            SineMode::Exact => (phase * T::TAU).sin(),
+            SineMode::Polynomial => polynomial_sine(phase),
+            SineMode::Table => table_sine(phase),
+        }
+    }
+}
+
+fn polynomial_sine<T: Float>(phase: T) -> T {
+    // as a signed phase in [-0.5, 0.5), folded onto [-0.25, 0.25], where
+    // the polynomial takes over
+    let quarter = T::from_f64(0.25);
+    let phase = if phase >= T::HALF {
+        phase - T::ONE
+    } else {
+        phase
+    };
+    let folded = if phase > quarter {
+        T::HALF - phase
+    } else if phase < -quarter {
+        -T::HALF - phase
+    } else {
+        phase
+    };
+
+    // a quarter cycle is 1.0
+    let x = folded * T::from_f64(4.0);
+    let x2 = x * x;
+    let sum = POLYNOMIAL[1..]
+        .iter()
+        .fold(T::from_f32(POLYNOMIAL[0]), |sum, &c| {
+            sum * x2 + T::from_f32(c)
+        });
+    sum * x
+}
+
+fn table_sine<T: Float>(phase: T) -> T {
+    let position = phase * T::from_usize(TABLE_LEN);
+    // saturating conversion and clamping keep the index in the table
+    let index = position.to_usize().min(TABLE_LEN - 1);
+    let fraction = position - T::from_usize(index);
+
+    let a = T::from_f32(TABLE[index]);
+    let b = T::from_f32(TABLE[index + 1]);
+    a + (b - a) * fraction
+}
+
+/// Builds the lookup table at compile time.
+const fn table() -> [f32; TABLE_LEN + 1] {
+    let mut table = [0.0; TABLE_LEN + 1];
+    let mut i = 0;
+    while i <= TABLE_LEN {
+        table[i] = taylor_sine(i as f64 / TABLE_LEN as f64) as f32;
+        i += 1;
+    }
+    table
+}
+
+/// Computes the sine at `phase` in [0.0, 1.0] from its Taylor series,
+/// since `sin` isn't available in constant evaluation.
+const fn taylor_sine(phase: f64) -> f64 {
+    // fold onto [-0.25, 0.25], where 13 terms are exact to f64 rounding
+    let phase = if phase >= 0.5 { phase - 1.0 } else { phase };
+    let folded = if phase > 0.25 {
+        0.5 - phase
+    } else if phase < -0.25 {
+        -0.5 - phase
+    } else {
+        phase
+    };
+
+    let x = folded * core::f64::consts::TAU;
+    let x2 = x * x;
+    let mut term = x;
+    let mut sum = x;
+    let mut n = 1;
+    while n < 13 {
+        term *= -x2 / ((2 * n) * (2 * n + 1)) as f64;
+        sum += term;
+        n += 1;
+    }
+    sum
+}
@@ -0,0 +1,101 @@
+use std::f64::consts::TAU;
+
+use super::{SineMode, TABLE, TABLE_LEN};
+use crate::test_utils::{ANALYSIS_LEN, analysis_frequency, distortion_db};
+use crate::{Oscillator, Waveform, naive::NaiveOsc};
+
+const MODES: [SineMode; 3] = [SineMode::Exact, SineMode::Polynomial, SineMode::Table];
+
+/// Returns the largest difference from the exact sine over a fine sweep of
+/// the cycle, in `f32` and `f64`.
+fn max_errors(mode: SineMode) -> (f32, f64) {
+    let steps = 1_000_000;
+    let mut error_f32: f32 = 0.0;
+    let mut error_f64: f64 = 0.0;
+
+    for i in 0..steps {
+        let phase = i as f64 / steps as f64;
+        let exact = (phase * TAU).sin();
+        error_f64 = error_f64.max((mode.sine(phase) - exact).abs());
+        error_f32 = error_f32.max((mode.sine(phase as f32) - exact as f32).abs());
+    }
+    (error_f32, error_f64)
+}
+
+/// Returns the distortion of a `NaiveOsc` sine in `mode`, in decibels.
+fn distortion(mode: SineMode, cycles: usize) -> f32 {
+    let mut osc = NaiveOsc::new(44100.0, analysis_frequency(44100.0, cycles), Waveform::Sine);
+    osc.set_sine_mode(mode);
+
+    let samples: Vec<f32> = (0..ANALYSIS_LEN).map(|_| osc.next_sample()).collect();
+    distortion_db(&samples, cycles)
+}
+
+// accuracy tests
+
+#[test]
+fn test_exact_matches_sin() {
+    let (error_f32, error_f64) = max_errors(SineMode::Exact);
+    assert!(error_f32 < 1e-6, "{error_f32}");
+    // libm rounds differently from std in the last place
+    assert!(error_f64 < 1e-15, "{error_f64}");
+}
+
+#[test]
+fn test_polynomial_error_bounded() {
+    let (error_f32, error_f64) = max_errors(SineMode::Polynomial);
+    assert!(error_f32 < 1e-6, "{error_f32}");
+    assert!(error_f64 < 1e-6, "{error_f64}");
+}
+
+#[test]
+fn test_table_error_bounded() {
+    let (error_f32, error_f64) = max_errors(SineMode::Table);
+    assert!(error_f32 < 5e-6, "{error_f32}");
+    assert!(error_f64 < 5e-6, "{error_f64}");
+}
+
+#[test]
+fn test_table_holds_exact_sine() {
+    for (i, &value) in TABLE.iter().enumerate() {
+        let exact = (i as f64 / TABLE_LEN as f64 * TAU).sin();
+        assert!((value as f64 - exact).abs() < 1e-7, "{i}: {value}");
+    }
+}
+
+#[test]
+fn test_modes_hit_quarter_points() {
+    for mode in MODES {
+        assert!(mode.sine(0.0f32).abs() < 1e-6);
+        assert!((mode.sine(0.25f32) - 1.0).abs() < 1e-5);
+        assert!(mode.sine(0.5f32).abs() < 1e-5);
+        assert!((mode.sine(0.75f32) + 1.0).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn test_table_end_of_cycle_stays_in_table() {
+    let sample = SineMode::Table.sine(1.0 - f32::EPSILON);
+    assert!(sample.abs() < 1e-5);
+}
+
+// distortion tests
+
+#[test]
+fn test_distortion_bounded() {
+    for (mode, limit) in [
+        (SineMode::Exact, -135.0),
+        (SineMode::Polynomial, -120.0),
+        (SineMode::Table, -110.0),
+    ] {
+        for cycles in [7, 101, 1021] {
+            let thd = distortion(mode, cycles);
+            assert!(thd < limit, "{mode:?} at {cycles} cycles: {thd} dB");
+        }
+    }
+}
+
+#[test]
+fn test_default_is_exact() {
+    assert_eq!(SineMode::default(), SineMode::Exact);
+}
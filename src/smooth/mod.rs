@@ -0,0 +1,181 @@
+#[cfg(test)]
+mod tests;
+
+use crate::Float;
+
+/// How far an exponential glide has yet to go when its time is up, at
+/// which point it snaps to the target (-60 dB).
+const EXPONENTIAL_RESIDUE: f64 = 0.001;
+
+/// The shape of a glide from one value to the next.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GlideCurve {
+    /// Moves at a constant rate, arriving exactly at the end of the glide
+    /// time.
+    #[default]
+    Linear,
+    /// Moves quickly at first and slows down towards the target, like a
+    /// capacitor charging. It comes within 0.1% of the way after the glide
+    /// time, then snaps to the target.
+    Exponential,
+}
+
+/// How long a glide takes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GlideTime<T: Float = f32> {
+    /// Milliseconds, converted with the sample rate.
+    Millis(T),
+    /// Samples, rounded to the nearest whole sample.
+    Samples(T),
+}
+
+impl<T: Float> GlideTime<T> {
+    /// Returns the glide time in whole samples, at least zero.
+    pub fn to_samples(self, sample_rate: T) -> usize {
+        let samples = match self {
+            GlideTime::Millis(ms) => ms * sample_rate / T::from_f64(1000.0),
+            GlideTime::Samples(samples) => samples,
+        };
+        samples.max(T::ZERO).round().to_usize()
+    }
+}
+
+/// Glides a parameter towards its target, one sample at a time.
+///
+/// Without a glide time, which is the default, new targets are reached
+/// straight away. With one, every new target starts a glide from the
+/// current value, taking the full glide time whatever the distance.
+///
+/// # Example
+///
+/// ```
+/// use oscy::smooth::Smoother;
+///
+/// let mut gain = Smoother::new(0.0);
+/// gain.set_time(4);
+/// gain.set_target(1.0);
+///
+/// let ramp: Vec<f32> = (0..5).map(|_| gain.next_value()).collect();
+/// assert_eq!(ramp, [0.25, 0.5, 0.75, 1.0, 1.0]);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Smoother<T: Float = f32> {
+    value: T,
+    target: T,
+    curve: GlideCurve,
+    // glide time in samples
+    time: usize,
+    // samples left in the current glide
+    remaining: usize,
+    // change per sample when linear
+    step: T,
+    // share of the distance left after every sample when exponential
+    coefficient: T,
+}
+
+impl<T: Float> Smoother<T> {
+    /// Creates a smoother resting at `value`, without a glide time.
+    pub fn new(value: T) -> Self {
+        Self {
+            value,
+            target: value,
+            curve: GlideCurve::Linear,
+            time: 0,
+            remaining: 0,
+            step: T::ZERO,
+            coefficient: T::ZERO,
+        }
+    }
+
+    /// Sets the glide curve.
+    ///
+    /// A glide in progress continues from its current value along the new
+    /// curve, over the full glide time.
+    pub fn set_curve(&mut self, curve: GlideCurve) {
+        self.curve = curve;
+        self.start();
+    }
+
+    /// Sets the glide time in samples, where zero makes changes instant.
+    ///
+    /// A glide in progress continues from its current value over the new
+    /// time, so the glide time can change independently of the target.
+    pub fn set_time(&mut self, samples: usize) {
+        self.time = samples;
+        self.coefficient = match samples {
+            0 => T::ZERO,
+            samples => T::from_f64(Float::exp(Float::ln(EXPONENTIAL_RESIDUE) / samples as f64)),
+        };
+        self.start();
+    }
+
+    /// Returns the glide time in samples.
+    pub fn time(&self) -> usize {
+        self.time
+    }
+
+    /// Starts a glide from the current value to `target`.
+    pub fn set_target(&mut self, target: T) {
+        self.target = target;
+        self.start();
+    }
+
+    /// Returns the value being glided to.
+    pub fn target(&self) -> T {
+        self.target
+    }
+
+    /// Returns the current value.
+    pub fn value(&self) -> T {
+        self.value
+    }
+
+    /// Jumps straight to `value`, cancelling any glide.
+    pub fn jump(&mut self, value: T) {
+        self.value = value;
+        self.target = value;
+        self.remaining = 0;
+    }
+
+    /// Finishes the glide in progress at once.
+    pub fn settle(&mut self) {
+        self.jump(self.target);
+    }
+
+    /// Returns whether a glide is in progress.
+    pub fn is_gliding(&self) -> bool {
+        self.remaining > 0
+    }
+
+    /// Moves one sample further and returns the new value.
+    pub fn next_value(&mut self) -> T {
+        if self.remaining == 0 {
+            return self.value;
+        }
+
+        self.remaining -= 1;
+        self.value = if self.remaining == 0 {
+            // land exactly, whatever rounding built up along the way
+            self.target
+        } else {
+            match self.curve {
+                GlideCurve::Linear => self.value + self.step,
+                GlideCurve::Exponential => {
+                    self.target + (self.value - self.target) * self.coefficient
+                }
+            }
+        };
+        self.value
+    }
+
+    /// Restarts the glide from the current value.
+    fn start(&mut self) {
+        if self.time == 0 || self.value == self.target {
+            self.settle();
+            return;
+        }
+
+        self.remaining = self.time;
+        self.step = (self.target - self.value) / T::from_usize(self.time);
+    }
+}
@@ -0,0 +1,135 @@
+use super::{GlideCurve, GlideTime, Smoother};
+
+const EPSILON: f32 = 1e-6;
+
+fn approx_eq(a: f32, b: f32) -> bool {
+    (a - b).abs() < EPSILON
+}
+
+// glide time tests
+
+#[test]
+fn test_millis_to_samples() {
+    assert_eq!(GlideTime::Millis(10.0f32).to_samples(48000.0), 480);
+    assert_eq!(GlideTime::Millis(0.01f32).to_samples(44100.0), 0);
+}
+
+#[test]
+fn test_samples_round_and_clamp() {
+    assert_eq!(GlideTime::Samples(2.6f32).to_samples(48000.0), 3);
+    assert_eq!(GlideTime::Samples(-5.0f32).to_samples(48000.0), 0);
+}
+
+// linear tests
+
+#[test]
+fn test_without_time_jumps() {
+    let mut smoother: Smoother = Smoother::new(1.0);
+    smoother.set_target(3.0);
+
+    assert!(!smoother.is_gliding());
+    assert!(approx_eq(smoother.next_value(), 3.0));
+}
+
+#[test]
+fn test_linear_arrives_on_time() {
+    let mut smoother: Smoother = Smoother::new(100.0);
+    smoother.set_time(4);
+    smoother.set_target(200.0);
+
+    assert!(approx_eq(smoother.next_value(), 125.0));
+    assert!(approx_eq(smoother.next_value(), 150.0));
+    assert!(approx_eq(smoother.next_value(), 175.0));
+    assert!(smoother.is_gliding());
+    assert_eq!(smoother.next_value(), 200.0);
+    assert!(!smoother.is_gliding());
+    assert_eq!(smoother.next_value(), 200.0);
+}
+
+#[test]
+fn test_new_target_starts_from_current_value() {
+    let mut smoother: Smoother = Smoother::new(0.0);
+    smoother.set_time(4);
+    smoother.set_target(4.0);
+    smoother.next_value(); // 1.0
+
+    // the full time again, for the remaining distance
+    smoother.set_target(-2.0);
+    assert!(approx_eq(smoother.next_value(), 0.25));
+    for _ in 0..3 {
+        smoother.next_value();
+    }
+    assert_eq!(smoother.value(), -2.0);
+}
+
+#[test]
+fn test_set_time_keeps_target() {
+    let mut smoother: Smoother = Smoother::new(0.0);
+    smoother.set_time(100);
+    smoother.set_target(1.0);
+    smoother.next_value(); // 0.01
+
+    smoother.set_time(3);
+    assert_eq!(smoother.target(), 1.0);
+    assert!(approx_eq(smoother.next_value(), 0.34));
+    smoother.next_value();
+    assert_eq!(smoother.next_value(), 1.0);
+}
+
+#[test]
+fn test_set_time_zero_settles() {
+    let mut smoother: Smoother = Smoother::new(0.0);
+    smoother.set_time(100);
+    smoother.set_target(1.0);
+    smoother.next_value();
+
+    smoother.set_time(0);
+    assert_eq!(smoother.value(), 1.0);
+}
+
+#[test]
+fn test_jump_cancels_glide() {
+    let mut smoother: Smoother = Smoother::new(0.0);
+    smoother.set_time(100);
+    smoother.set_target(1.0);
+
+    smoother.jump(0.5);
+    assert!(!smoother.is_gliding());
+    assert_eq!(smoother.next_value(), 0.5);
+    assert_eq!(smoother.target(), 0.5);
+}
+
+// exponential tests
+
+#[test]
+fn test_exponential_slows_down() {
+    let mut smoother: Smoother = Smoother::new(0.0);
+    smoother.set_curve(GlideCurve::Exponential);
+    smoother.set_time(100);
+    smoother.set_target(1.0);
+
+    let values: Vec<f32> = (0..100).map(|_| smoother.next_value()).collect();
+    for pair in values.windows(3).take(97) {
+        assert!(pair[1] - pair[0] > pair[2] - pair[1]);
+    }
+
+    // within 0.1% just before the end, then exactly there
+    assert!((values[98] - 0.999).abs() < 1e-4);
+    assert_eq!(values[99], 1.0);
+}
+
+#[test]
+fn test_exponential_goes_down_too() {
+    let mut smoother: Smoother = Smoother::new(880.0);
+    smoother.set_curve(GlideCurve::Exponential);
+    smoother.set_time(10);
+    smoother.set_target(440.0);
+
+    let mut previous = 880.0;
+    for _ in 0..10 {
+        let value = smoother.next_value();
+        assert!(value < previous && value >= 440.0);
+        previous = value;
+    }
+    assert_eq!(previous, 440.0);
+}
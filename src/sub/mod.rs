@@ -0,0 +1,254 @@
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    Float, Oscillator, Waveform,
+    poly_blep::{poly_blamp, poly_blep},
+};
+
+/// How far below the main oscillator a sub-oscillator plays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SubOctave {
+    /// One octave down, dividing the main frequency by 2.
+    #[default]
+    One,
+    /// Two octaves down, dividing the main frequency by 4.
+    Two,
+}
+
+impl SubOctave {
+    /// Returns how many main cycles make one sub cycle.
+    pub fn divisor(self) -> usize {
+        match self {
+            SubOctave::One => 2,
+            SubOctave::Two => 4,
+        }
+    }
+}
+
+/// The waveform of a sub-oscillator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SubWaveform {
+    /// A square toggling on main wraps, like the flip-flop dividers of
+    /// analog sub-oscillators.
+    #[default]
+    Square,
+    /// A pure sine.
+    Sine,
+    /// A sawtooth restarting with every sub cycle.
+    Saw,
+    /// A triangle with its corners on main wraps.
+    Triangle,
+}
+
+/// An oscillator with a phase-locked sub-oscillator one or two octaves
+/// below it.
+///
+/// The sub-oscillator doesn't run on its own: it counts the main
+/// oscillator's wraps, like a flip-flop divider, and derives its phase from
+/// the count and the main phase. Its edges land exactly on the main wraps
+/// forever, whatever the main frequency does, including glides, FM and
+/// negative frequencies. The edges are smoothed with the same polyBLEP and
+/// polyBLAMP corrections as [`PolyBlepOsc`](crate::poly_blep::PolyBlepOsc).
+///
+/// The divider counts the wraps the main reports through
+/// [`Oscillator::next_wrap`], so the main must implement it, as
+/// [`NaiveOsc`](crate::naive::NaiveOsc) and
+/// [`PolyBlepOsc`](crate::poly_blep::PolyBlepOsc) do. With the default,
+/// which never reports a wrap, the divider never counts and the sub phase
+/// jumps back on every main wrap.
+///
+/// The output mixes the main oscillator with the sub at its level, and
+/// [`SubOsc::next_outputs`] returns both on their own.
+///
+/// # Example
+///
+/// ```
+/// use oscy::{
+///     poly_blep::PolyBlepOsc,
+///     sub::{SubOctave, SubOsc, SubWaveform},
+///     Oscillator, Waveform,
+/// };
+///
+/// let main = PolyBlepOsc::new(44100.0, 110.0, Waveform::Saw);
+/// let mut bass = SubOsc::new(main, SubWaveform::Square, SubOctave::One);
+/// bass.set_level(0.7);
+///
+/// let sample: f32 = bass.next_sample();
+/// ```
+pub struct SubOsc<O, T: Float = f32> {
+    main: O,
+    waveform: SubWaveform,
+    octave: SubOctave,
+    level: T,
+    // main cycles completed within the current sub cycle
+    count: usize,
+    // main phase after the latest sample, to measure its steps
+    previous: T,
+    phase: T,
+    phase_increment: T,
+}
+
+impl<T: Float, O: Oscillator<T>> SubOsc<O, T> {
+    /// Wraps a main oscillator with a sub-oscillator at full level.
+    pub fn new(main: O, waveform: SubWaveform, octave: SubOctave) -> Self {
+        let previous = main.phase();
+        let divisor = T::from_usize(octave.divisor());
+
+        Self {
+            main,
+            waveform,
+            octave,
+            level: T::ONE,
+            count: 0,
+            previous,
+            phase: previous / divisor,
+            phase_increment: T::ZERO,
+        }
+    }
+
+    /// Returns a mutable reference to the main oscillator.
+    pub fn main_mut(&mut self) -> &mut O {
+        &mut self.main
+    }
+
+    /// Consumes the pair, returning the main oscillator.
+    pub fn into_inner(self) -> O {
+        self.main
+    }
+
+    /// Sets the sub-oscillator waveform.
+    pub fn set_sub_waveform(&mut self, waveform: SubWaveform) {
+        self.waveform = waveform;
+    }
+
+    /// Sets how many octaves below the main oscillator the sub plays.
+    ///
+    /// The sub keeps its place within the main cycle, so only the
+    /// count of main cycles carries over.
+    pub fn set_octave(&mut self, octave: SubOctave) {
+        self.octave = octave;
+        self.count %= octave.divisor();
+    }
+
+    /// Sets the sub-oscillator level in the mix, 1.0 by default.
+    pub fn set_level(&mut self, level: T) {
+        self.level = level;
+    }
+
+    /// Returns the sub-oscillator phase, in the range [0.0, 1.0).
+    pub fn sub_phase(&self) -> T {
+        self.phase
+    }
+
+    /// Generates the next samples of the main oscillator and the sub,
+    /// without mixing them.
+    pub fn next_outputs(&mut self) -> (T, T) {
+        // a wrap during the coming step advances the divider
+        let wraps = self.main.next_wrap().is_some();
+        let main = self.main.next_sample();
+        self.follow(wraps);
+
+        (main, self.render())
+    }
+
+    /// Moves the sub phase along with the main oscillator's latest step.
+    fn follow(&mut self, wraps: bool) {
+        let divisor = self.octave.divisor();
+        let phase = self.main.phase();
+        let mut step = phase - self.previous;
+        self.previous = phase;
+
+        if wraps {
+            // a forward wrap lands the phase below where it was, a
+            // backward one above
+            if step < T::ZERO {
+                step += T::ONE;
+                self.count = (self.count + 1) % divisor;
+            } else {
+                step -= T::ONE;
+                self.count = (self.count + divisor - 1) % divisor;
+            }
+        }
+
+        let divisor = T::from_usize(divisor);
+        self.phase = (T::from_usize(self.count) + phase) / divisor;
+        self.phase_increment = step / divisor;
+    }
+
+    /// Computes the band-limited sub sample at the current sub phase.
+    fn render(&self) -> T {
+        let phase = self.phase;
+        let dt = self.phase_increment.abs();
+        let opposite = (phase + T::HALF).fract();
+
+        match self.waveform {
+            SubWaveform::Sine => (phase * T::TAU).sin(),
+            SubWaveform::Saw => T::TWO * phase - T::ONE - poly_blep(phase, dt),
+            SubWaveform::Square => {
+                let naive = if phase < T::HALF { T::ONE } else { -T::ONE };
+                naive + poly_blep(phase, dt) - poly_blep(opposite, dt)
+            }
+            SubWaveform::Triangle => {
+                let naive = T::ONE - T::from_f64(4.0) * (phase - T::HALF).abs();
+                // slope changes by +8 at phase 0 and by -8 at phase 0.5
+                naive + T::from_f64(8.0) * dt * (poly_blamp(phase, dt) - poly_blamp(opposite, dt))
+            }
+        }
+    }
+}
+
+impl<T: Float, O: Oscillator<T>> Oscillator<T> for SubOsc<O, T> {
+    fn set_frequency(&mut self, hz: T) {
+        self.main.set_frequency(hz);
+    }
+
+    /// Sets the main phase. The sub keeps its count of main cycles.
+    fn set_phase(&mut self, phase: T) {
+        self.main.set_phase(phase);
+        self.previous = self.main.phase();
+        self.phase =
+            (T::from_usize(self.count) + self.previous) / T::from_usize(self.octave.divisor());
+    }
+
+    /// Returns the main phase.
+    fn phase(&self) -> T {
+        self.main.phase()
+    }
+
+    fn reset(&mut self) {
+        self.main.reset();
+        self.count = 0;
+        self.previous = self.main.phase();
+        self.phase = self.previous / T::from_usize(self.octave.divisor());
+        self.phase_increment = T::ZERO;
+    }
+
+    fn next_wrap(&self) -> Option<T> {
+        self.main.next_wrap()
+    }
+
+    fn sync(&mut self, fraction: T) {
+        self.main.sync(fraction);
+    }
+
+    /// Sets the main waveform. See [`SubOsc::set_sub_waveform`] for the
+    /// sub.
+    fn set_waveform(&mut self, waveform: Waveform) {
+        self.main.set_waveform(waveform);
+    }
+
+    /// Generates the main sample with the sub mixed in at its level.
+    fn next_sample(&mut self) -> T {
+        let (main, sub) = self.next_outputs();
+        main + self.level * sub
+    }
+}
+
+impl<T: Float, O: Oscillator<T>> Iterator for SubOsc<O, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        Some(self.next_sample())
+    }
+}
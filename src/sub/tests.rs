@@ -0,0 +1,225 @@
+use super::{SubOctave, SubOsc, SubWaveform};
+use crate::{
+    Oscillator, Waveform,
+    naive::NaiveOsc,
+    poly_blep::PolyBlepOsc,
+    smooth::GlideTime,
+    test_utils::{ANALYSIS_LEN, aliasing_ratio, analysis_frequency},
+};
+
+const EPSILON: f32 = 1e-5;
+
+fn approx_eq(a: f32, b: f32) -> bool {
+    (a - b).abs() < EPSILON
+}
+
+// phase lock tests
+
+#[test]
+fn test_square_toggles_on_main_wraps() {
+    // 10 samples per main cycle, so the sub flips every 10 samples
+    let main = NaiveOsc::new(1000.0, 100.0, Waveform::Saw);
+    let mut osc = SubOsc::new(main, SubWaveform::Square, SubOctave::One);
+
+    let subs: Vec<f32> = (0..40).map(|_| osc.next_outputs().1).collect();
+    for (i, sub) in subs.iter().enumerate() {
+        // away from the edges, where polyBLEP leaves the naive square
+        if i % 10 != 9 && i % 10 != 0 {
+            let expected = if (i + 1) % 20 < 10 { 1.0 } else { -1.0 };
+            assert!(approx_eq(*sub, expected), "sample {i}: {sub}");
+        }
+    }
+}
+
+#[test]
+fn test_matches_poly_blep_an_octave_down() {
+    // exact increments, so the sub and the reference agree closely
+    for (waveform, reference) in [
+        (SubWaveform::Square, Waveform::Square),
+        (SubWaveform::Saw, Waveform::Saw),
+        (SubWaveform::Sine, Waveform::Sine),
+        (SubWaveform::Triangle, Waveform::Triangle),
+    ] {
+        let main = PolyBlepOsc::new(1024.0, 37.0, Waveform::Saw);
+        let mut osc = SubOsc::new(main, waveform, SubOctave::One);
+        let mut expected = PolyBlepOsc::new(1024.0, 18.5, reference);
+
+        for _ in 0..2000 {
+            assert!(approx_eq(osc.next_outputs().1, expected.next_sample()));
+        }
+    }
+}
+
+#[test]
+fn test_matches_poly_blep_two_octaves_down() {
+    let main = PolyBlepOsc::new(1024.0, 37.0, Waveform::Saw);
+    let mut osc = SubOsc::new(main, SubWaveform::Square, SubOctave::Two);
+    let mut expected = PolyBlepOsc::new(1024.0, 9.25, Waveform::Square);
+
+    for _ in 0..2000 {
+        assert!(approx_eq(osc.next_outputs().1, expected.next_sample()));
+    }
+}
+
+#[test]
+fn test_stays_locked_through_frequency_changes() {
+    let main = PolyBlepOsc::new(44100.0, 110.0, Waveform::Saw);
+    let mut osc = SubOsc::new(main, SubWaveform::Square, SubOctave::Two);
+
+    for i in 0..100_000 {
+        osc.set_frequency(110.0 + (i % 997) as f32 * 0.731);
+        osc.next_sample();
+
+        // the sub phase is always a quarter of the main cycles counted
+        let sub = osc.sub_phase() * 4.0;
+        assert!(((sub - osc.phase()) - (sub - osc.phase()).round()).abs() < 1e-3);
+    }
+}
+
+#[test]
+fn test_negative_frequency_runs_backwards() {
+    let main = PolyBlepOsc::new(1024.0, -37.0, Waveform::Saw);
+    let mut osc = SubOsc::new(main, SubWaveform::Saw, SubOctave::One);
+    let mut expected = PolyBlepOsc::new(1024.0, -18.5, Waveform::Saw);
+
+    for _ in 0..2000 {
+        assert!(approx_eq(osc.next_outputs().1, expected.next_sample()));
+    }
+}
+
+#[test]
+fn test_square_aliases_less_than_naive() {
+    let cycles = 127;
+    let hz = analysis_frequency(44100.0, cycles);
+
+    // the main oscillator plays an octave up, so the sub lands on `hz`
+    let main = PolyBlepOsc::new(44100.0, 2.0 * hz, Waveform::Saw);
+    let mut osc = SubOsc::new(main, SubWaveform::Square, SubOctave::One);
+    let mut naive = NaiveOsc::new(44100.0, hz, Waveform::Square);
+
+    let sub: Vec<f32> = (0..ANALYSIS_LEN).map(|_| osc.next_outputs().1).collect();
+    let naive: Vec<f32> = (0..ANALYSIS_LEN).map(|_| naive.next_sample()).collect();
+
+    assert!(aliasing_ratio(&sub, cycles) < aliasing_ratio(&naive, cycles) / 4.0);
+}
+
+#[test]
+fn test_stays_locked_while_main_glides() {
+    let mut main: PolyBlepOsc = PolyBlepOsc::new(44100.0, 200.0, Waveform::Saw);
+    main.set_glide_time(GlideTime::Samples(8.0));
+    let mut osc = SubOsc::new(main, SubWaveform::Square, SubOctave::One);
+
+    for i in 0..2000 {
+        if i % 50 == 0 {
+            osc.set_frequency(if i % 100 == 0 { 15000.0 } else { 200.0 });
+        }
+
+        // the sub moves half as far as the main, never jumping a half cycle
+        let before = osc.sub_phase();
+        osc.next_outputs();
+        let step = (osc.sub_phase() - before).rem_euclid(1.0);
+        assert!(step < 0.25, "sample {i}: {step}");
+    }
+}
+
+// mix tests
+
+#[test]
+fn test_mix_adds_sub_at_level() {
+    let main = PolyBlepOsc::new(44100.0, 220.0, Waveform::Saw);
+    let mut mixed = SubOsc::new(main, SubWaveform::Sine, SubOctave::One);
+    let main = PolyBlepOsc::new(44100.0, 220.0, Waveform::Saw);
+    let mut split = SubOsc::new(main, SubWaveform::Sine, SubOctave::One);
+    mixed.set_level(0.4);
+
+    for _ in 0..256 {
+        let (main, sub) = split.next_outputs();
+        assert!(approx_eq(mixed.next_sample(), main + 0.4 * sub));
+    }
+}
+
+#[test]
+fn test_set_octave_keeps_place_in_main_cycle() {
+    let main = NaiveOsc::new(1000.0, 100.0, Waveform::Saw);
+    let mut osc = SubOsc::new(main, SubWaveform::Saw, SubOctave::Two);
+    for _ in 0..35 {
+        osc.next_sample();
+    }
+
+    // three main cycles counted, and half way through the fourth
+    assert!(approx_eq(osc.sub_phase(), 0.875));
+    osc.set_octave(SubOctave::One);
+    osc.next_sample();
+    assert!(approx_eq(osc.sub_phase(), 0.8));
+}
+
+// trait impl tests
+
+#[test]
+fn test_set_frequency_drives_main() {
+    let main = NaiveOsc::new(1000.0, 100.0, Waveform::Saw);
+    let mut osc = SubOsc::new(main, SubWaveform::Square, SubOctave::One);
+    osc.set_frequency(250.0);
+    osc.next_sample();
+
+    assert!(approx_eq(osc.phase(), 0.25));
+    assert!(approx_eq(osc.sub_phase(), 0.125));
+}
+
+#[test]
+fn test_set_waveform_drives_main() {
+    let main = NaiveOsc::new(1000.0, 100.0, Waveform::Saw);
+    let mut osc = SubOsc::new(main, SubWaveform::Square, SubOctave::One);
+    osc.set_waveform(Waveform::Square);
+    osc.set_level(0.0);
+
+    assert!(approx_eq(osc.next_sample(), 1.0));
+}
+
+#[test]
+fn test_reset_restarts_sub() {
+    let main = NaiveOsc::new(1000.0, 100.0, Waveform::Saw);
+    let mut osc = SubOsc::new(main, SubWaveform::Square, SubOctave::Two);
+
+    let first: Vec<f32> = (0..45).map(|_| osc.next_sample()).collect();
+    osc.reset();
+    assert!(approx_eq(osc.sub_phase(), 0.0));
+    let second: Vec<f32> = (0..45).map(|_| osc.next_sample()).collect();
+
+    for (a, b) in first.iter().zip(&second) {
+        assert!(approx_eq(*a, *b));
+    }
+}
+
+#[test]
+fn test_next_wrap_reports_main() {
+    let mut main = NaiveOsc::new(10.0, 3.0, Waveform::Saw); // inc = 0.3
+    main.set_phase(0.9);
+    let osc = SubOsc::new(main, SubWaveform::Square, SubOctave::One);
+
+    let fraction: f32 = osc.next_wrap().unwrap();
+    assert!((fraction - 1.0 / 3.0).abs() < 1e-5);
+}
+
+#[test]
+fn test_fill_buffer() {
+    let main = PolyBlepOsc::new(44100.0, 110.0, Waveform::Saw);
+    let mut filled = SubOsc::new(main, SubWaveform::Square, SubOctave::One);
+    let main = PolyBlepOsc::new(44100.0, 110.0, Waveform::Saw);
+    let mut reference = SubOsc::new(main, SubWaveform::Square, SubOctave::One);
+    let mut buffer = [0.0f32; 64];
+
+    filled.fill(&mut buffer);
+
+    for sample in buffer {
+        assert!(approx_eq(sample, reference.next_sample()));
+    }
+}
+
+#[test]
+fn test_iterator() {
+    let main = PolyBlepOsc::new(44100.0, 110.0, Waveform::Saw);
+    let osc = SubOsc::new(main, SubWaveform::Square, SubOctave::One);
+    let samples: Vec<f32> = osc.take(64).collect();
+    assert_eq!(samples.len(), 64);
+}
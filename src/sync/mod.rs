@@ -0,0 +1,131 @@
+#[cfg(test)]
+mod tests;
+
+use core::marker::PhantomData;
+
+use crate::{Float, Oscillator, Waveform};
+
+/// A hard-synced pair of oscillators.
+///
+/// Every time the master wraps, the slave restarts its cycle at the exact
+/// sub-sample position of the wrap. The output is the slave's signal, with
+/// the pitch of the master. Sweeping the slave's frequency produces the
+/// classic sync lead sound.
+///
+/// The master's own output is not heard, so its waveform doesn't matter.
+/// Band-limited slaves such as [`PolyBlepOsc`](crate::poly_blep::PolyBlepOsc)
+/// smooth each reset with a polyBLEP of the right height.
+///
+/// # Example
+///
+/// ```
+/// use oscy::{poly_blep::PolyBlepOsc, sync::HardSync, Oscillator, Waveform};
+///
+/// let master = PolyBlepOsc::new(44100.0, 110.0, Waveform::Saw);
+/// let slave = PolyBlepOsc::new(44100.0, 330.0, Waveform::Saw);
+/// let mut lead = HardSync::new(master, slave);
+///
+/// // sweep the slave for the typical sync timbre
+/// lead.slave_mut().set_frequency(400.0);
+/// let sample = lead.next_sample();
+/// assert!(sample >= -1.0 && sample <= 1.0);
+/// ```
+pub struct HardSync<M, S, T: Float = f32> {
+    master: M,
+    slave: S,
+    // the sample type the pair runs at
+    _sample: PhantomData<T>,
+}
+
+impl<T: Float, M: Oscillator<T>, S: Oscillator<T>> HardSync<M, S, T> {
+    /// Creates a hard-synced pair from a master and a slave oscillator.
+    pub fn new(master: M, slave: S) -> Self {
+        Self {
+            master,
+            slave,
+            _sample: PhantomData,
+        }
+    }
+
+    /// Returns a mutable reference to the master oscillator.
+    pub fn master_mut(&mut self) -> &mut M {
+        &mut self.master
+    }
+
+    /// Returns a mutable reference to the slave oscillator.
+    pub fn slave_mut(&mut self) -> &mut S {
+        &mut self.slave
+    }
+
+    /// Consumes the pair, returning the master and slave oscillators.
+    pub fn into_inner(self) -> (M, S) {
+        (self.master, self.slave)
+    }
+}
+
+impl<T: Float, M: Oscillator<T>, S: Oscillator<T>> Oscillator<T> for HardSync<M, S, T> {
+    /// Sets the master frequency, which is the pitch of the pair.
+    fn set_frequency(&mut self, hz: T) {
+        self.master.set_frequency(hz);
+    }
+
+    fn set_phase(&mut self, phase: T) {
+        self.master.set_phase(phase);
+        self.slave.set_phase(phase);
+    }
+
+    /// Returns the master phase, which is the phase of the pair's cycle.
+    fn phase(&self) -> T {
+        self.master.phase()
+    }
+
+    fn reset(&mut self) {
+        self.master.reset();
+        self.slave.reset();
+    }
+
+    fn next_wrap(&self) -> Option<T> {
+        self.master.next_wrap()
+    }
+
+    fn sync(&mut self, fraction: T) {
+        self.master.sync(fraction);
+    }
+
+    /// Sets the slave waveform, which is the one heard.
+    fn set_waveform(&mut self, waveform: Waveform) {
+        self.slave.set_waveform(waveform);
+    }
+
+    fn next_sample(&mut self) -> T {
+        self.master.next_sample();
+
+        // tell the slave about a wrap in the step after this sample, so a
+        // band-limited slave can correct the sample before the reset too
+        if let Some(fraction) = self.master.next_wrap() {
+            self.slave.sync(fraction);
+        }
+
+        self.slave.next_sample()
+    }
+
+    /// Modulates the master phase, which moves the slave's resets. The
+    /// slave itself keeps running undisturbed.
+    fn fill_with_pm(&mut self, buffer: &mut [T], phase_offsets: &[T]) {
+        for (sample, &offset) in buffer.iter_mut().zip(phase_offsets) {
+            let phase = self.master.phase();
+            self.master.set_phase(phase + offset);
+            *sample = self.next_sample();
+            let phase = self.master.phase();
+            self.master.set_phase(phase - offset);
+        }
+    }
+}
+
+impl<T: Float, M: Oscillator<T>, S: Oscillator<T>> Iterator for HardSync<M, S, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        Some(self.next_sample())
+    }
+}
@@ -0,0 +1,248 @@
+use super::HardSync;
+use crate::{
+    Oscillator, Waveform,
+    naive::NaiveOsc,
+    poly_blep::PolyBlepOsc,
+    smooth::GlideTime,
+    test_utils::{ANALYSIS_LEN, aliasing_ratio, analysis_frequency},
+};
+
+const EPSILON: f32 = 1e-6;
+
+fn approx_eq(a: f32, b: f32) -> bool {
+    (a - b).abs() < EPSILON
+}
+
+#[test]
+fn test_slave_follows_master_period() {
+    // master: 10 samples per cycle, slave: 4 samples per cycle
+    let master = NaiveOsc::new(1000.0, 100.0, Waveform::Saw);
+    let slave = NaiveOsc::new(1000.0, 250.0, Waveform::Saw);
+    let sync = HardSync::new(master, slave);
+
+    let samples: Vec<f32> = sync.take(40).collect();
+
+    // once the master has wrapped, the output repeats with its period
+    for i in 10..30 {
+        assert!((samples[i] - samples[i + 10]).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn test_reset_lands_at_fractional_position() {
+    // master increment 0.3: wraps a third of the way into the 4th step
+    let master = NaiveOsc::new(10.0, 3.0, Waveform::Saw);
+    let slave = NaiveOsc::new(10.0, 1.5, Waveform::Saw); // increment 0.15
+    let mut sync = HardSync::new(master, slave);
+
+    assert!(approx_eq(sync.next_sample(), -0.7)); // phase 0.15
+    assert!(approx_eq(sync.next_sample(), -0.4)); // phase 0.3
+    assert!(approx_eq(sync.next_sample(), -0.1)); // phase 0.45
+
+    // reset at 1/3 of the step, leaving 2/3 of an increment: phase 0.1
+    assert!(approx_eq(sync.next_sample(), -0.8));
+}
+
+#[test]
+fn test_set_frequency_drives_master() {
+    let master = NaiveOsc::new(1000.0, 100.0, Waveform::Saw);
+    let slave = NaiveOsc::new(1000.0, 330.0, Waveform::Saw);
+    let mut sync = HardSync::new(master, slave);
+
+    // master now has 20 samples per cycle
+    sync.set_frequency(50.0);
+    let samples: Vec<f32> = sync.take(60).collect();
+
+    for i in 20..40 {
+        assert!((samples[i] - samples[i + 20]).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn test_fill_with_fm_modulates_master() {
+    let mut block = HardSync::new(
+        NaiveOsc::new(1000.0, 100.0, Waveform::Saw),
+        NaiveOsc::new(1000.0, 330.0, Waveform::Saw),
+    );
+    let mut single = HardSync::new(
+        NaiveOsc::new(1000.0, 100.0, Waveform::Saw),
+        NaiveOsc::new(1000.0, 330.0, Waveform::Saw),
+    );
+
+    let frequencies: Vec<f32> = (0..64).map(|i| 50.0 + i as f32).collect();
+    let mut buffer = [0.0f32; 64];
+    block.fill_with_fm(&mut buffer, &frequencies);
+
+    for (sample, &hz) in buffer.iter().zip(&frequencies) {
+        single.set_frequency(hz);
+        assert!(approx_eq(*sample, single.next_sample()));
+    }
+}
+
+#[test]
+fn test_set_waveform_drives_slave() {
+    let mut sync = HardSync::new(
+        NaiveOsc::new(1000.0, 100.0, Waveform::Saw),
+        NaiveOsc::new(1000.0, 330.0, Waveform::Saw),
+    );
+    let mut square = HardSync::new(
+        NaiveOsc::new(1000.0, 100.0, Waveform::Saw),
+        NaiveOsc::new(1000.0, 330.0, Waveform::Square),
+    );
+    sync.set_waveform(Waveform::Square);
+
+    for _ in 0..64 {
+        assert!(approx_eq(sync.next_sample(), square.next_sample()));
+    }
+}
+
+#[test]
+fn test_reset_restarts_both() {
+    let master = NaiveOsc::new(1000.0, 100.0, Waveform::Saw);
+    let slave = NaiveOsc::new(1000.0, 330.0, Waveform::Saw);
+    let mut sync = HardSync::new(master, slave);
+
+    let first: Vec<f32> = (0..25).map(|_| sync.next_sample()).collect();
+    sync.reset();
+    let second: Vec<f32> = (0..25).map(|_| sync.next_sample()).collect();
+
+    for (a, b) in first.iter().zip(&second) {
+        assert!(approx_eq(*a, *b));
+    }
+}
+
+#[test]
+fn test_set_phase_sets_both() {
+    let master = NaiveOsc::new(1000.0, 100.0, Waveform::Saw);
+    let slave = NaiveOsc::new(1000.0, 330.0, Waveform::Saw);
+    let mut sync = HardSync::new(master, slave);
+    sync.set_phase(0.4);
+
+    assert!(approx_eq(sync.master_mut().phase(), 0.4));
+    assert!(approx_eq(sync.slave_mut().phase(), 0.4));
+}
+
+#[test]
+fn test_fill_with_pm_leaves_slave_running() {
+    let pair = || {
+        let master = NaiveOsc::new(1000.0, 100.0, Waveform::Saw);
+        let slave = NaiveOsc::new(1000.0, 330.0, Waveform::Saw);
+        HardSync::new(master, slave)
+    };
+    let mut modulated = pair();
+    let mut plain = pair();
+
+    // without offsets, phase modulation changes nothing
+    let mut buffer = [0.0; 50];
+    modulated.fill_with_pm(&mut buffer, &[0.0; 50]);
+    for sample in buffer {
+        assert!(approx_eq(sample, plain.next_sample()));
+    }
+}
+
+#[test]
+fn test_slave_follows_gliding_master() {
+    let mut master = PolyBlepOsc::new(44100.0, 200.0, Waveform::Saw);
+    master.set_glide_time(GlideTime::Samples(8.0));
+    // too slow to ever wrap on its own
+    let slave = NaiveOsc::new(44100.0, 10.0, Waveform::Saw);
+    let mut osc = HardSync::new(master, slave);
+
+    for i in 0..2000 {
+        if i % 50 == 0 {
+            osc.set_frequency(if i % 100 == 0 { 15000.0 } else { 200.0 });
+        }
+
+        let master_before = osc.master_mut().phase();
+        let slave_before = osc.slave_mut().phase();
+        osc.next_sample();
+
+        // the slave restarts in exactly the steps the master wraps in
+        let master_wrapped = osc.master_mut().phase() < master_before;
+        let slave_restarted = osc.slave_mut().phase() < slave_before;
+        assert_eq!(master_wrapped, slave_restarted, "sample {i}");
+    }
+}
+
+#[test]
+fn test_poly_blep_slave_output_range() {
+    let master = PolyBlepOsc::new(44100.0, 110.0, Waveform::Saw);
+    let slave = PolyBlepOsc::new(44100.0, 370.0, Waveform::Saw);
+    let mut sync = HardSync::new(master, slave);
+
+    for i in 0..4410 {
+        // sweep the slave like a sync lead
+        sync.slave_mut().set_frequency(200.0 + i as f32 * 0.5);
+        let sample = sync.next_sample();
+        assert!((-1.0..=1.0).contains(&sample));
+    }
+}
+
+#[test]
+fn test_poly_blep_slave_smooths_reset() {
+    // master wraps a third of the way into the 4th step, see above
+    let mut poly = HardSync::new(
+        PolyBlepOsc::new(10.0, 3.0, Waveform::Sine),
+        PolyBlepOsc::new(10.0, 2.0, Waveform::Sine),
+    );
+    let mut naive = HardSync::new(
+        NaiveOsc::new(10.0, 3.0, Waveform::Sine),
+        NaiveOsc::new(10.0, 2.0, Waveform::Sine),
+    );
+
+    let poly_samples: Vec<f32> = (0..5).map(|_| poly.next_sample()).collect();
+    let naive_samples: Vec<f32> = (0..5).map(|_| naive.next_sample()).collect();
+
+    // the slave resets from phase 2/3 (a sine has no discontinuities of its
+    // own), so only the samples on either side of the reset differ
+    assert!(approx_eq(poly_samples[1], naive_samples[1]));
+    assert!((poly_samples[2] - naive_samples[2]).abs() > 0.1);
+    assert!((poly_samples[3] - naive_samples[3]).abs() > 0.01);
+    assert!(approx_eq(poly_samples[4], naive_samples[4]));
+}
+
+#[test]
+fn test_poly_blep_sync_aliases_less_than_naive() {
+    let cycles = 61;
+    let frequency = analysis_frequency(44100.0, cycles);
+
+    for waveform in [Waveform::Saw, Waveform::Sine, Waveform::Square] {
+        let poly = HardSync::new(
+            PolyBlepOsc::new(44100.0, frequency, Waveform::Saw),
+            PolyBlepOsc::new(44100.0, frequency * 2.37, waveform),
+        );
+        let naive = HardSync::new(
+            NaiveOsc::new(44100.0, frequency, Waveform::Saw),
+            NaiveOsc::new(44100.0, frequency * 2.37, waveform),
+        );
+
+        let poly_samples: Vec<f32> = poly.take(ANALYSIS_LEN).collect();
+        let naive_samples: Vec<f32> = naive.take(ANALYSIS_LEN).collect();
+
+        let poly = aliasing_ratio(&poly_samples, cycles);
+        let naive = aliasing_ratio(&naive_samples, cycles);
+        assert!(poly < naive * 0.1);
+    }
+}
+
+#[test]
+fn test_poly_blep_backwards_slave_aliases_less_than_naive() {
+    let cycles = 61;
+    let frequency = analysis_frequency(44100.0, cycles);
+
+    let poly = HardSync::new(
+        PolyBlepOsc::new(44100.0, frequency, Waveform::Saw),
+        PolyBlepOsc::new(44100.0, frequency * -2.37, Waveform::Saw),
+    );
+    let naive = HardSync::new(
+        NaiveOsc::new(44100.0, frequency, Waveform::Saw),
+        NaiveOsc::new(44100.0, frequency * -2.37, Waveform::Saw),
+    );
+
+    let poly_samples: Vec<f32> = poly.take(ANALYSIS_LEN).collect();
+    let naive_samples: Vec<f32> = naive.take(ANALYSIS_LEN).collect();
+
+    let poly = aliasing_ratio(&poly_samples, cycles);
+    let naive = aliasing_ratio(&naive_samples, cycles);
+    assert!(poly < naive * 0.1);
+}
@@ -0,0 +1,75 @@
+//! Shared helpers for measuring oscillator output in tests.
+
+use std::f64::consts::TAU;
+
+/// Number of samples used for spectral measurements.
+pub const ANALYSIS_LEN: usize = 4096;
+
+/// Returns the frequency that completes exactly `cycles` periods in
+/// [`ANALYSIS_LEN`] samples.
+pub fn analysis_frequency(sample_rate: f32, cycles: usize) -> f32 {
+    (cycles as f64 * sample_rate as f64 / ANALYSIS_LEN as f64) as f32
+}
+
+/// Returns the fraction of signal energy that lies outside the harmonics,
+/// which for a periodic oscillator is the aliased energy.
+///
+/// `samples` must span exactly `cycles` periods, with `cycles` coprime to the
+/// length (an odd prime works for power-of-two lengths). Harmonics then fall
+/// exactly on multiples of `cycles` and every aliased partial lands on some
+/// other bin, so no window is needed and there is no leakage between them.
+pub fn aliasing_ratio(samples: &[f32], cycles: usize) -> f32 {
+    let samples: Vec<f64> = samples.iter().map(|&s| s as f64).collect();
+
+    let mut total = 0.0;
+    let mut aliased = 0.0;
+
+    for bin in 0..=samples.len() / 2 {
+        let power = bin_power(&samples, bin);
+        total += power;
+
+        if bin % cycles != 0 {
+            aliased += power;
+        }
+    }
+
+    (aliased / total) as f32
+}
+
+/// Computes the DFT power of a single bin with the Goertzel algorithm.
+fn bin_power(samples: &[f64], bin: usize) -> f64 {
+    let omega = TAU * bin as f64 / samples.len() as f64;
+    let coeff = 2.0 * omega.cos();
+
+    let (mut s1, mut s2) = (0.0, 0.0);
+    for &x in samples {
+        let s0 = x + coeff * s1 - s2;
+        s2 = s1;
+        s1 = s0;
+    }
+
+    s1 * s1 + s2 * s2 - coeff * s1 * s2
+}
+
+/// Returns the energy of everything but the fundamental relative to the
+/// fundamental, in decibels: the THD including noise.
+///
+/// `samples` must span exactly `cycles` periods, see [`aliasing_ratio`].
+pub fn distortion_db(samples: &[f32], cycles: usize) -> f32 {
+    let samples: Vec<f64> = samples.iter().map(|&s| s as f64).collect();
+
+    let mut fundamental = 0.0;
+    let mut rest = 0.0;
+
+    for bin in 0..=samples.len() / 2 {
+        // rounding can leave near-silent bins slightly negative
+        let power = bin_power(&samples, bin).max(0.0);
+        if bin == cycles {
+            fundamental += power;
+        } else {
+            rest += power;
+        }
+    }
+
+    (10.0 * (rest / fundamental).log10()) as f32
+}
@@ -0,0 +1,327 @@
+#[cfg(test)]
+mod tests;
+
+use alloc::{vec, vec::Vec};
+
+use crate::{
+    Float, Oscillator, Waveform, advance_phase, next_wrap,
+    poly_blep::PolyBlepOsc,
+    rng::{Rng, next_seed},
+    wrap,
+};
+
+/// Relative detune of the outermost supersaw voices at full detune,
+/// measured on the JP-8000.
+const SUPERSAW_SPREAD: f64 = 0.11;
+
+/// How far apart the voices of a [`UnisonOsc`] are tuned.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Detune<T: Float = f32> {
+    /// The outermost voices are detuned this many cents below and above
+    /// the pitch, and the others spread evenly in between.
+    Cents(T),
+    /// A detune amount in [0.0, 1.0], following the curve of the JP-8000
+    /// supersaw. The curve stays fine over most of the range and rises
+    /// steeply near the top, up to about 11% either side (close to 190
+    /// cents) at 1.0.
+    Supersaw(T),
+}
+
+/// A unison oscillator stacking detuned polyBLEP voices.
+///
+/// Every voice plays the same waveform, detuned across the [`Detune`]
+/// range and panned across the stereo spread. Voices start at random
+/// phases by default, re-rolled on every [`Oscillator::reset`], so each
+/// note sounds slightly different like on analog polysynths. Seeding the
+/// random phases makes them repeatable.
+///
+/// The output is scaled by one over the square root of the voice count,
+/// which keeps the loudness of uncorrelated voices about that of one
+/// voice. [`Oscillator::next_sample`] gives the mono mix, and
+/// [`UnisonOsc::next_stereo`] the stereo one. The oscillator's phase is
+/// that of the undetuned pitch.
+///
+/// # Example
+///
+/// ```
+/// use oscy::{unison::{Detune, UnisonOsc}, Oscillator, Waveform};
+///
+/// let mut lead = UnisonOsc::supersaw(44100.0, 220.0);
+/// let (left, right) = lead.next_stereo();
+///
+/// let mut pad = UnisonOsc::new(44100.0, 110.0, Waveform::Pulse, 4);
+/// pad.set_detune(Detune::Cents(12.0));
+/// pad.set_spread(0.5);
+/// pad.set_pulse_width(0.3);
+/// let sample = pad.next_sample();
+/// ```
+pub struct UnisonOsc<T: Float = f32> {
+    voices: Vec<PolyBlepOsc<T>>,
+    // position of every voice from -1.0 to 1.0, both across the detune
+    // range and across the stereo field
+    positions: Vec<T>,
+    start_phases: Vec<T>,
+    // (left, right) gain of every voice, including the normalization
+    gains: Vec<(T, T)>,
+    // gain of every voice in the mono mix
+    scale: T,
+    frequency: T,
+    phase: T,
+    phase_increment: T,
+    sample_rate: T,
+    detune: Detune<T>,
+    spread: T,
+    random_phases: bool,
+    rng: Rng,
+}
+
+impl<T: Float> UnisonOsc<T> {
+    /// Creates a unison oscillator with `voices` voices of `waveform`.
+    ///
+    /// The voices start without detune or stereo spread, at random phases.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `voices` is zero.
+    pub fn new(sample_rate: T, frequency: T, waveform: Waveform, voices: usize) -> Self {
+        assert!(voices > 0, "a unison oscillator needs at least one voice");
+
+        let positions = (0..voices)
+            .map(|i| match voices {
+                1 => T::ZERO,
+                n => T::TWO * T::from_usize(i) / T::from_usize(n - 1) - T::ONE,
+            })
+            .collect();
+
+        let mut osc = Self {
+            voices: (0..voices)
+                .map(|_| PolyBlepOsc::new(sample_rate, frequency, waveform))
+                .collect(),
+            positions,
+            start_phases: vec![T::ZERO; voices],
+            gains: vec![(T::ZERO, T::ZERO); voices],
+            scale: T::ONE,
+            frequency,
+            phase: T::ZERO,
+            phase_increment: frequency / sample_rate,
+            sample_rate,
+            detune: Detune::Cents(T::ZERO),
+            spread: T::ZERO,
+            random_phases: true,
+            rng: Rng::new(next_seed()),
+        };
+        osc.update_gains();
+        osc.reset();
+        osc
+    }
+
+    /// Creates a JP-8000 style supersaw: seven saw voices at a supersaw
+    /// detune of 0.5, spread across the full stereo width.
+    pub fn supersaw(sample_rate: T, frequency: T) -> Self {
+        let mut osc = Self::new(sample_rate, frequency, Waveform::Saw, 7);
+        osc.set_detune(Detune::Supersaw(T::HALF));
+        osc.set_spread(T::ONE);
+        osc
+    }
+
+    /// Returns the number of voices.
+    pub fn voice_count(&self) -> usize {
+        self.voices.len()
+    }
+
+    /// Returns the detune.
+    pub fn detune(&self) -> Detune<T> {
+        self.detune
+    }
+
+    /// Sets how far apart the voices are tuned.
+    pub fn set_detune(&mut self, detune: Detune<T>) {
+        self.detune = detune;
+        self.update_frequencies();
+    }
+
+    /// Returns the stereo spread.
+    pub fn spread(&self) -> T {
+        self.spread
+    }
+
+    /// Sets the stereo spread, clamped to [0.0, 1.0].
+    ///
+    /// At 0.0 every voice sits in the middle, and at 1.0 the outermost
+    /// voices are panned hard left and right. The voices are panned in the
+    /// order of their detune, lowest on the left.
+    pub fn set_spread(&mut self, spread: T) {
+        self.spread = spread.clamp(T::ZERO, T::ONE);
+        self.update_gains();
+    }
+
+    /// Sets the pulse width of every voice, see
+    /// [`PolyBlepOsc::set_pulse_width`].
+    pub fn set_pulse_width(&mut self, width: T) {
+        for voice in &mut self.voices {
+            voice.set_pulse_width(width);
+        }
+    }
+
+    /// Sets the morph shape of every voice, see [`PolyBlepOsc::set_shape`].
+    pub fn set_shape(&mut self, shape: T) {
+        for voice in &mut self.voices {
+            voice.set_shape(shape);
+        }
+    }
+
+    /// Sets whether [`Oscillator::reset`] starts the voices at random
+    /// phases. Without, every voice starts at phase 0.0, which gives a
+    /// harder, identical attack on every note.
+    pub fn set_random_phases(&mut self, random: bool) {
+        self.random_phases = random;
+    }
+
+    /// Seeds the random start phases, so the following resets repeat the
+    /// same phases for the same seed.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = Rng::new(seed);
+    }
+
+    /// Generates the next stereo sample as `(left, right)`.
+    pub fn next_stereo(&mut self) -> (T, T) {
+        self.advance();
+
+        let mut left = T::ZERO;
+        let mut right = T::ZERO;
+        for (voice, &(left_gain, right_gain)) in self.voices.iter_mut().zip(&self.gains) {
+            let sample = voice.next_sample();
+            left += left_gain * sample;
+            right += right_gain * sample;
+        }
+        (left, right)
+    }
+
+    /// Fills `left` and `right` with consecutive stereo samples. Only as
+    /// many samples as the shorter slice holds are written.
+    pub fn fill_stereo(&mut self, left: &mut [T], right: &mut [T]) {
+        for (left, right) in left.iter_mut().zip(right.iter_mut()) {
+            (*left, *right) = self.next_stereo();
+        }
+    }
+
+    /// Tunes every voice to its place in the detune range.
+    fn update_frequencies(&mut self) {
+        for (voice, &position) in self.voices.iter_mut().zip(&self.positions) {
+            let ratio = match self.detune {
+                Detune::Cents(cents) => (position * cents / T::from_f64(1200.0)).exp2(),
+                Detune::Supersaw(amount) => {
+                    let spread = SUPERSAW_SPREAD * supersaw_detune(amount.to_f64());
+                    T::ONE + position * T::from_f64(spread)
+                }
+            };
+            voice.set_frequency(self.frequency * ratio);
+        }
+    }
+
+    /// Pans every voice to its place in the stereo spread.
+    fn update_gains(&mut self) {
+        self.scale = T::ONE / T::from_usize(self.voices.len()).sqrt();
+        let scale = self.scale;
+        for (gains, &position) in self.gains.iter_mut().zip(&self.positions) {
+            // fading out the opposite side keeps a centered voice at full level
+            let pan = position * self.spread;
+            *gains = (
+                scale * (T::ONE - pan).min(T::ONE),
+                scale * (T::ONE + pan).min(T::ONE),
+            );
+        }
+    }
+
+    /// Moves the phase of the undetuned pitch one step forward.
+    fn advance(&mut self) {
+        self.phase = advance_phase(self.phase, self.phase_increment);
+    }
+}
+
+/// Maps a supersaw detune amount to the share of the full detune, with the
+/// polynomial fitted to the JP-8000 by Adam Szabo.
+fn supersaw_detune(amount: f64) -> f64 {
+    const COEFFICIENTS: [f64; 12] = [
+        10028.7312891634,
+        -50818.8652045924,
+        111363.4808729368,
+        -138150.6761080548,
+        106649.6679158292,
+        -53046.9642751875,
+        17019.9518580080,
+        -3425.0836591318,
+        404.2703938388,
+        -24.1878824391,
+        0.6717417634,
+        0.0030115596,
+    ];
+
+    let x = amount.clamp(0.0, 1.0);
+    COEFFICIENTS.iter().fold(0.0, |sum, &c| sum * x + c)
+}
+
+impl<T: Float> Oscillator<T> for UnisonOsc<T> {
+    fn set_frequency(&mut self, hz: T) {
+        self.frequency = hz;
+        self.phase_increment = hz / self.sample_rate;
+        self.update_frequencies();
+    }
+
+    /// Sets the phase of the undetuned pitch. Every voice moves to the
+    /// phase plus its start phase.
+    fn set_phase(&mut self, phase: T) {
+        self.phase = wrap(phase);
+        for (voice, &start) in self.voices.iter_mut().zip(&self.start_phases) {
+            voice.set_phase(self.phase + start);
+        }
+    }
+
+    fn phase(&self) -> T {
+        self.phase
+    }
+
+    /// Restarts every voice, at new random start phases unless those are
+    /// turned off with [`UnisonOsc::set_random_phases`].
+    fn reset(&mut self) {
+        for (voice, start) in self.voices.iter_mut().zip(&mut self.start_phases) {
+            voice.reset();
+            *start = if self.random_phases {
+                self.rng.next_unit()
+            } else {
+                T::ZERO
+            };
+        }
+        self.set_phase(T::ZERO);
+    }
+
+    fn next_wrap(&self) -> Option<T> {
+        next_wrap(self.phase, self.phase_increment)
+    }
+
+    /// Switches the waveform of every voice.
+    fn set_waveform(&mut self, waveform: Waveform) {
+        for voice in &mut self.voices {
+            voice.set_waveform(waveform);
+        }
+    }
+
+    fn next_sample(&mut self) -> T {
+        self.advance();
+
+        self.scale
+            * self
+                .voices
+                .iter_mut()
+                .map(|voice| voice.next_sample())
+                .sum::<T>()
+    }
+}
+
+impl<T: Float> Iterator for UnisonOsc<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        Some(self.next_sample())
+    }
+}
@@ -0,0 +1,306 @@
+use super::{Detune, UnisonOsc, supersaw_detune};
+use crate::{Oscillator, Waveform, naive::NaiveOsc, poly_blep::PolyBlepOsc};
+
+const EPSILON: f32 = 1e-5;
+
+fn approx_eq(a: f32, b: f32) -> bool {
+    (a - b).abs() < EPSILON
+}
+
+/// A unison oscillator with every voice starting at phase 0.0.
+fn aligned(frequency: f32, waveform: Waveform, voices: usize) -> UnisonOsc {
+    let mut osc = UnisonOsc::new(44100.0, frequency, waveform, voices);
+    osc.set_random_phases(false);
+    osc.reset();
+    osc
+}
+
+// voice tests
+
+#[test]
+fn test_single_voice_matches_poly_blep() {
+    let mut osc = aligned(440.0, Waveform::Saw, 1);
+    let mut reference = PolyBlepOsc::new(44100.0, 440.0, Waveform::Saw);
+
+    for _ in 0..1000 {
+        assert!(approx_eq(osc.next_sample(), reference.next_sample()));
+    }
+}
+
+#[test]
+fn test_undetuned_voices_add_up() {
+    // in phase, n voices scaled by 1/sqrt(n) add up to sqrt(n) times one
+    let mut osc = aligned(440.0, Waveform::Saw, 4);
+    let mut reference = PolyBlepOsc::new(44100.0, 440.0, Waveform::Saw);
+
+    for _ in 0..1000 {
+        assert!((osc.next_sample() - 2.0 * reference.next_sample()).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn test_cents_detune_outer_voices() {
+    // an octave either side
+    let mut osc = aligned(441.0, Waveform::Sine, 2);
+    osc.set_detune(Detune::Cents(1200.0));
+    let mut low = NaiveOsc::new(44100.0, 220.5, Waveform::Sine);
+    let mut high = NaiveOsc::new(44100.0, 882.0, Waveform::Sine);
+
+    for _ in 0..1000 {
+        let expected = (low.next_sample() + high.next_sample()) / 2.0f32.sqrt();
+        assert!((osc.next_sample() - expected).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn test_cents_detune_keeps_middle_voice() {
+    let mut osc = aligned(441.0, Waveform::Sine, 3);
+    osc.set_detune(Detune::Cents(1200.0));
+    let mut low = NaiveOsc::new(44100.0, 220.5, Waveform::Sine);
+    let mut middle = NaiveOsc::new(44100.0, 441.0, Waveform::Sine);
+    let mut high = NaiveOsc::new(44100.0, 882.0, Waveform::Sine);
+
+    for _ in 0..1000 {
+        let sum = low.next_sample() + middle.next_sample() + high.next_sample();
+        assert!((osc.next_sample() - sum / 3.0f32.sqrt()).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn test_supersaw_curve() {
+    assert!(supersaw_detune(0.0).abs() < 0.01);
+    assert!((supersaw_detune(1.0) - 1.0).abs() < 0.01);
+
+    // rising throughout, slowly at first, give or take a ripple of the fit
+    let curve: Vec<f64> = (0..=100)
+        .map(|i| supersaw_detune(i as f64 / 100.0))
+        .collect();
+    assert!(curve.windows(2).all(|pair| pair[1] > pair[0] - 1e-4));
+    assert!(curve[50] < 0.1);
+}
+
+#[test]
+fn test_supersaw_detune_outer_voices() {
+    let mut osc = aligned(1000.0, Waveform::Sine, 2);
+    osc.set_detune(Detune::Supersaw(1.0));
+
+    let spread = (0.11 * supersaw_detune(1.0)) as f32;
+    let mut low = NaiveOsc::new(44100.0, 1000.0 * (1.0 - spread), Waveform::Sine);
+    let mut high = NaiveOsc::new(44100.0, 1000.0 * (1.0 + spread), Waveform::Sine);
+
+    for _ in 0..1000 {
+        let expected = (low.next_sample() + high.next_sample()) / 2.0f32.sqrt();
+        assert!((osc.next_sample() - expected).abs() < 1e-3);
+    }
+}
+
+#[test]
+fn test_set_frequency_keeps_detune() {
+    let mut osc = aligned(100.0, Waveform::Sine, 2);
+    osc.set_detune(Detune::Cents(1200.0));
+    osc.set_frequency(441.0);
+
+    let mut reference = aligned(441.0, Waveform::Sine, 2);
+    reference.set_detune(Detune::Cents(1200.0));
+
+    for _ in 0..100 {
+        assert!(approx_eq(osc.next_sample(), reference.next_sample()));
+    }
+}
+
+// stereo tests
+
+#[test]
+fn test_no_spread_is_centered() {
+    let mut stereo = aligned(440.0, Waveform::Saw, 5);
+    let mut mono = aligned(440.0, Waveform::Saw, 5);
+    stereo.set_detune(Detune::Cents(30.0));
+    mono.set_detune(Detune::Cents(30.0));
+
+    for _ in 0..1000 {
+        let (left, right) = stereo.next_stereo();
+        let sample = mono.next_sample();
+        assert!(approx_eq(left, sample));
+        assert!(approx_eq(right, sample));
+    }
+}
+
+#[test]
+fn test_full_spread_pans_outer_voices_hard() {
+    let mut osc = aligned(441.0, Waveform::Sine, 2);
+    osc.set_detune(Detune::Cents(1200.0));
+    osc.set_spread(1.0);
+    let mut low = NaiveOsc::new(44100.0, 220.5, Waveform::Sine);
+    let mut high = NaiveOsc::new(44100.0, 882.0, Waveform::Sine);
+
+    for _ in 0..1000 {
+        let (left, right) = osc.next_stereo();
+        let scale = 1.0 / 2.0f32.sqrt();
+        assert!((left - scale * low.next_sample()).abs() < 1e-4);
+        assert!((right - scale * high.next_sample()).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn test_fill_stereo() {
+    let mut filled = UnisonOsc::supersaw(44100.0, 220.0);
+    let mut reference = UnisonOsc::supersaw(44100.0, 220.0);
+    for osc in [&mut filled, &mut reference] {
+        osc.set_seed(1);
+        osc.reset();
+    }
+    let mut left = [0.0f32; 64];
+    let mut right = [0.0f32; 64];
+
+    filled.fill_stereo(&mut left, &mut right);
+
+    for (left, right) in left.into_iter().zip(right) {
+        let expected = reference.next_stereo();
+        assert!(approx_eq(left, expected.0));
+        assert!(approx_eq(right, expected.1));
+    }
+}
+
+// phase tests
+
+#[test]
+fn test_random_start_phases() {
+    let mut osc = UnisonOsc::new(44100.0, 0.0, Waveform::Saw, 4);
+    osc.set_detune(Detune::Cents(10.0));
+
+    // at 0 Hz every voice holds its start phase
+    let starts: Vec<f32> = osc.voices.iter().map(|voice| voice.phase()).collect();
+    assert!(starts.windows(2).all(|pair| pair[0] != pair[1]));
+
+    // every reset rolls new phases
+    osc.reset();
+    assert_ne!(osc.voices[0].phase(), starts[0]);
+    assert!(approx_eq(osc.phase(), 0.0));
+}
+
+#[test]
+fn test_seed_repeats_phases() {
+    let mut a = UnisonOsc::new(44100.0, 440.0, Waveform::Saw, 3);
+    let mut b = UnisonOsc::new(44100.0, 440.0, Waveform::Saw, 3);
+    a.set_seed(7);
+    b.set_seed(7);
+    a.reset();
+    b.reset();
+
+    for _ in 0..100 {
+        assert!(approx_eq(a.next_sample(), b.next_sample()));
+    }
+}
+
+#[test]
+fn test_fresh_oscillators_differ() {
+    let a: UnisonOsc = UnisonOsc::new(44100.0, 440.0, Waveform::Saw, 3);
+    let b: UnisonOsc = UnisonOsc::new(44100.0, 440.0, Waveform::Saw, 3);
+    assert!(a.zip(b).take(100).any(|(a, b)| a != b));
+}
+
+#[test]
+fn test_random_phases_off_at_next_reset() {
+    let mut osc = UnisonOsc::new(44100.0, 440.0, Waveform::Saw, 3);
+    osc.set_random_phases(false);
+    osc.reset();
+
+    assert!(osc.voices.iter().all(|voice| voice.phase() == 0.0));
+}
+
+#[test]
+fn test_tiny_negative_phase_stays_below_one() {
+    // a step from 1e-9 back by 2e-9 lands at -1e-9, which rounds to 1.0
+    let mut osc: UnisonOsc = UnisonOsc::new(1.0, -2e-9, Waveform::Saw, 3);
+    osc.set_phase(1e-9);
+    osc.next_sample();
+    assert!(osc.phase() < 1.0);
+
+    osc.set_phase(-1e-9);
+    assert!(osc.phase() < 1.0);
+}
+
+// sample type tests
+
+#[test]
+fn test_f64_matches_f32() {
+    let mut single = UnisonOsc::<f32>::new(44100.0, 220.0, Waveform::Triangle, 5);
+    let mut double = UnisonOsc::<f64>::new(44100.0, 220.0, Waveform::Triangle, 5);
+    single.set_detune(Detune::Cents(20.0));
+    double.set_detune(Detune::Cents(20.0));
+
+    // both draw the same start phases from the same seed
+    single.set_seed(1);
+    double.set_seed(1);
+    single.reset();
+    double.reset();
+    for _ in 0..1000 {
+        assert!((single.next_sample() as f64 - double.next_sample()).abs() < 1e-3);
+    }
+}
+
+// trait impl tests
+
+#[test]
+fn test_set_phase() {
+    let mut osc = aligned(1.0, Waveform::Saw, 3);
+    osc.set_phase(-0.25);
+    assert!(approx_eq(osc.phase(), 0.75));
+    assert!(
+        osc.voices
+            .iter()
+            .all(|voice| approx_eq(voice.phase(), 0.75))
+    );
+}
+
+#[test]
+fn test_reset() {
+    let mut osc = aligned(440.0, Waveform::Saw, 3);
+    osc.next_sample();
+    osc.reset();
+    assert!(approx_eq(osc.phase(), 0.0));
+}
+
+#[test]
+fn test_set_waveform_switches_every_voice() {
+    let mut osc = aligned(440.0, Waveform::Saw, 3);
+    osc.set_waveform(Waveform::Square);
+    let mut reference = aligned(440.0, Waveform::Square, 3);
+
+    for _ in 0..64 {
+        assert!(approx_eq(osc.next_sample(), reference.next_sample()));
+    }
+}
+
+#[test]
+fn test_next_wrap_reports_fraction() {
+    let mut osc = UnisonOsc::new(10.0, 3.0, Waveform::Saw, 3); // inc = 0.3
+    osc.set_phase(0.9);
+
+    let fraction: f32 = osc.next_wrap().unwrap();
+    assert!((fraction - 1.0 / 3.0).abs() < 1e-5);
+}
+
+#[test]
+fn test_fill_buffer() {
+    let mut filled = UnisonOsc::supersaw(44100.0, 440.0);
+    let mut reference = UnisonOsc::supersaw(44100.0, 440.0);
+    for osc in [&mut filled, &mut reference] {
+        osc.set_seed(1);
+        osc.reset();
+    }
+    let mut buffer = [0.0f32; 64];
+
+    filled.fill(&mut buffer);
+
+    for sample in buffer {
+        assert!(approx_eq(sample, reference.next_sample()));
+    }
+}
+
+#[test]
+fn test_iterator() {
+    let osc = UnisonOsc::supersaw(44100.0, 440.0);
+    let samples: Vec<f32> = osc.take(64).collect();
+    assert_eq!(samples.len(), 64);
+}
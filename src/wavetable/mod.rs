@@ -0,0 +1,510 @@
+#[cfg(test)]
+mod tests;
+
+/// Loading wavetables from WAV files.
+///
+/// Reads single-cycle WAV files as well as multi-frame wavetables, with the
+/// frame size taken from the file's metadata. Requires the `wav` feature.
+#[cfg(feature = "wav")]
+pub mod wav;
+
+use alloc::{sync::Arc, vec, vec::Vec};
+use core::f64::consts::{PI, TAU};
+
+use crate::{Float, Oscillator, Waveform, advance_phase, fft::fft, next_wrap, wrap};
+
+/// Samples per mip level.
+const TABLE_SIZE: usize = 2048;
+/// Harmonics kept by the first mip level, leaving every level oversampled
+/// at least twice for the interpolation.
+const MAX_HARMONICS: usize = TABLE_SIZE / 4;
+/// Mip levels, halving the harmonics from [`MAX_HARMONICS`] down to one,
+/// followed by a level holding only the DC offset.
+const LEVELS: usize = MAX_HARMONICS.trailing_zeros() as usize + 2;
+
+/// Samples stored per level, repeating the first one at the end for the
+/// interpolation.
+const STRIDE: usize = TABLE_SIZE + 1;
+/// Samples stored per frame.
+const FRAME_STRIDE: usize = LEVELS * STRIDE;
+
+/// One or more single-cycle waveforms (frames), each band-limited into one
+/// mip level per octave.
+///
+/// Each level keeps half the harmonics of the one before it, from 512 down
+/// to a single sine, and a last level keeps only the DC offset. Building the
+/// levels takes a few FFTs per frame, so tables are built once and shared
+/// between oscillators through an [`Arc`].
+///
+/// # Example
+///
+/// ```
+/// use oscy::{wavetable::Wavetable, Waveform};
+///
+/// let saw = Wavetable::from_waveform(Waveform::Saw);
+/// let custom = Wavetable::from_samples(&[0.0, 1.0, 0.5, -0.5, -1.0]);
+/// assert_eq!(custom.frame_count(), 1);
+/// ```
+pub struct Wavetable {
+    // every level of every frame, one after the other
+    levels: Vec<f32>,
+    frame_count: usize,
+}
+
+impl Wavetable {
+    /// Builds a table from one of the standard waveforms.
+    ///
+    /// The levels are summed from the waveform's Fourier series, so they
+    /// hold no aliasing from sampling the waveform first. [`Waveform::Pulse`]
+    /// uses a pulse width of 0.5 and [`Waveform::Morph`] a shape of 0.0,
+    /// matching the defaults of the other oscillators.
+    pub fn from_waveform(waveform: Waveform) -> Self {
+        let spectrum = match waveform {
+            Waveform::Sine | Waveform::Morph => sine_spectrum(),
+            Waveform::Saw => saw_spectrum(),
+            Waveform::Square | Waveform::Pulse => pulse_spectrum(0.5),
+            Waveform::Triangle => triangle_spectrum(),
+        };
+
+        let mut levels = Vec::with_capacity(FRAME_STRIDE);
+        push_levels(&mut levels, &spectrum);
+        Self {
+            levels,
+            frame_count: 1,
+        }
+    }
+
+    /// Builds a table from one cycle of samples, of any length.
+    ///
+    /// The cycle keeps every harmonic below its own Nyquist, up to 512.
+    /// Its DC offset and levels are kept as they are.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `samples` is empty.
+    pub fn from_samples(samples: &[f32]) -> Self {
+        Self::from_frames(samples, samples.len())
+    }
+
+    /// Builds a multi-frame table from consecutive cycles of `frame_size`
+    /// samples each, such as the frames of a wavetable file.
+    ///
+    /// Every frame is band-limited like [`Wavetable::from_samples`]. Samples
+    /// left over after the last whole frame are ignored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `frame_size` is zero or `samples` holds no whole frame.
+    pub fn from_frames(samples: &[f32], frame_size: usize) -> Self {
+        assert!(
+            frame_size > 0 && samples.len() >= frame_size,
+            "a wavetable needs at least one sample per frame and one frame"
+        );
+
+        let frame_count = samples.len() / frame_size;
+        let mut levels = Vec::with_capacity(frame_count * FRAME_STRIDE);
+        for frame in samples.chunks_exact(frame_size) {
+            push_levels(&mut levels, &spectrum(frame));
+        }
+
+        Self {
+            levels,
+            frame_count,
+        }
+    }
+
+    /// Returns the number of frames in the table.
+    pub fn frame_count(&self) -> usize {
+        self.frame_count
+    }
+
+    /// Returns a table with `steps` frames spectrally interpolated between
+    /// every pair of adjacent frames.
+    ///
+    /// Crossfading two frames whose harmonics are out of phase cancels them
+    /// part of the way, which scanning the table hears as a dip or a hollow
+    /// sound halfway between the frames. The new frames instead interpolate
+    /// the magnitude of every harmonic and turn its phase the short way
+    /// round, so the harmonics keep their level. Scanning the result with
+    /// [`WavetableOsc::set_position`] reaches the original frames at the
+    /// same positions as before, and crossfades only between frames that are
+    /// already close.
+    pub fn spectrally_interpolated(&self, steps: usize) -> Self {
+        let frame_count = (self.frame_count - 1) * (steps + 1) + 1;
+        let mut levels = Vec::with_capacity(frame_count * FRAME_STRIDE);
+
+        // the richest level of a frame holds its whole spectrum
+        let spectra: Vec<Vec<(f64, f64)>> = (0..self.frame_count)
+            .map(|frame| spectrum(&self.frame(frame)[..TABLE_SIZE]))
+            .collect();
+
+        for (frame, pair) in spectra.windows(2).enumerate() {
+            levels.extend_from_slice(self.frame(frame));
+            for step in 1..=steps {
+                let t = step as f64 / (steps + 1) as f64;
+                push_levels(&mut levels, &interpolate_spectra(&pair[0], &pair[1], t));
+            }
+        }
+        levels.extend_from_slice(self.frame(self.frame_count - 1));
+
+        Self {
+            levels,
+            frame_count,
+        }
+    }
+
+    /// Returns every level of `frame`.
+    fn frame(&self, frame: usize) -> &[f32] {
+        &self.levels[frame * FRAME_STRIDE..(frame + 1) * FRAME_STRIDE]
+    }
+
+    /// Reads `level` of `frame` at `phase` with linear interpolation.
+    fn read<T: Float>(&self, frame: usize, level: usize, phase: T) -> T {
+        let start = frame * FRAME_STRIDE + level * STRIDE;
+        let table = &self.levels[start..start + STRIDE];
+        let position = phase * T::from_usize(TABLE_SIZE);
+        // a phase just below 1.0 can round up to the table size
+        let index = position.to_usize().min(TABLE_SIZE - 1);
+        let fraction = position - T::from_usize(index);
+        let (a, b) = (T::from_f32(table[index]), T::from_f32(table[index + 1]));
+        a + fraction * (b - a)
+    }
+}
+
+/// Builds the mip levels of one frame from the complex amplitudes of its
+/// harmonics, starting at DC, and appends them to `levels`.
+fn push_levels(levels: &mut Vec<f32>, spectrum: &[(f64, f64)]) {
+    let mut re = vec![0.0; TABLE_SIZE];
+    let mut im = vec![0.0; TABLE_SIZE];
+
+    for level in 0..LEVELS {
+        // down to none for the last level
+        let harmonics = MAX_HARMONICS >> level;
+
+        re.fill(0.0);
+        im.fill(0.0);
+        for (k, &(a, b)) in spectrum.iter().enumerate().take(harmonics + 1) {
+            let scale = TABLE_SIZE as f64;
+            re[k] = a * scale;
+            im[k] = b * scale;
+            if k > 0 {
+                // mirror the harmonic so the table comes out real
+                re[TABLE_SIZE - k] = a * scale;
+                im[TABLE_SIZE - k] = -b * scale;
+            }
+        }
+        fft(&mut re, &mut im, true);
+
+        levels.extend(re.iter().map(|&x| x as f32));
+        levels.push(re[0] as f32);
+    }
+}
+
+/// Interpolates between two spectra by `t`, the magnitude of every harmonic
+/// linearly and its phase along the shorter way round.
+fn interpolate_spectra(from: &[(f64, f64)], to: &[(f64, f64)], t: f64) -> Vec<(f64, f64)> {
+    from.iter()
+        .zip(to)
+        .enumerate()
+        .map(|(k, (&(a_re, a_im), &(b_re, b_im)))| {
+            if k == 0 {
+                // DC is real and may change sign, so it's blended directly
+                return (a_re + t * (b_re - a_re), 0.0);
+            }
+
+            let (a_mag, b_mag) = (a_re.hypot(a_im), b_re.hypot(b_im));
+            let (a_arg, b_arg) = (a_im.atan2(a_re), b_im.atan2(b_re));
+            // a missing harmonic takes the phase of the other one
+            let (a_arg, b_arg) = match (a_mag > 0.0, b_mag > 0.0) {
+                (true, false) => (a_arg, a_arg),
+                (false, true) => (b_arg, b_arg),
+                _ => (a_arg, b_arg),
+            };
+
+            let turn = (b_arg - a_arg + PI).rem_euclid(TAU) - PI;
+            let magnitude = a_mag + t * (b_mag - a_mag);
+            let (sin, cos) = (a_arg + t * turn).sin_cos();
+            (magnitude * cos, magnitude * sin)
+        })
+        .collect()
+}
+
+/// An oscillator playing a [`Wavetable`].
+///
+/// The oscillator reads the mip level whose harmonics all stay below Nyquist
+/// at the current frequency, crossfaded with the duller level after it. The
+/// richer level fades in as the frequency drops towards the point where the
+/// next level fits, so glides change the timbre smoothly and never alias.
+/// The cost is that harmonics in the top octave below Nyquist are
+/// attenuated. Levels are read with linear interpolation.
+///
+/// Tables with several frames are scanned with
+/// [`WavetableOsc::set_position`], which crossfades between the two frames
+/// either side of the position. Both are read from the same mip levels, so
+/// the band-limiting holds at every position. For smoother scans between
+/// frames that differ a lot, see [`Wavetable::spectrally_interpolated`].
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use oscy::{wavetable::{Wavetable, WavetableOsc}, Oscillator, Waveform};
+///
+/// let table = Arc::new(Wavetable::from_waveform(Waveform::Saw));
+/// let mut osc = WavetableOsc::new(44100.0, 440.0, table);
+/// let sample: f32 = osc.next_sample();
+/// assert!(sample.abs() < 1.2);
+/// ```
+pub struct WavetableOsc<T: Float = f32> {
+    phase: T,
+    phase_increment: T,
+    sample_rate: T,
+    table: Arc<Wavetable>,
+    // richer of the two mip levels read, and its share of the mix
+    level: usize,
+    blend: T,
+    position: T,
+    // first of the two frames read, and the second one's share of the mix
+    frame: usize,
+    frame_blend: T,
+}
+
+impl<T: Float> WavetableOsc<T> {
+    /// Creates a new wavetable oscillator playing `table`.
+    pub fn new(sample_rate: T, frequency: T, table: Arc<Wavetable>) -> Self {
+        let mut osc = Self {
+            phase: T::ZERO,
+            phase_increment: frequency / sample_rate,
+            sample_rate,
+            table,
+            level: 0,
+            blend: T::ONE,
+            position: T::ZERO,
+            frame: 0,
+            frame_blend: T::ZERO,
+        };
+        osc.select_levels();
+        osc
+    }
+
+    /// Returns the table being played.
+    pub fn table(&self) -> &Arc<Wavetable> {
+        &self.table
+    }
+
+    /// Replaces the table being played, keeping the phase and position.
+    pub fn set_table(&mut self, table: Arc<Wavetable>) {
+        self.table = table;
+        self.select_frames();
+    }
+
+    /// Sets the position in a multi-frame table, from 0.0 at the first
+    /// frame to 1.0 at the last one. Clamped to that range.
+    ///
+    /// Positions between frames crossfade the two frames either side.
+    /// Tables with a single frame ignore the position.
+    pub fn set_position(&mut self, position: T) {
+        self.position = position.clamp(T::ZERO, T::ONE);
+        self.select_frames();
+    }
+
+    /// Returns the position in the table.
+    pub fn position(&self) -> T {
+        self.position
+    }
+
+    /// Fills `buffer` while scanning the table, with one position per sample.
+    ///
+    /// Stops at the shorter of `buffer` and `positions`. The oscillator is
+    /// left at the last position.
+    pub fn fill_with_position(&mut self, buffer: &mut [T], positions: &[T]) {
+        for (sample, &position) in buffer.iter_mut().zip(positions) {
+            self.set_position(position);
+            *sample = self.next_sample();
+        }
+    }
+
+    /// Picks the frames for the current position.
+    fn select_frames(&mut self) {
+        let last = self.table.frame_count - 1;
+        let scaled = self.position * T::from_usize(last);
+        // the last frame is reached at full blend from the one before it
+        let frame = scaled.to_usize().min(last.saturating_sub(1));
+
+        self.frame = frame;
+        self.frame_blend = scaled - T::from_usize(frame);
+    }
+
+    /// Picks the mip levels for the current phase increment.
+    fn select_levels(&mut self) {
+        // highest harmonic that fits below Nyquist
+        let fits = T::HALF / self.phase_increment.abs();
+
+        let max_harmonics = T::from_usize(MAX_HARMONICS);
+        if fits >= T::TWO * max_harmonics {
+            self.level = 0;
+            self.blend = T::ONE;
+            return;
+        }
+
+        // richest level that fits, fading in over the duller one as its
+        // harmonics go from fitting once to fitting twice
+        let level = (max_harmonics / fits).log2().ceil().max(T::ZERO).to_usize();
+        let level = level.min(LEVELS - 1);
+        let harmonics = T::from_usize(MAX_HARMONICS >> level);
+
+        self.level = level;
+        self.blend = if level + 1 < LEVELS {
+            (fits / harmonics - T::ONE).clamp(T::ZERO, T::ONE)
+        } else {
+            T::ONE
+        };
+    }
+
+    /// Reads `frame` from the mip levels for the current frequency.
+    fn read_frame(&self, frame: usize) -> T {
+        let richer = self.table.read(frame, self.level, self.phase);
+        if self.blend >= T::ONE {
+            return richer;
+        }
+
+        let duller = self.table.read(frame, self.level + 1, self.phase);
+        duller + self.blend * (richer - duller)
+    }
+
+    fn read(&self) -> T {
+        let first = self.read_frame(self.frame);
+        if self.frame_blend <= T::ZERO {
+            return first;
+        }
+
+        let second = self.read_frame(self.frame + 1);
+        first + self.frame_blend * (second - first)
+    }
+
+    /// Moves the phase one step forward.
+    fn advance(&mut self) {
+        self.phase = advance_phase(self.phase, self.phase_increment);
+    }
+}
+
+impl<T: Float> Oscillator<T> for WavetableOsc<T> {
+    fn set_frequency(&mut self, hz: T) {
+        self.phase_increment = hz / self.sample_rate;
+        self.select_levels();
+    }
+
+    fn set_phase(&mut self, phase: T) {
+        self.phase = wrap(phase);
+    }
+
+    fn phase(&self) -> T {
+        self.phase
+    }
+
+    fn reset(&mut self) {
+        self.phase = T::ZERO;
+    }
+
+    fn next_wrap(&self) -> Option<T> {
+        next_wrap(self.phase, self.phase_increment)
+    }
+
+    fn next_sample(&mut self) -> T {
+        self.advance();
+        self.read()
+    }
+}
+
+impl<T: Float> Iterator for WavetableOsc<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        Some(self.next_sample())
+    }
+}
+
+/// Computes the complex amplitudes of the harmonics of one cycle, from DC
+/// up to the last harmonic below the cycle's Nyquist.
+fn spectrum(samples: &[f32]) -> Vec<(f64, f64)> {
+    let n = samples.len();
+    let harmonics = ((n - 1) / 2).min(MAX_HARMONICS);
+
+    if n.is_power_of_two() {
+        // the usual frame sizes take the fast path
+        let mut re: Vec<f64> = samples.iter().map(|&x| x as f64).collect();
+        let mut im = vec![0.0; n];
+        fft(&mut re, &mut im, false);
+
+        return (0..=harmonics)
+            .map(|k| (re[k] / n as f64, im[k] / n as f64))
+            .collect();
+    }
+
+    // every product of a harmonic and a sample index lands on one of these
+    let twiddles: Vec<(f64, f64)> = (0..n)
+        .map(|i| (TAU * i as f64 / n as f64).sin_cos())
+        .map(|(sin, cos)| (cos, -sin))
+        .collect();
+
+    (0..=harmonics)
+        .map(|k| {
+            let (mut re, mut im) = (0.0, 0.0);
+            for (i, &sample) in samples.iter().enumerate() {
+                let (cos, sin) = twiddles[k * i % n];
+                re += sample as f64 * cos;
+                im += sample as f64 * sin;
+            }
+            (re / n as f64, im / n as f64)
+        })
+        .collect()
+}
+
+/// Returns the spectrum of a series with sine amplitudes `b(k)` for every
+/// harmonic, on top of no DC.
+fn sine_series(b: impl Fn(f64) -> f64) -> Vec<(f64, f64)> {
+    (0..=MAX_HARMONICS)
+        .map(|k| match k {
+            0 => (0.0, 0.0),
+            k => (0.0, -0.5 * b(k as f64)),
+        })
+        .collect()
+}
+
+fn sine_spectrum() -> Vec<(f64, f64)> {
+    sine_series(|k| if k == 1.0 { 1.0 } else { 0.0 })
+}
+
+fn saw_spectrum() -> Vec<(f64, f64)> {
+    // 2·phase - 1
+    sine_series(|k| -2.0 / (PI * k))
+}
+
+fn pulse_spectrum(width: f64) -> Vec<(f64, f64)> {
+    // high below the width, low above it
+    (0..=MAX_HARMONICS)
+        .map(|k| {
+            if k == 0 {
+                return (2.0 * width - 1.0, 0.0);
+            }
+            let k = k as f64;
+            let (sin, cos) = (TAU * k * width).sin_cos();
+            let scale = 1.0 / (PI * k);
+            (sin * scale, -(1.0 - cos) * scale)
+        })
+        .collect()
+}
+
+fn triangle_spectrum() -> Vec<(f64, f64)> {
+    // -1 at phase 0 and 1 at phase 0.5, with odd cosine harmonics
+    (0..=MAX_HARMONICS)
+        .map(|k| {
+            if k % 2 == 0 {
+                return (0.0, 0.0);
+            }
+            let k = k as f64;
+            (-4.0 / (PI * PI * k * k), 0.0)
+        })
+        .collect()
+}
@@ -0,0 +1,382 @@
+use std::f32::consts::TAU;
+use std::sync::Arc;
+
+use super::{Wavetable, WavetableOsc};
+use crate::{
+    Oscillator, Waveform,
+    naive::NaiveOsc,
+    poly_blep::PolyBlepOsc,
+    test_utils::{ANALYSIS_LEN, aliasing_ratio, analysis_frequency},
+};
+
+const EPSILON: f32 = 1e-5;
+
+fn approx_eq(a: f32, b: f32) -> bool {
+    (a - b).abs() < EPSILON
+}
+
+fn osc(frequency: f32, waveform: Waveform) -> WavetableOsc {
+    WavetableOsc::new(
+        44100.0,
+        frequency,
+        Arc::new(Wavetable::from_waveform(waveform)),
+    )
+}
+
+// table tests
+
+#[test]
+fn test_sine_table_matches_naive() {
+    let mut osc = osc(441.0, Waveform::Sine);
+    let mut naive = NaiveOsc::new(44100.0, 441.0, Waveform::Sine);
+
+    for _ in 0..1000 {
+        assert!((osc.next_sample() - naive.next_sample()).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn test_saw_table_follows_naive_mid_ramp() {
+    let mut osc = osc(44.1, Waveform::Saw); // 1000 samples per cycle
+    let mut naive = NaiveOsc::new(44100.0, 44.1, Waveform::Saw);
+
+    for i in 0..2000 {
+        let (sample, expected) = (osc.next_sample(), naive.next_sample());
+        if (100..900).contains(&(i % 1000)) {
+            assert!((sample - expected).abs() < 0.01);
+        }
+    }
+}
+
+#[test]
+fn test_from_samples_keeps_the_cycle() {
+    // a sine with a DC offset, in a cycle that's not a power of two
+    let cycle: Vec<f32> = (0..100)
+        .map(|i| 0.25 + 0.5 * (i as f32 / 100.0 * TAU).sin())
+        .collect();
+    let mut osc = WavetableOsc::new(44100.0, 441.0, Arc::new(Wavetable::from_samples(&cycle)));
+
+    for i in 1..=200 {
+        let expected = cycle[i % 100];
+        assert!((osc.next_sample() - expected).abs() < 1e-4);
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_from_samples_rejects_empty_cycle() {
+    Wavetable::from_samples(&[]);
+}
+
+// frame tests
+
+/// Two frames of 64 samples, a sine followed by a cosine.
+fn sine_cosine() -> Wavetable {
+    let frames: Vec<f32> = (0..128)
+        .map(|i| {
+            let phase = i as f32 / 64.0 * TAU;
+            if i < 64 { phase.sin() } else { phase.cos() }
+        })
+        .collect();
+    Wavetable::from_frames(&frames, 64)
+}
+
+/// Plays one cycle of 64 samples starting at phase zero.
+fn play_cycle(osc: &mut WavetableOsc) -> Vec<f32> {
+    osc.set_phase(-1.0 / 64.0);
+    (0..64).map(|_| osc.next_sample()).collect()
+}
+
+#[test]
+fn test_position_selects_frames() {
+    let mut osc = WavetableOsc::new(64.0 * 441.0, 441.0, Arc::new(sine_cosine()));
+
+    for (position, frame) in [(0.0, f32::sin as fn(f32) -> f32), (1.0, f32::cos)] {
+        osc.set_position(position);
+        for (i, sample) in play_cycle(&mut osc).into_iter().enumerate() {
+            assert!((sample - frame(i as f32 / 64.0 * TAU)).abs() < 1e-4);
+        }
+    }
+}
+
+#[test]
+fn test_position_crossfades_adjacent_frames() {
+    // three frames, the middle one silent
+    let frames: Vec<f32> = (0..192)
+        .map(|i| match i / 64 {
+            1 => 0.0,
+            _ => (i as f32 / 64.0 * TAU).sin(),
+        })
+        .collect();
+    let mut osc = WavetableOsc::new(
+        64.0 * 441.0,
+        441.0,
+        Arc::new(Wavetable::from_frames(&frames, 64)),
+    );
+
+    // a quarter of the way through is halfway between the first two frames
+    osc.set_position(0.25);
+    for (i, sample) in play_cycle(&mut osc).into_iter().enumerate() {
+        let expected = 0.5 * (i as f32 / 64.0 * TAU).sin();
+        assert!((sample - expected).abs() < 1e-4);
+    }
+
+    osc.set_position(0.5);
+    for sample in play_cycle(&mut osc) {
+        assert!(sample.abs() < 1e-4);
+    }
+}
+
+#[test]
+fn test_position_is_clamped() {
+    let mut osc = WavetableOsc::new(44100.0, 441.0, Arc::new(sine_cosine()));
+    osc.set_position(-1.0);
+    assert!(approx_eq(osc.position(), 0.0));
+    osc.set_position(2.0);
+    assert!(approx_eq(osc.position(), 1.0));
+}
+
+#[test]
+fn test_single_frame_ignores_position() {
+    let mut scanned = osc(441.0, Waveform::Saw);
+    let mut reference = osc(441.0, Waveform::Saw);
+    scanned.set_position(0.7);
+
+    for _ in 0..100 {
+        assert!(approx_eq(scanned.next_sample(), reference.next_sample()));
+    }
+}
+
+#[test]
+fn test_band_limited_at_every_position() {
+    let saw: Vec<f32> = NaiveOsc::new(2048.0, 1.0, Waveform::Saw)
+        .take(2048)
+        .collect();
+    let square: Vec<f32> = NaiveOsc::new(2048.0, 1.0, Waveform::Square)
+        .take(2048)
+        .collect();
+    let table = Arc::new(Wavetable::from_frames(&[saw, square].concat(), 2048));
+
+    let cycles = 233;
+    let frequency = analysis_frequency(44100.0, cycles);
+    let poly: Vec<f32> = PolyBlepOsc::new(44100.0, frequency, Waveform::Saw)
+        .take(ANALYSIS_LEN)
+        .collect();
+    let poly = aliasing_ratio(&poly, cycles);
+
+    for position in [0.0, 0.3, 0.5, 1.0] {
+        let mut osc = WavetableOsc::new(44100.0, frequency, table.clone());
+        osc.set_position(position);
+        let samples: Vec<f32> = osc.take(ANALYSIS_LEN).collect();
+
+        let ratio = aliasing_ratio(&samples, cycles);
+        assert!(ratio < poly * 0.01, "{position}: {ratio} vs {poly}");
+    }
+}
+
+#[test]
+fn test_fill_with_position_matches_set_position() {
+    let table = Arc::new(sine_cosine());
+    let mut filled = WavetableOsc::new(44100.0, 441.0, table.clone());
+    let mut reference = WavetableOsc::new(44100.0, 441.0, table);
+    let positions: Vec<f32> = (0..64).map(|i| i as f32 / 63.0).collect();
+    let mut buffer = [0.0f32; 64];
+
+    filled.fill_with_position(&mut buffer, &positions);
+
+    for (sample, &position) in buffer.iter().zip(&positions) {
+        reference.set_position(position);
+        assert!(approx_eq(*sample, reference.next_sample()));
+    }
+    assert!(approx_eq(filled.position(), 1.0));
+}
+
+#[test]
+fn test_spectral_interpolation_keeps_level() {
+    let table = sine_cosine();
+    let interpolated = Arc::new(table.spectrally_interpolated(3));
+    assert_eq!(interpolated.frame_count(), 5);
+
+    let mut crossfaded = WavetableOsc::new(64.0 * 441.0, 441.0, Arc::new(table));
+    let mut spectral = WavetableOsc::new(64.0 * 441.0, 441.0, interpolated);
+    crossfaded.set_position(0.5);
+    spectral.set_position(0.5);
+
+    // the crossfade of a sine and a cosine dips to 1/sqrt(2) halfway, the
+    // spectral frame is a sine turned an eighth of a cycle
+    let peak = |samples: Vec<f32>| samples.iter().fold(0.0f32, |a, s| a.max(s.abs()));
+    assert!((peak(play_cycle(&mut crossfaded)) - 0.5f32.sqrt()).abs() < 1e-3);
+    for (i, sample) in play_cycle(&mut spectral).into_iter().enumerate() {
+        let expected = ((i as f32 / 64.0 + 0.125) * TAU).sin();
+        assert!((sample - expected).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn test_spectral_interpolation_keeps_original_frames() {
+    let table = Arc::new(sine_cosine());
+    let interpolated = Arc::new(table.spectrally_interpolated(2));
+    let mut original = WavetableOsc::new(44100.0, 441.0, table);
+    let mut scanned = WavetableOsc::new(44100.0, 441.0, interpolated);
+
+    for position in [0.0, 1.0] {
+        original.set_position(position);
+        scanned.set_position(position);
+        for _ in 0..100 {
+            assert!(approx_eq(original.next_sample(), scanned.next_sample()));
+        }
+    }
+}
+
+// oscillator tests
+
+#[test]
+fn test_aliases_less_than_poly_blep() {
+    for cycles in [61, 233] {
+        let frequency = analysis_frequency(44100.0, cycles);
+
+        for waveform in [Waveform::Saw, Waveform::Square, Waveform::Triangle] {
+            let table: Vec<f32> = osc(frequency, waveform).take(ANALYSIS_LEN).collect();
+            let poly: Vec<f32> = PolyBlepOsc::new(44100.0, frequency, waveform)
+                .take(ANALYSIS_LEN)
+                .collect();
+
+            let table = aliasing_ratio(&table, cycles);
+            let poly = aliasing_ratio(&poly, cycles);
+            assert!(
+                table < poly * 0.01,
+                "{waveform:?} at {cycles}: {table} vs {poly}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_glide_changes_timbre_smoothly() {
+    // the same phase read just either side of a level boundary
+    let boundary = 44100.0 / 64.0; // 32 harmonics fit
+    let mut below = osc(boundary * 0.999, Waveform::Saw);
+    let mut above = osc(boundary * 1.001, Waveform::Saw);
+
+    for i in 0..64 {
+        let phase = i as f32 / 64.0;
+        below.set_phase(phase);
+        above.set_phase(phase);
+        assert!((below.next_sample() - above.next_sample()).abs() < 0.02);
+    }
+}
+
+#[test]
+fn test_above_nyquist_only_dc_is_left() {
+    let cycle = [1.0, 0.0, 0.5, 0.5];
+    let mut osc = WavetableOsc::new(44100.0, 30000.0, Arc::new(Wavetable::from_samples(&cycle)));
+
+    for _ in 0..100 {
+        assert!(approx_eq(osc.next_sample(), 0.5));
+    }
+}
+
+#[test]
+fn test_negative_frequency_runs_backwards() {
+    let mut forwards = osc(441.0, Waveform::Saw);
+    let mut backwards = osc(-441.0, Waveform::Saw);
+    forwards.set_phase(0.5);
+    backwards.set_phase(0.5);
+
+    // the saw is odd around the middle of the cycle
+    for _ in 0..100 {
+        assert!((forwards.next_sample() + backwards.next_sample()).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn test_set_table_keeps_phase() {
+    let mut osc = osc(441.0, Waveform::Saw);
+    osc.set_phase(0.25);
+    osc.set_table(Arc::new(Wavetable::from_waveform(Waveform::Sine)));
+
+    assert!(approx_eq(osc.phase(), 0.25));
+    assert!((osc.next_sample() - (0.26 * TAU).sin()).abs() < 1e-4);
+}
+
+#[test]
+fn test_tables_are_shared() {
+    let table = Arc::new(Wavetable::from_waveform(Waveform::Square));
+    let a = WavetableOsc::new(44100.0, 440.0, table.clone());
+    let b = WavetableOsc::new(44100.0, 660.0, table.clone());
+
+    assert!(Arc::ptr_eq(a.table(), b.table()));
+}
+
+#[test]
+fn test_tiny_negative_phase_stays_below_one() {
+    // a step from 1e-9 back by 2e-9 lands at -1e-9, which rounds to 1.0
+    let table = Arc::new(Wavetable::from_waveform(Waveform::Saw));
+    let mut osc: WavetableOsc = WavetableOsc::new(1.0, -2e-9, table);
+    osc.set_phase(1e-9);
+    osc.next_sample();
+    assert!(osc.phase() < 1.0);
+
+    osc.set_phase(-1e-9);
+    assert!(osc.phase() < 1.0);
+}
+
+// sample type tests
+
+#[test]
+fn test_f64_matches_f32() {
+    let table = Arc::new(Wavetable::from_waveform(Waveform::Triangle));
+    let mut single = WavetableOsc::<f32>::new(44100.0, 1234.0, table.clone());
+    let mut double = WavetableOsc::<f64>::new(44100.0, 1234.0, table);
+
+    for _ in 0..1000 {
+        assert!((single.next_sample() as f64 - double.next_sample()).abs() < 1e-3);
+    }
+}
+
+// trait impl tests
+
+#[test]
+fn test_set_phase() {
+    let mut osc = osc(1.0, Waveform::Saw);
+    osc.set_phase(-0.25);
+    assert!(approx_eq(osc.phase(), 0.75));
+}
+
+#[test]
+fn test_reset() {
+    let mut osc = osc(440.0, Waveform::Saw);
+    osc.next_sample();
+    osc.reset();
+    assert!(approx_eq(osc.phase(), 0.0));
+}
+
+#[test]
+fn test_next_wrap_reports_fraction() {
+    let table = Arc::new(Wavetable::from_waveform(Waveform::Saw));
+    let mut osc = WavetableOsc::new(10.0, 3.0, table); // inc = 0.3
+    osc.set_phase(0.9);
+
+    let fraction: f32 = osc.next_wrap().unwrap();
+    assert!((fraction - 1.0 / 3.0).abs() < 1e-5);
+}
+
+#[test]
+fn test_fill_buffer() {
+    let mut filled = osc(440.0, Waveform::Triangle);
+    let mut reference = osc(440.0, Waveform::Triangle);
+    let mut buffer = [0.0f32; 64];
+
+    filled.fill(&mut buffer);
+
+    for sample in buffer {
+        assert!(approx_eq(sample, reference.next_sample()));
+    }
+}
+
+#[test]
+fn test_iterator() {
+    let osc = osc(440.0, Waveform::Saw);
+    let samples: Vec<f32> = osc.take(64).collect();
+    assert_eq!(samples.len(), 64);
+}
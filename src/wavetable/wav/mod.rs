@@ -0,0 +1,268 @@
+#[cfg(test)]
+mod tests;
+
+use alloc::vec::Vec;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::{fs, io, path::Path};
+
+use super::Wavetable;
+
+/// Frame size assumed for tables without metadata whose length is a
+/// multiple of it, the size most wavetable editors export.
+const DEFAULT_FRAME_SIZE: usize = 2048;
+
+const FORMAT_PCM: u16 = 1;
+const FORMAT_FLOAT: u16 = 3;
+const FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+
+/// An error while loading a wavetable from a WAV file.
+#[derive(Debug)]
+pub enum WavError {
+    /// The file couldn't be read.
+    #[cfg(feature = "std")]
+    Io(io::Error),
+    /// The data doesn't start with a RIFF `WAVE` header.
+    NotWav,
+    /// A chunk runs past the end of the data, or a required chunk is
+    /// missing.
+    Malformed(&'static str),
+    /// The sample format isn't 8, 16, 24 or 32-bit PCM, or 32 or 64-bit
+    /// float.
+    Unsupported {
+        /// Format tag from the `fmt ` chunk.
+        format: u16,
+        /// Bits per sample.
+        bits: u16,
+    },
+    /// The file holds no samples.
+    Empty,
+    /// The samples don't divide into frames of the given size.
+    FrameSize {
+        /// Samples per channel in the file.
+        samples: usize,
+        /// Frame size requested or read from the metadata.
+        frame_size: usize,
+    },
+}
+
+impl fmt::Display for WavError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            #[cfg(feature = "std")]
+            WavError::Io(err) => write!(f, "failed to read WAV file: {err}"),
+            WavError::NotWav => write!(f, "not a RIFF WAVE file"),
+            WavError::Malformed(reason) => write!(f, "malformed WAV file: {reason}"),
+            WavError::Unsupported { format, bits } => {
+                write!(f, "unsupported WAV format {format} with {bits} bits")
+            }
+            WavError::Empty => write!(f, "WAV file holds no samples"),
+            WavError::FrameSize {
+                samples,
+                frame_size,
+            } => write!(
+                f,
+                "{samples} samples don't divide into frames of {frame_size}"
+            ),
+        }
+    }
+}
+
+impl core::error::Error for WavError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            #[cfg(feature = "std")]
+            WavError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<io::Error> for WavError {
+    fn from(err: io::Error) -> Self {
+        WavError::Io(err)
+    }
+}
+
+impl Wavetable {
+    /// Loads a wavetable from a WAV file, see [`Wavetable::from_wav`].
+    ///
+    /// Requires the `std` feature. Without it, read the file some other
+    /// way and pass its bytes to [`Wavetable::from_wav`].
+    #[cfg(feature = "std")]
+    pub fn open_wav(path: impl AsRef<Path>) -> Result<Self, WavError> {
+        Self::from_wav(&fs::read(path)?)
+    }
+
+    /// Loads a wavetable from the bytes of a WAV file.
+    ///
+    /// The frame size is read from the `clm ` chunk that wavetable editors
+    /// such as Serum write (`<!>2048 ...`). Without it, files that are a
+    /// whole number of 2048-sample frames longer than one frame load as
+    /// multi-frame tables, and anything else as a single cycle. Channels
+    /// are mixed down to mono. Supports 8, 16, 24 and 32-bit PCM and 32 and
+    /// 64-bit float samples.
+    pub fn from_wav(bytes: &[u8]) -> Result<Self, WavError> {
+        let wav = parse(bytes)?;
+
+        let frame_size = match wav.frame_size {
+            Some(frame_size) => frame_size,
+            None if wav.samples.len() > DEFAULT_FRAME_SIZE
+                && wav.samples.len() % DEFAULT_FRAME_SIZE == 0 =>
+            {
+                DEFAULT_FRAME_SIZE
+            }
+            None => wav.samples.len(),
+        };
+
+        frames(&wav.samples, frame_size)
+    }
+
+    /// Loads a wavetable from the bytes of a WAV file, split into frames of
+    /// `frame_size` samples regardless of its metadata.
+    pub fn from_wav_frames(bytes: &[u8], frame_size: usize) -> Result<Self, WavError> {
+        let wav = parse(bytes)?;
+        frames(&wav.samples, frame_size)
+    }
+}
+
+/// Splits mono samples into a table, checking they hold whole frames.
+fn frames(samples: &[f32], frame_size: usize) -> Result<Wavetable, WavError> {
+    if frame_size == 0 || samples.len() % frame_size != 0 {
+        return Err(WavError::FrameSize {
+            samples: samples.len(),
+            frame_size,
+        });
+    }
+
+    Ok(Wavetable::from_frames(samples, frame_size))
+}
+
+/// Mono samples and the frame size from the metadata, if any.
+struct Wav {
+    samples: Vec<f32>,
+    frame_size: Option<usize>,
+}
+
+/// Layout of the samples, from the `fmt ` chunk.
+struct Format {
+    format: u16,
+    channels: usize,
+    bits: u16,
+}
+
+fn parse(bytes: &[u8]) -> Result<Wav, WavError> {
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err(WavError::NotWav);
+    }
+
+    let mut format = None;
+    let mut data = None;
+    let mut frame_size = None;
+
+    let mut rest = &bytes[12..];
+    while rest.len() >= 8 {
+        let id = &rest[0..4];
+        let size = u32::from_le_bytes([rest[4], rest[5], rest[6], rest[7]]) as usize;
+        let past_end = "chunk runs past the end of the file";
+        // sizes near u32::MAX overflow usize on 32-bit targets
+        let end = size.checked_add(8).ok_or(WavError::Malformed(past_end))?;
+        let body = rest.get(8..end).ok_or(WavError::Malformed(past_end))?;
+
+        match id {
+            b"fmt " => format = Some(parse_format(body)?),
+            b"data" => data = Some(body),
+            b"clm " => frame_size = parse_frame_size(body),
+            _ => {}
+        }
+
+        // chunks are padded to an even size
+        let next = end
+            .checked_add(size & 1)
+            .ok_or(WavError::Malformed(past_end))?
+            .min(rest.len());
+        rest = &rest[next..];
+    }
+
+    let format = format.ok_or(WavError::Malformed("missing fmt chunk"))?;
+    let data = data.ok_or(WavError::Malformed("missing data chunk"))?;
+    let samples = decode(&format, data)?;
+
+    if samples.is_empty() {
+        return Err(WavError::Empty);
+    }
+
+    Ok(Wav {
+        samples,
+        frame_size,
+    })
+}
+
+fn parse_format(body: &[u8]) -> Result<Format, WavError> {
+    if body.len() < 16 {
+        return Err(WavError::Malformed("fmt chunk too short"));
+    }
+
+    let mut format = u16::from_le_bytes([body[0], body[1]]);
+    let channels = u16::from_le_bytes([body[2], body[3]]) as usize;
+    let bits = u16::from_le_bytes([body[14], body[15]]);
+
+    if format == FORMAT_EXTENSIBLE {
+        // the actual format opens the sub-format GUID
+        let guid = body
+            .get(24..26)
+            .ok_or(WavError::Malformed("fmt chunk too short"))?;
+        format = u16::from_le_bytes([guid[0], guid[1]]);
+    }
+
+    if channels == 0 {
+        return Err(WavError::Malformed("no channels"));
+    }
+
+    Ok(Format {
+        format,
+        channels,
+        bits,
+    })
+}
+
+/// Reads the frame size from a `clm ` chunk, which starts with `<!>` and
+/// the frame size in decimal.
+fn parse_frame_size(body: &[u8]) -> Option<usize> {
+    let digits = body.strip_prefix(b"<!>")?;
+    let end = digits
+        .iter()
+        .position(|byte| !byte.is_ascii_digit())
+        .unwrap_or(digits.len());
+
+    core::str::from_utf8(&digits[..end])
+        .ok()?
+        .parse()
+        .ok()
+        .filter(|&size| size > 0)
+}
+
+/// Decodes interleaved samples into mono, averaging the channels.
+fn decode(format: &Format, data: &[u8]) -> Result<Vec<f32>, WavError> {
+    let read: fn(&[u8]) -> f32 = match (format.format, format.bits) {
+        (FORMAT_PCM, 8) => |b| (b[0] as f32 - 128.0) / 128.0,
+        (FORMAT_PCM, 16) => |b| i16::from_le_bytes([b[0], b[1]]) as f32 / 32768.0,
+        (FORMAT_PCM, 24) => |b| i32::from_le_bytes([0, b[0], b[1], b[2]]) as f32 / 2147483648.0,
+        (FORMAT_PCM, 32) => |b| i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f32 / 2147483648.0,
+        (FORMAT_FLOAT, 32) => |b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+        (FORMAT_FLOAT, 64) => {
+            |b| f64::from_le_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]) as f32
+        }
+        (format, bits) => return Err(WavError::Unsupported { format, bits }),
+    };
+
+    let width = format.bits as usize / 8;
+    let block = width * format.channels;
+    let scale = 1.0 / format.channels as f32;
+
+    Ok(data
+        .chunks_exact(block)
+        .map(|frame| frame.chunks_exact(width).map(read).sum::<f32>() * scale)
+        .collect())
+}
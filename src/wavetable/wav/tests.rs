@@ -0,0 +1,229 @@
+use std::f32::consts::TAU;
+use std::sync::Arc;
+
+use super::WavError;
+use crate::{
+    Oscillator,
+    wavetable::{Wavetable, WavetableOsc},
+};
+
+/// Builds a WAV file from `fmt ` fields, sample bytes and extra chunks.
+fn wav(
+    format: u16,
+    channels: u16,
+    bits: u16,
+    data: &[u8],
+    chunks: &[(&[u8; 4], &[u8])],
+) -> Vec<u8> {
+    let mut fmt = Vec::new();
+    fmt.extend(format.to_le_bytes());
+    fmt.extend(channels.to_le_bytes());
+    fmt.extend(44100u32.to_le_bytes());
+    fmt.extend((44100 * (bits / 8 * channels) as u32).to_le_bytes());
+    fmt.extend((bits / 8 * channels).to_le_bytes());
+    fmt.extend(bits.to_le_bytes());
+
+    let mut body = b"WAVE".to_vec();
+    for (id, chunk) in [(b"fmt ", fmt.as_slice())]
+        .iter()
+        .chain(chunks)
+        .chain(&[(b"data", data)])
+    {
+        body.extend(*id);
+        body.extend((chunk.len() as u32).to_le_bytes());
+        body.extend(*chunk);
+        if chunk.len() % 2 == 1 {
+            body.push(0);
+        }
+    }
+
+    let mut file = b"RIFF".to_vec();
+    file.extend((body.len() as u32).to_le_bytes());
+    file.extend(body);
+    file
+}
+
+fn sine(len: usize) -> Vec<f32> {
+    (0..len)
+        .map(|i| (i as f32 / len as f32 * TAU).sin())
+        .collect()
+}
+
+fn pcm16(samples: &[f32]) -> Vec<u8> {
+    samples
+        .iter()
+        .flat_map(|&s| ((s * 32767.0) as i16).to_le_bytes())
+        .collect()
+}
+
+fn float32(samples: &[f32]) -> Vec<u8> {
+    samples.iter().flat_map(|&s| s.to_le_bytes()).collect()
+}
+
+/// Plays the first cycle of a table with one sample per table entry of a
+/// 256-sample cycle.
+fn play(table: Wavetable) -> Vec<f32> {
+    let mut osc = WavetableOsc::new(25600.0, 100.0, Arc::new(table));
+    osc.set_phase(-1.0 / 256.0);
+    osc.take(256).collect()
+}
+
+#[test]
+fn test_loads_single_cycle_pcm16() {
+    let cycle = sine(256);
+    let table = Wavetable::from_wav(&wav(1, 1, 16, &pcm16(&cycle), &[])).unwrap();
+
+    assert_eq!(table.frame_count(), 1);
+    for (sample, expected) in play(table).iter().zip(&cycle) {
+        assert!((sample - expected).abs() < 1e-3);
+    }
+}
+
+#[test]
+fn test_loads_every_sample_format() {
+    let cycle = sine(256);
+    let pcm8: Vec<u8> = cycle.iter().map(|&s| (s * 127.0 + 128.0) as u8).collect();
+    let pcm24: Vec<u8> = cycle
+        .iter()
+        .flat_map(|&s| {
+            let [_, a, b, c] = ((s * 8388607.0) as i32 * 256).to_le_bytes();
+            [a, b, c]
+        })
+        .collect();
+    let pcm32: Vec<u8> = cycle
+        .iter()
+        .flat_map(|&s| ((s as f64 * 2147483647.0) as i32).to_le_bytes())
+        .collect();
+    let float64: Vec<u8> = cycle
+        .iter()
+        .flat_map(|&s| (s as f64).to_le_bytes())
+        .collect();
+
+    for (format, bits, data, tolerance) in [
+        (1, 8, pcm8, 0.02),
+        (1, 24, pcm24, 1e-4),
+        (1, 32, pcm32, 1e-4),
+        (3, 32, float32(&cycle), 1e-4),
+        (3, 64, float64, 1e-4),
+    ] {
+        let table = Wavetable::from_wav(&wav(format, 1, bits, &data, &[])).unwrap();
+        for (sample, expected) in play(table).iter().zip(&cycle) {
+            assert!((sample - expected).abs() < tolerance, "{format} {bits}");
+        }
+    }
+}
+
+#[test]
+fn test_mixes_channels_down() {
+    // left holds the cycle and right silence
+    let cycle = sine(256);
+    let stereo: Vec<f32> = cycle.iter().flat_map(|&s| [s, 0.0]).collect();
+    let table = Wavetable::from_wav(&wav(3, 2, 32, &float32(&stereo), &[])).unwrap();
+
+    for (sample, expected) in play(table).iter().zip(&cycle) {
+        assert!((sample - 0.5 * expected).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn test_reads_frame_size_from_clm_chunk() {
+    let frames: Vec<f32> = (0..8).flat_map(|_| sine(256)).collect();
+    let clm: &[u8] = b"<!>256 10000000 wavetable (www.xferrecords.com)";
+    let table = Wavetable::from_wav(&wav(3, 1, 32, &float32(&frames), &[(b"clm ", clm)])).unwrap();
+
+    assert_eq!(table.frame_count(), 8);
+}
+
+#[test]
+fn test_splits_long_files_into_2048_sample_frames() {
+    let frames: Vec<f32> = (0..4).flat_map(|_| sine(2048)).collect();
+    let table = Wavetable::from_wav(&wav(3, 1, 32, &float32(&frames), &[])).unwrap();
+
+    assert_eq!(table.frame_count(), 4);
+}
+
+#[test]
+fn test_explicit_frame_size() {
+    let frames: Vec<f32> = (0..4).flat_map(|_| sine(600)).collect();
+    let bytes = wav(3, 1, 32, &float32(&frames), &[]);
+
+    assert_eq!(Wavetable::from_wav(&bytes).unwrap().frame_count(), 1);
+    assert_eq!(
+        Wavetable::from_wav_frames(&bytes, 600)
+            .unwrap()
+            .frame_count(),
+        4
+    );
+    assert!(matches!(
+        Wavetable::from_wav_frames(&bytes, 700),
+        Err(WavError::FrameSize {
+            samples: 2400,
+            frame_size: 700
+        })
+    ));
+}
+
+#[test]
+fn test_extensible_format() {
+    let cycle = sine(256);
+    let mut bytes = wav(0xFFFE, 1, 32, &float32(&cycle), &[]);
+
+    // grow the fmt chunk with the extension, whose GUID starts with the
+    // float format tag
+    let mut extension = vec![22, 0, 32, 0, 4, 0, 0, 0, 3, 0];
+    extension.extend([0; 14]);
+    let fmt_end = 12 + 8 + 16;
+    bytes.splice(fmt_end..fmt_end, extension);
+    bytes[16..20].copy_from_slice(&40u32.to_le_bytes());
+    let riff_size = bytes.len() as u32 - 8;
+    bytes[4..8].copy_from_slice(&riff_size.to_le_bytes());
+
+    let table = Wavetable::from_wav(&bytes).unwrap();
+    for (sample, expected) in play(table).iter().zip(&cycle) {
+        assert!((sample - expected).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn test_rejects_invalid_files() {
+    let cycle = float32(&sine(256));
+
+    assert!(matches!(
+        Wavetable::from_wav(b"not a wav file"),
+        Err(WavError::NotWav)
+    ));
+    assert!(matches!(
+        Wavetable::from_wav(&wav(2, 1, 16, &cycle, &[])),
+        Err(WavError::Unsupported {
+            format: 2,
+            bits: 16
+        })
+    ));
+    assert!(matches!(
+        Wavetable::from_wav(&wav(3, 1, 32, &[], &[])),
+        Err(WavError::Empty)
+    ));
+
+    let mut truncated = wav(3, 1, 32, &cycle, &[]);
+    truncated.truncate(truncated.len() - 10);
+    assert!(matches!(
+        Wavetable::from_wav(&truncated),
+        Err(WavError::Malformed(_))
+    ));
+
+    // a chunk size of u32::MAX, which overflows on 32-bit targets
+    let mut oversized = wav(3, 1, 32, &cycle, &[]);
+    oversized[16..20].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(matches!(
+        Wavetable::from_wav(&oversized),
+        Err(WavError::Malformed(_))
+    ));
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn test_open_reports_missing_file() {
+    let err = Wavetable::open_wav("does/not/exist.wav").err().unwrap();
+    assert!(matches!(err, WavError::Io(_)));
+    assert!(err.to_string().starts_with("failed to read WAV file"));
+}